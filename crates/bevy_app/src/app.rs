@@ -1,6 +1,9 @@
-use crate::app_builder::AppBuilder;
+use crate::{
+    app_builder::AppBuilder,
+    sub_app::{AppLabel, SubApp},
+};
 use bevy_ecs::{
-    schedule::{Schedule, Stage},
+    schedule::{Schedule, ScheduleGraph, Stage},
     world::World,
 };
 #[cfg(feature = "trace")]
@@ -33,6 +36,8 @@ pub struct App {
     pub world: World,
     pub runner: Box<dyn Fn(App)>,
     pub schedule: Schedule,
+    /// Sub-apps, updated in insertion order after this app's schedule has run.
+    pub sub_apps: Vec<SubApp>,
 }
 
 impl Default for App {
@@ -41,6 +46,7 @@ impl Default for App {
             world: Default::default(),
             schedule: Default::default(),
             runner: Box::new(run_once),
+            sub_apps: Vec::new(),
         }
     }
 }
@@ -60,6 +66,9 @@ impl App {
         #[cfg(feature = "trace")]
         let _bevy_frame_update_guard = bevy_frame_update_span.enter();
         self.schedule.run(&mut self.world);
+        for sub_app in self.sub_apps.iter_mut() {
+            sub_app.update(&mut self.world);
+        }
     }
 
     pub fn run(mut self) {
@@ -71,6 +80,57 @@ impl App {
         let runner = std::mem::replace(&mut self.runner, Box::new(run_once));
         (runner)(self);
     }
+
+    /// Adds a [`SubApp`] with its own world and schedule. After each update of this app, `extract`
+    /// is called with this app's world and the sub-app, then the sub-app is updated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a sub-app with the same label already exists.
+    pub fn add_sub_app(
+        &mut self,
+        label: impl AppLabel,
+        app: App,
+        extract: impl Fn(&mut World, &mut App) + 'static,
+    ) {
+        let sub_app = SubApp::new(label, app, extract);
+        if self.get_sub_app(&*sub_app.label).is_some() {
+            panic!("Sub-app already exists: {:?}.", sub_app.label);
+        }
+        self.sub_apps.push(sub_app);
+    }
+
+    pub fn get_sub_app(&self, label: &dyn AppLabel) -> Option<&App> {
+        self.sub_apps
+            .iter()
+            .find(|sub_app| &*sub_app.label == label)
+            .map(|sub_app| &sub_app.app)
+    }
+
+    pub fn get_sub_app_mut(&mut self, label: &dyn AppLabel) -> Option<&mut App> {
+        self.sub_apps
+            .iter_mut()
+            .find(|sub_app| &*sub_app.label == label)
+            .map(|sub_app| &mut sub_app.app)
+    }
+
+    /// Describes the app's schedule: its stages, systems, labels, ordering dependencies and the
+    /// data each system accesses. Use [`ScheduleGraph::to_dot`] or [`ScheduleGraph::to_json`] to
+    /// export it for visualization.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// fn hello_world_system() {}
+    ///
+    /// let mut builder = App::build();
+    /// builder.add_system(hello_world_system.system());
+    /// let dot = builder.app.schedule_graph().to_dot();
+    /// assert!(dot.contains("hello_world_system"));
+    /// ```
+    pub fn schedule_graph(&mut self) -> ScheduleGraph {
+        self.schedule.graph(&mut self.world)
+    }
 }
 
 /// An event that indicates the app should exit. This will fully exit the app process.
@@ -1,19 +1,21 @@
 use crate::{
     app::{App, AppExit},
     plugin::Plugin,
+    sub_app::AppLabel,
     CoreStage, PluginGroup, PluginGroupBuilder, StartupStage,
 };
 use bevy_ecs::{
     component::{Component, ComponentDescriptor},
-    event::Events,
+    event::{EventRetention, Events},
     schedule::{
-        RunOnce, Schedule, Stage, StageLabel, State, SystemDescriptor, SystemSet, SystemStage,
+        computed_state_driver, state_driver, sub_state_driver, ComputedStates, NextState, RunOnce,
+        Schedule, Stage, StageLabel, State, StateTransitionSchedules, States, SubStates,
+        SystemDescriptor, SystemSet, SystemStage,
     },
-    system::{IntoExclusiveSystem, IntoSystem},
-    world::{FromWorld, World},
+    system::{IntoExclusiveSystem, IntoSystem, SystemErrorHandler},
+    world::{FromWorld, Mut, World},
 };
 use bevy_utils::tracing::debug;
-use std::{fmt::Debug, hash::Hash};
 
 /// Configure [App]s using the builder pattern
 pub struct AppBuilder {
@@ -138,6 +140,27 @@ impl AppBuilder {
         self
     }
 
+    /// Sets the handler of errors returned by systems, for every stage that does not have its own
+    /// handler. Errors are logged if no handler is set.
+    pub fn set_error_handler(&mut self, handler: SystemErrorHandler) -> &mut Self {
+        self.insert_resource(handler)
+    }
+
+    /// Sets the handler of errors returned by the systems of the [SystemStage] with the given
+    /// `label`, overriding the handler set with [AppBuilder::set_error_handler].
+    ///
+    /// # Panics
+    /// Panics if the stage does not exist or is not a [SystemStage].
+    pub fn set_stage_error_handler(
+        &mut self,
+        label: impl StageLabel,
+        handler: SystemErrorHandler,
+    ) -> &mut Self {
+        self.stage(label, |stage: &mut SystemStage| {
+            stage.set_error_handler(handler)
+        })
+    }
+
     pub fn add_system(&mut self, system: impl Into<SystemDescriptor>) -> &mut Self {
         self.add_system_to_stage(CoreStage::Update, system)
     }
@@ -184,28 +207,104 @@ impl AppBuilder {
     }
 
     /// Adds a new [State] with the given `initial` value.
-    /// This inserts a new `State<T>` resource and adds a new "driver" to [CoreStage::Update].
-    /// Each stage that uses `State<T>` for system run criteria needs a driver. If you need to use
-    /// your state in a different stage, consider using [Self::add_state_to_stage] or manually
-    /// adding [State::get_driver] to additional stages you need it in.
-    pub fn add_state<T>(&mut self, initial: T) -> &mut Self
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        self.add_state_to_stage(CoreStage::Update, initial)
+    /// This inserts the `State<T>` and [NextState] resources and adds the state's driver to
+    /// [CoreStage::StateTransition], where transitions are applied and the initial state is
+    /// entered. Systems in any stage can then use [in_state](bevy_ecs::schedule::in_state) as their
+    /// run criteria.
+    pub fn add_state<T: States>(&mut self, initial: T) -> &mut Self {
+        self.insert_resource(State::new(initial))
+            .init_resource::<NextState<T>>()
+            .init_resource::<StateTransitionSchedules<T>>()
+            .add_system_to_stage(CoreStage::StateTransition, state_driver::<T>())
     }
 
-    /// Adds a new [State] with the given `initial` value.
-    /// This inserts a new `State<T>` resource and adds a new "driver" to the given stage.
-    /// Each stage that uses `State<T>` for system run criteria needs a driver. If you need to use
-    /// your state in more than one stage, consider manually adding [State::get_driver] to the
-    /// stages you need it in.
-    pub fn add_state_to_stage<T>(&mut self, stage: impl StageLabel, initial: T) -> &mut Self
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        self.insert_resource(State::new(initial))
-            .add_system_set_to_stage(stage, State::<T>::get_driver())
+    /// Adds a [ComputedStates] state, derived from its source state, which must also be added to
+    /// the app.
+    pub fn add_computed_state<S: ComputedStates>(&mut self) -> &mut Self {
+        self.init_resource::<StateTransitionSchedules<S>>()
+            .add_system_to_stage(CoreStage::StateTransition, computed_state_driver::<S>())
+    }
+
+    /// Adds a [SubStates] state, which exists while its source state allows it. The source state
+    /// must also be added to the app.
+    pub fn add_sub_state<S: SubStates>(&mut self) -> &mut Self {
+        self.init_resource::<NextState<S>>()
+            .init_resource::<StateTransitionSchedules<S>>()
+            .add_system_to_stage(CoreStage::StateTransition, sub_state_driver::<S>())
+    }
+
+    /// Adds a system to the stage run when entering `state`, see [StateTransitionSchedules].
+    pub fn add_system_on_enter<T: States>(
+        &mut self,
+        state: T,
+        system: impl Into<SystemDescriptor>,
+    ) -> &mut Self {
+        self.state_transition_schedules::<T>()
+            .on_enter(state)
+            .add_system(system);
+        self
+    }
+
+    /// Adds a system set to the stage run when entering `state`, see [StateTransitionSchedules].
+    pub fn add_system_set_on_enter<T: States>(
+        &mut self,
+        state: T,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        self.state_transition_schedules::<T>()
+            .on_enter(state)
+            .add_system_set(system_set);
+        self
+    }
+
+    /// Adds a system to the stage run when exiting `state`, see [StateTransitionSchedules].
+    pub fn add_system_on_exit<T: States>(
+        &mut self,
+        state: T,
+        system: impl Into<SystemDescriptor>,
+    ) -> &mut Self {
+        self.state_transition_schedules::<T>()
+            .on_exit(state)
+            .add_system(system);
+        self
+    }
+
+    /// Adds a system set to the stage run when exiting `state`, see [StateTransitionSchedules].
+    pub fn add_system_set_on_exit<T: States>(
+        &mut self,
+        state: T,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        self.state_transition_schedules::<T>()
+            .on_exit(state)
+            .add_system_set(system_set);
+        self
+    }
+
+    /// Adds a system to the stage run when changing from `from` to `to`, see
+    /// [StateTransitionSchedules].
+    pub fn add_system_on_transition<T: States>(
+        &mut self,
+        from: T,
+        to: T,
+        system: impl Into<SystemDescriptor>,
+    ) -> &mut Self {
+        self.state_transition_schedules::<T>()
+            .on_transition(from, to)
+            .add_system(system);
+        self
+    }
+
+    fn state_transition_schedules<T: States>(&mut self) -> Mut<StateTransitionSchedules<T>> {
+        self.app
+            .world
+            .get_resource_mut::<StateTransitionSchedules<T>>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "state '{}' has not been added to the app",
+                    std::any::type_name::<T>()
+                )
+            })
     }
 
     pub fn add_default_stages(&mut self) -> &mut Self {
@@ -219,6 +318,7 @@ impl AppBuilder {
                     .with_stage(StartupStage::PostStartup, SystemStage::parallel()),
             )
             .add_stage(CoreStage::PreUpdate, SystemStage::parallel())
+            .add_stage(CoreStage::StateTransition, SystemStage::parallel())
             .add_stage(CoreStage::Update, SystemStage::parallel())
             .add_stage(CoreStage::PostUpdate, SystemStage::parallel())
             .add_stage(CoreStage::Last, SystemStage::parallel())
@@ -232,10 +332,56 @@ impl AppBuilder {
     where
         T: Component,
     {
-        self.insert_resource(Events::<T>::default())
+        self.add_event_with_retention::<T>(EventRetention::default())
+    }
+
+    /// Like [`Self::add_event`], but with the given [`EventRetention`] policy, e.g. to keep events
+    /// until every [`EventReader`](bevy_ecs::event::EventReader) has read them.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::event::EventRetention;
+    /// struct Jump;
+    ///
+    /// App::build().add_event_with_retention::<Jump>(EventRetention::UntilConsumed);
+    /// ```
+    pub fn add_event_with_retention<T>(&mut self, retention: EventRetention) -> &mut Self
+    where
+        T: Component,
+    {
+        self.insert_resource(Events::<T>::with_retention(retention))
             .add_system_to_stage(CoreStage::First, Events::<T>::update_system.system())
     }
 
+    /// Makes `R` required by `T`, so that adding `T` to an entity also inserts `R::default()` if
+    /// the entity has no `R`. See [`World::register_required_components`].
+    pub fn register_required_components<T, R>(&mut self) -> &mut Self
+    where
+        T: Component,
+        R: Component + Default,
+    {
+        self.app.world.register_required_components::<T, R>();
+        self
+    }
+
+    /// Allows entities with a `T` component to be captured by [`World::snapshot`].
+    pub fn register_snapshot_component<T>(&mut self) -> &mut Self
+    where
+        T: Component + Clone,
+    {
+        self.app.world.register_snapshot_component::<T>();
+        self
+    }
+
+    /// Makes [`World::snapshot`] capture the resource `T`.
+    pub fn register_snapshot_resource<T>(&mut self) -> &mut Self
+    where
+        T: Component + Clone,
+    {
+        self.app.world.register_snapshot_resource::<T>();
+        self
+    }
+
     /// Inserts a resource to the current [App] and overwrites any resource previously added of the
     /// same type.
     pub fn insert_resource<T>(&mut self, resource: T) -> &mut Self
@@ -285,6 +431,46 @@ impl AppBuilder {
         self
     }
 
+    /// Adds a sub-app with its own world and schedule; see [`App::add_sub_app`].
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Debug, Clone, PartialEq, Eq, Hash, AppLabel)]
+    /// struct RenderApp;
+    ///
+    /// struct Frame(u32);
+    ///
+    /// fn count_frames(mut frame: ResMut<Frame>) {
+    ///     frame.0 += 1;
+    /// }
+    ///
+    /// let mut render_app = App::default();
+    /// render_app.world.insert_resource(0u32);
+    /// render_app.schedule.add_stage("render", SystemStage::single_threaded());
+    ///
+    /// let mut builder = App::build();
+    /// builder
+    ///     .insert_resource(Frame(0))
+    ///     .add_system(count_frames.system())
+    ///     .add_sub_app(RenderApp, render_app, |world, render_app| {
+    ///         let frame = world.get_resource::<Frame>().unwrap().0;
+    ///         render_app.world.insert_resource(frame);
+    ///     });
+    /// builder.app.update();
+    /// let render_app = builder.app.get_sub_app(&RenderApp).unwrap();
+    /// assert_eq!(*render_app.world.get_resource::<u32>().unwrap(), 1);
+    /// ```
+    pub fn add_sub_app(
+        &mut self,
+        label: impl AppLabel,
+        app: App,
+        extract: impl Fn(&mut World, &mut App) + 'static,
+    ) -> &mut Self {
+        self.app.add_sub_app(label, app, extract);
+        self
+    }
+
     pub fn add_plugin<T>(&mut self, plugin: T) -> &mut Self
     where
         T: Plugin,
@@ -335,4 +521,44 @@ impl AppBuilder {
         }
         self
     }
+
+    /// Registers a method of `T` that can be called by name through the
+    /// [TypeRegistry](bevy_reflect::TypeRegistry). `T` must already be registered.
+    ///
+    /// See [TypeRegistry::register_method](bevy_reflect::TypeRegistry::register_method)
+    #[cfg(feature = "bevy_reflect")]
+    pub fn register_method<T: bevy_reflect::Reflect, Params>(
+        &mut self,
+        name: &str,
+        method: impl bevy_reflect::IntoReflectMethod<T, Params>,
+    ) -> &mut Self {
+        {
+            let registry = self
+                .world_mut()
+                .get_resource_mut::<bevy_reflect::TypeRegistryArc>()
+                .unwrap();
+            registry.write().register_method(name, method);
+        }
+        self
+    }
+
+    /// Registers a function that can be called by name through the
+    /// [TypeRegistry](bevy_reflect::TypeRegistry).
+    ///
+    /// See [TypeRegistry::register_function](bevy_reflect::TypeRegistry::register_function)
+    #[cfg(feature = "bevy_reflect")]
+    pub fn register_function<Params>(
+        &mut self,
+        name: &str,
+        function: impl bevy_reflect::IntoReflectFunction<Params>,
+    ) -> &mut Self {
+        {
+            let registry = self
+                .world_mut()
+                .get_resource_mut::<bevy_reflect::TypeRegistryArc>()
+                .unwrap();
+            registry.write().register_function(name, function);
+        }
+        self
+    }
 }
@@ -3,22 +3,24 @@ mod app_builder;
 mod plugin;
 mod plugin_group;
 mod schedule_runner;
+mod sub_app;
 
 #[cfg(feature = "bevy_ci_testing")]
 mod ci_testing;
 
 pub use app::*;
 pub use app_builder::*;
-pub use bevy_derive::DynamicPlugin;
+pub use bevy_derive::{AppLabel, DynamicPlugin};
 pub use bevy_ecs::event::*;
 pub use plugin::*;
 pub use plugin_group::*;
 pub use schedule_runner::*;
+pub use sub_app::*;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        app::App, app_builder::AppBuilder, CoreStage, DynamicPlugin, Plugin, PluginGroup,
+        app::App, app_builder::AppBuilder, AppLabel, CoreStage, DynamicPlugin, Plugin, PluginGroup,
         StartupStage,
     };
 }
@@ -34,6 +36,8 @@ pub enum CoreStage {
     First,
     /// Name of app stage responsible for performing setup before an update. Runs before UPDATE.
     PreUpdate,
+    /// Name of app stage where state transitions are applied, after PREUPDATE and before UPDATE.
+    StateTransition,
     /// Name of app stage responsible for doing most app logic. Systems should be registered here
     /// by default.
     Update,
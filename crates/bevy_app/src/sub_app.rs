@@ -0,0 +1,233 @@
+use crate::app::App;
+use bevy_ecs::{schedule::DynHash, world::World};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+/// A label identifying a [`SubApp`]. Can be derived with `#[derive(AppLabel)]`.
+pub trait AppLabel: DynHash + Debug + Send + Sync + 'static {
+    #[doc(hidden)]
+    fn dyn_clone(&self) -> Box<dyn AppLabel>;
+}
+pub(crate) type BoxedAppLabel = Box<dyn AppLabel>;
+
+impl PartialEq for dyn AppLabel {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other.as_dyn_eq())
+    }
+}
+
+impl Eq for dyn AppLabel {}
+
+impl Hash for dyn AppLabel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dyn_hash(state);
+    }
+}
+
+impl Clone for Box<dyn AppLabel> {
+    fn clone(&self) -> Self {
+        self.dyn_clone()
+    }
+}
+
+impl AppLabel for Cow<'static, str> {
+    fn dyn_clone(&self) -> Box<dyn AppLabel> {
+        Box::new(self.clone())
+    }
+}
+
+impl AppLabel for &'static str {
+    fn dyn_clone(&self) -> Box<dyn AppLabel> {
+        Box::new(<&str>::clone(self))
+    }
+}
+
+/// An [`App`] with its own [`World`] and [`Schedule`](bevy_ecs::schedule::Schedule), updated
+/// after each update of the app that owns it.
+///
+/// Before the sub-app's schedule runs, its extraction function is called with the parent app's
+/// world and the sub-app, to copy over whatever data the sub-app needs. This allows e.g. running
+/// rendering against a separate world from the simulation.
+pub struct SubApp {
+    pub(crate) label: BoxedAppLabel,
+    pub app: App,
+    extract: Box<dyn Fn(&mut World, &mut App)>,
+}
+
+impl SubApp {
+    pub fn new(
+        label: impl AppLabel,
+        app: App,
+        extract: impl Fn(&mut World, &mut App) + 'static,
+    ) -> Self {
+        Self {
+            label: Box::new(label),
+            app,
+            extract: Box::new(extract),
+        }
+    }
+
+    pub fn label(&self) -> &dyn AppLabel {
+        &*self.label
+    }
+
+    /// Runs the extraction function, then updates the sub-app.
+    pub fn update(&mut self, parent: &mut World) {
+        (self.extract)(parent, &mut self.app);
+        self.app.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use bevy_ecs::{
+        schedule::SystemStage,
+        system::{IntoSystem, Res, ResMut},
+    };
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Order(Arc<Mutex<Vec<&'static str>>>);
+
+    impl Order {
+        fn push(&self, name: &'static str) {
+            self.0.lock().unwrap().push(name);
+        }
+
+        fn take(&self) -> Vec<&'static str> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    fn recording_app(order: &Order, name: &'static str) -> App {
+        let mut app = App::default();
+        app.world.insert_resource(order.clone());
+        app.schedule.add_stage(
+            "update",
+            SystemStage::parallel()
+                .with_system((move |order: Res<Order>| order.push(name)).system()),
+        );
+        app
+    }
+
+    #[test]
+    fn sub_apps_run_after_parent_in_order() {
+        let order = Order::default();
+        let mut app = recording_app(&order, "parent");
+        let first = recording_app(&order, "first");
+        app.add_sub_app("first", first, |parent, _| {
+            parent
+                .get_resource::<Order>()
+                .unwrap()
+                .push("extract first")
+        });
+        let second = recording_app(&order, "second");
+        app.add_sub_app("second", second, |parent, _| {
+            parent
+                .get_resource::<Order>()
+                .unwrap()
+                .push("extract second")
+        });
+
+        app.update();
+        assert_eq!(
+            order.take(),
+            vec![
+                "parent",
+                "extract first",
+                "first",
+                "extract second",
+                "second"
+            ]
+        );
+        app.update();
+        assert_eq!(order.take().len(), 5);
+    }
+
+    #[test]
+    fn get_sub_app() {
+        let mut app = App::default();
+        app.add_sub_app("first", App::default(), |_, _| {});
+        app.add_sub_app("second", App::default(), |_, _| {});
+        assert!(app.get_sub_app(&"first").is_some());
+        assert!(app.get_sub_app(&"missing").is_none());
+        assert!(app.get_sub_app_mut(&"missing").is_none());
+
+        app.get_sub_app_mut(&"second")
+            .unwrap()
+            .world
+            .insert_resource(1u32);
+        assert_eq!(
+            app.get_sub_app(&"second")
+                .unwrap()
+                .world
+                .get_resource::<u32>(),
+            Some(&1)
+        );
+        assert!(app
+            .get_sub_app(&"first")
+            .unwrap()
+            .world
+            .get_resource::<u32>()
+            .is_none());
+        assert!(app.world.get_resource::<u32>().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_sub_app_panics() {
+        let mut app = App::default();
+        app.add_sub_app("first", App::default(), |_, _| {});
+        app.add_sub_app("first", App::default(), |_, _| {});
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Counter(u32);
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Extracted(Vec<u32>);
+
+    #[test]
+    fn extract_syncs_parent_world_into_sub_app() {
+        let mut app = App::default();
+        app.world.insert_resource(Counter(0));
+        app.schedule.add_stage(
+            "update",
+            SystemStage::parallel()
+                .with_system((|mut counter: ResMut<Counter>| counter.0 += 1).system()),
+        );
+
+        let mut sub_app = App::default();
+        sub_app.world.insert_resource(Extracted(Vec::new()));
+        sub_app.schedule.add_stage(
+            "update",
+            SystemStage::parallel().with_system(
+                (|counter: Res<Counter>, mut extracted: ResMut<Extracted>| {
+                    extracted.0.push(counter.0)
+                })
+                .system(),
+            ),
+        );
+        // Extraction has mutable access to the parent world, so it can also write back to it.
+        app.add_sub_app("sub", sub_app, |parent, sub_app| {
+            let counter = parent.remove_resource::<Counter>().unwrap();
+            parent.insert_resource(Counter(counter.0 * 10));
+            sub_app.world.insert_resource(counter);
+        });
+
+        app.update();
+        app.update();
+        assert_eq!(app.world.get_resource::<Counter>(), Some(&Counter(110)));
+        assert_eq!(
+            app.get_sub_app(&"sub")
+                .unwrap()
+                .world
+                .get_resource::<Extracted>(),
+            Some(&Extracted(vec![1, 11]))
+        );
+    }
+}
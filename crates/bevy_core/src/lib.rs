@@ -56,6 +56,13 @@ impl Plugin for CorePlugin {
             .register_type::<Labels>()
             .register_type::<Range<f32>>()
             .register_type::<Timer>()
+            .register_method("reset", Timer::reset)
+            .register_method("pause", Timer::pause)
+            .register_method("unpause", Timer::unpause)
+            .register_method("set_duration", Timer::set_duration)
+            .register_method("set_repeating", Timer::set_repeating)
+            .register_method("finished", Timer::finished)
+            .register_method("percent", Timer::percent)
             // time system is added as an "exclusive system" to ensure it runs before other systems
             // in CoreStage::First
             .add_system_to_stage(
@@ -1,4 +1,4 @@
-use bevy_ecs::reflect::ReflectComponent;
+use bevy_ecs::{index::IndexedComponent, reflect::ReflectComponent};
 use bevy_reflect::Reflect;
 use bevy_utils::AHasher;
 use std::{
@@ -90,6 +90,16 @@ impl Ord for Name {
     }
 }
 
+/// Allows looking up entities by name with an [`Index<Name>`](bevy_ecs::index::Index).
+impl IndexedComponent for Name {
+    type Key = Name;
+
+    #[inline]
+    fn index_key(&self) -> Name {
+        self.clone()
+    }
+}
+
 impl Deref for Name {
     type Target = Cow<'static, str>;
 
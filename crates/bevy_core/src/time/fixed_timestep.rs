@@ -168,6 +168,10 @@ impl System for FixedTimestep {
         self.internal_system.is_send()
     }
 
+    fn reserves_entities(&self) -> bool {
+        self.internal_system.reserves_entities()
+    }
+
     unsafe fn run_unsafe(&mut self, _input: (), world: &World) -> ShouldRun {
         // SAFE: this system inherits the internal system's component access and archetype component
         // access, which means the caller has ensured running the internal system is safe
@@ -0,0 +1,21 @@
+use crate::modules::{get_modules, get_path};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput};
+
+pub fn derive_app_label(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let modules = get_modules(&ast.attrs);
+    let bevy_app_path = get_path(&modules.bevy_app);
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let struct_name = &ast.ident;
+
+    TokenStream::from(quote! {
+        impl #impl_generics #bevy_app_path::AppLabel for #struct_name #ty_generics #where_clause {
+            fn dyn_clone(&self) -> Box<dyn #bevy_app_path::AppLabel> {
+                Box::new(Clone::clone(self))
+            }
+        }
+    })
+}
@@ -1,5 +1,6 @@
 extern crate proc_macro;
 
+mod app_label;
 mod app_plugin;
 mod bevy_main;
 mod bytes;
@@ -51,6 +52,13 @@ pub fn derive_dynamic_plugin(input: TokenStream) -> TokenStream {
     app_plugin::derive_dynamic_plugin(input)
 }
 
+/// Derives the AppLabel trait, used to identify sub-apps. The type must also implement `Clone`,
+/// `Debug`, `Eq` and `Hash`.
+#[proc_macro_derive(AppLabel)]
+pub fn derive_app_label(input: TokenStream) -> TokenStream {
+    app_label::derive_app_label(input)
+}
+
 #[proc_macro_attribute]
 pub fn bevy_main(attr: TokenStream, item: TokenStream) -> TokenStream {
     bevy_main::bevy_main(attr, item)
@@ -258,6 +258,18 @@ impl Archetype {
         }
     }
 
+    /// Replaces the entities of this archetype and their table rows.
+    ///
+    /// # Safety
+    /// `entities` must be stored at `table_rows` in this archetype's table, and their locations
+    /// must point to this archetype.
+    pub(crate) unsafe fn set_entities(&mut self, entities: &[Entity], table_rows: &[usize]) {
+        self.entities.clear();
+        self.entities.extend_from_slice(entities);
+        self.table_info.entity_rows.clear();
+        self.table_info.entity_rows.extend_from_slice(table_rows);
+    }
+
     pub fn reserve(&mut self, additional: usize) {
         self.entities.reserve(additional);
         self.table_info.entity_rows.reserve(additional);
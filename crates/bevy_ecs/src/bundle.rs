@@ -140,30 +140,61 @@ impl BundleInfo {
         // bundle_info.component_ids are also in "bundle order"
         let mut bundle_component = 0;
         bundle.get_components(|component_ptr| {
-            // SAFE: component_id was initialized by get_dynamic_bundle_info
-            let component_id = *self.component_ids.get_unchecked(bundle_component);
-            let component_status = bundle_status.get_unchecked(bundle_component);
-            match self.storage_types[bundle_component] {
-                StorageType::Table => {
-                    let column = table.get_column(component_id).unwrap();
-                    column.set_unchecked(table_row, component_ptr);
-                    let column_status = column.get_ticks_unchecked_mut(table_row);
-                    match component_status {
-                        ComponentStatus::Added => {
-                            *column_status = ComponentTicks::new(change_tick);
-                        }
-                        ComponentStatus::Mutated => {
-                            column_status.set_changed(change_tick);
-                        }
+            self.write_component(
+                sparse_sets,
+                entity,
+                table,
+                table_row,
+                bundle_component,
+                bundle_status,
+                component_ptr,
+                change_tick,
+            );
+            bundle_component += 1;
+        });
+    }
+
+    /// Writes the value of the bundle component at index `bundle_component`, taking ownership of
+    /// the value behind `component_ptr`.
+    ///
+    /// # Safety
+    /// table row must exist, entity must be valid, `bundle_component` must be in bounds and
+    /// `component_ptr` must point to a valid value of that component
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub(crate) unsafe fn write_component(
+        &self,
+        sparse_sets: &mut SparseSets,
+        entity: Entity,
+        table: &Table,
+        table_row: usize,
+        bundle_component: usize,
+        bundle_status: &[ComponentStatus],
+        component_ptr: *mut u8,
+        change_tick: u32,
+    ) {
+        // SAFE: component_id was initialized by get_dynamic_bundle_info
+        let component_id = *self.component_ids.get_unchecked(bundle_component);
+        let component_status = bundle_status.get_unchecked(bundle_component);
+        match self.storage_types[bundle_component] {
+            StorageType::Table => {
+                let column = table.get_column(component_id).unwrap();
+                column.set_unchecked(table_row, component_ptr);
+                let column_status = column.get_ticks_unchecked_mut(table_row);
+                match component_status {
+                    ComponentStatus::Added => {
+                        *column_status = ComponentTicks::new(change_tick);
+                    }
+                    ComponentStatus::Mutated => {
+                        column_status.set_changed(change_tick);
                     }
-                }
-                StorageType::SparseSet => {
-                    let sparse_set = sparse_sets.get_mut(component_id).unwrap();
-                    sparse_set.insert(entity, component_ptr, change_tick);
                 }
             }
-            bundle_component += 1;
-        });
+            StorageType::SparseSet => {
+                let sparse_set = sparse_sets.get_mut(component_id).unwrap();
+                sparse_set.insert(entity, component_ptr, change_tick);
+            }
+        }
     }
 
     #[inline]
@@ -186,6 +217,8 @@ impl BundleInfo {
 pub struct Bundles {
     bundle_infos: Vec<BundleInfo>,
     bundle_ids: HashMap<TypeId, BundleId>,
+    /// Bundles of components that are inserted by [`ComponentId`].
+    dynamic_bundle_ids: HashMap<Vec<ComponentId>, BundleId>,
 }
 
 impl Bundles {
@@ -215,6 +248,54 @@ impl Bundles {
         // SAFE: index either exists, or was initialized
         unsafe { self.bundle_infos.get_unchecked(id.0) }
     }
+
+    /// Returns the info of the bundle that contains the components with the given ids, in the
+    /// given order.
+    ///
+    /// # Panics
+    /// Panics if a component is not registered in `components` or if a component id is given more
+    /// than once.
+    pub(crate) fn init_dynamic_info<'a>(
+        &'a mut self,
+        components: &Components,
+        component_ids: &[ComponentId],
+    ) -> &'a BundleInfo {
+        let bundle_infos = &mut self.bundle_infos;
+        let id = self
+            .dynamic_bundle_ids
+            .entry(component_ids.to_vec())
+            .or_insert_with(|| {
+                let mut deduped = component_ids.to_vec();
+                deduped.sort();
+                deduped.dedup();
+                if deduped.len() != component_ids.len() {
+                    panic!(
+                        "Dynamic bundle {:?} has duplicate components",
+                        component_ids
+                    );
+                }
+                let storage_types = component_ids
+                    .iter()
+                    .map(|component_id| {
+                        components
+                            .get_info(*component_id)
+                            .unwrap_or_else(|| {
+                                panic!("Component {:?} is not registered", component_id)
+                            })
+                            .storage_type()
+                    })
+                    .collect();
+                let id = BundleId(bundle_infos.len());
+                bundle_infos.push(BundleInfo {
+                    id,
+                    component_ids: component_ids.to_vec(),
+                    storage_types,
+                });
+                id
+            });
+        // SAFE: index either exists, or was initialized
+        unsafe { self.bundle_infos.get_unchecked(id.0) }
+    }
 }
 
 fn initialize_bundle(
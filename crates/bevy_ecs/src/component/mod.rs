@@ -2,7 +2,7 @@ mod type_info;
 
 pub use type_info::*;
 
-use crate::storage::SparseSetIndex;
+use crate::{entity::Entity, storage::SparseSetIndex, world::World};
 use std::{
     alloc::Layout,
     any::{Any, TypeId},
@@ -49,6 +49,12 @@ pub struct ComponentInfo {
     layout: Layout,
     drop: unsafe fn(*mut u8),
     storage_type: StorageType,
+    hooks: ComponentHooks,
+    required: Vec<RequiredComponent>,
+    clone: Option<CloneFn>,
+    /// Keyed by the [`TypeId`] of the index they update.
+    index_hooks: Vec<(TypeId, ComponentHook)>,
+    world_local: bool,
 }
 
 impl ComponentInfo {
@@ -87,6 +93,40 @@ impl ComponentInfo {
         self.is_send_and_sync
     }
 
+    /// The lifecycle hooks that run when this component is added to or removed from an entity.
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
+
+    /// The components that are inserted along with this one, if the entity does not have them.
+    #[inline]
+    pub fn required_components(&self) -> &[RequiredComponent] {
+        &self.required
+    }
+
+    /// The function cloning values of this component into a
+    /// [`WorldSnapshot`](crate::world::WorldSnapshot), if it was registered for snapshots.
+    #[inline]
+    pub fn clone_fn(&self) -> Option<CloneFn> {
+        self.clone
+    }
+
+    /// The hooks removing entities from the [`ComponentIndex`](crate::index::ComponentIndex)es of
+    /// this component, which run after its `on_remove` hook.
+    #[inline]
+    pub fn index_hooks(&self) -> impl Iterator<Item = ComponentHook> + '_ {
+        self.index_hooks.iter().map(|(_, hook)| *hook)
+    }
+
+    /// Whether values of this component only make sense in the world they were created in, like
+    /// the bookkeeping of observers. Such components are dropped when an entity is moved to
+    /// another world.
+    #[inline]
+    pub fn is_world_local(&self) -> bool {
+        self.world_local
+    }
+
     fn new(id: ComponentId, descriptor: ComponentDescriptor) -> Self {
         ComponentInfo {
             id,
@@ -96,6 +136,11 @@ impl ComponentInfo {
             is_send_and_sync: descriptor.is_send_and_sync,
             drop: descriptor.drop,
             layout: descriptor.layout,
+            hooks: descriptor.hooks,
+            required: descriptor.required,
+            clone: descriptor.clone,
+            index_hooks: Vec::new(),
+            world_local: descriptor.world_local,
         }
     }
 }
@@ -135,6 +180,10 @@ pub struct ComponentDescriptor {
     type_id: Option<TypeId>,
     layout: Layout,
     drop: unsafe fn(*mut u8),
+    hooks: ComponentHooks,
+    required: Vec<RequiredComponent>,
+    clone: Option<CloneFn>,
+    world_local: bool,
 }
 
 impl ComponentDescriptor {
@@ -146,9 +195,89 @@ impl ComponentDescriptor {
             type_id: Some(TypeId::of::<T>()),
             layout: Layout::new::<T>(),
             drop: TypeInfo::drop_ptr::<T>,
+            hooks: ComponentHooks::default(),
+            required: Vec::new(),
+            clone: None,
+            world_local: false,
         }
     }
 
+    /// Creates a descriptor for a component that has no Rust type, for example one defined by a
+    /// script or a mod. Register it with [`World::register_component`] and access its values as
+    /// raw bytes with [`EntityMut::insert_by_id`](crate::world::EntityMut::insert_by_id),
+    /// [`EntityMut::get_by_id`](crate::world::EntityMut::get_by_id) and
+    /// [`EntityMut::remove_by_id`](crate::world::EntityMut::remove_by_id).
+    ///
+    /// `drop` is called on a value when it is removed from the world. Pass `None` if values do not
+    /// need to be dropped.
+    ///
+    /// # Safety
+    /// `drop` must be safe to call on a pointer to any value of this component, and values of this
+    /// component must be safe to send and share between threads.
+    pub unsafe fn new_with_layout(
+        name: impl Into<String>,
+        storage_type: StorageType,
+        layout: Layout,
+        drop: Option<unsafe fn(*mut u8)>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            storage_type,
+            is_send_and_sync: true,
+            type_id: None,
+            layout,
+            drop: drop.unwrap_or(drop_nothing),
+            hooks: ComponentHooks::default(),
+            required: Vec::new(),
+            clone: None,
+            world_local: false,
+        }
+    }
+
+    /// Sets the hook that runs when this component is added to an entity that did not have it.
+    pub fn on_add(mut self, hook: ComponentHook) -> Self {
+        self.hooks.on_add = Some(hook);
+        self
+    }
+
+    /// Sets the hook that runs whenever this component is inserted on an entity, including when it
+    /// replaces an existing value.
+    pub fn on_insert(mut self, hook: ComponentHook) -> Self {
+        self.hooks.on_insert = Some(hook);
+        self
+    }
+
+    /// Sets the hook that runs when this component is removed from an entity, either directly or
+    /// because the entity was despawned.
+    pub fn on_remove(mut self, hook: ComponentHook) -> Self {
+        self.hooks.on_remove = Some(hook);
+        self
+    }
+
+    /// Requires `R`: adding this component to an entity also inserts `R::default()`, unless the
+    /// entity already has an `R`. See [`Components::register_required`].
+    pub fn require<R: Component + Default>(mut self) -> Self {
+        RequiredComponent::of::<R>().add_to(&mut self.required);
+        self
+    }
+
+    /// Marks this component as world-local, see [`ComponentInfo::is_world_local`].
+    pub(crate) fn world_local(mut self) -> Self {
+        self.world_local = true;
+        self
+    }
+
+    /// Allows this component to be captured by [`World::snapshot`], using `clone` to copy its
+    /// values. See [`Components::register_snapshot`].
+    ///
+    /// # Safety
+    /// `clone` must write a valid clone of the value its first pointer points to into the
+    /// uninitialized memory its second pointer points to.
+    pub unsafe fn with_clone(mut self, clone: CloneFn) -> Self {
+        self.clone = Some(clone);
+        self
+    }
+
     #[inline]
     pub fn storage_type(&self) -> StorageType {
         self.storage_type
@@ -163,8 +292,15 @@ impl ComponentDescriptor {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    #[inline]
+    pub fn hooks(&self) -> &ComponentHooks {
+        &self.hooks
+    }
 }
 
+unsafe fn drop_nothing(_ptr: *mut u8) {}
+
 impl From<TypeInfo> for ComponentDescriptor {
     fn from(type_info: TypeInfo) -> Self {
         Self {
@@ -174,6 +310,142 @@ impl From<TypeInfo> for ComponentDescriptor {
             type_id: Some(type_info.type_id()),
             drop: type_info.drop(),
             layout: type_info.layout(),
+            hooks: ComponentHooks::default(),
+            required: Vec::new(),
+            clone: None,
+            world_local: false,
+        }
+    }
+}
+
+/// A function that runs when a component is added to, inserted on or removed from an entity. It
+/// receives the [`World`], the entity and the [`ComponentId`] of the component.
+pub type ComponentHook = fn(&mut World, Entity, ComponentId);
+
+/// A function that writes a clone of the component value behind its first pointer into the
+/// uninitialized memory behind its second pointer.
+pub type CloneFn = unsafe fn(*const u8, *mut u8);
+
+/// The lifecycle hooks of a component, registered with
+/// [`ComponentDescriptor::on_add`], [`ComponentDescriptor::on_insert`] and
+/// [`ComponentDescriptor::on_remove`].
+///
+/// Hooks run immediately, with full access to the [`World`], whenever a component is added or
+/// removed through [`World`], [`EntityMut`](crate::world::EntityMut) or
+/// [`Commands`](crate::system::Commands):
+/// * `on_add` runs after the component was added to an entity that did not have it.
+/// * `on_insert` runs after `on_add`, and also when an existing value is replaced.
+/// * `on_remove` runs before the component is removed, so the value can still be read.
+///
+/// Hooks may freely modify the world, but they must not despawn the entity they are called for.
+///
+/// ```
+/// use bevy_ecs::{
+///     component::{ComponentDescriptor, StorageType},
+///     prelude::*,
+/// };
+///
+/// struct Collider;
+/// #[derive(Default)]
+/// struct ColliderCount(usize);
+///
+/// let mut world = World::new();
+/// world.insert_resource(ColliderCount::default());
+/// world
+///     .register_component(
+///         ComponentDescriptor::new::<Collider>(StorageType::Table)
+///             .on_add(|world, _, _| world.get_resource_mut::<ColliderCount>().unwrap().0 += 1)
+///             .on_remove(|world, _, _| world.get_resource_mut::<ColliderCount>().unwrap().0 -= 1),
+///     )
+///     .unwrap();
+///
+/// let entity = world.spawn().insert(Collider).id();
+/// assert_eq!(world.get_resource::<ColliderCount>().unwrap().0, 1);
+/// world.despawn(entity);
+/// assert_eq!(world.get_resource::<ColliderCount>().unwrap().0, 0);
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ComponentHooks {
+    pub(crate) on_add: Option<ComponentHook>,
+    pub(crate) on_insert: Option<ComponentHook>,
+    pub(crate) on_remove: Option<ComponentHook>,
+}
+
+impl ComponentHooks {
+    #[inline]
+    pub fn on_add(&self) -> Option<ComponentHook> {
+        self.on_add
+    }
+
+    #[inline]
+    pub fn on_insert(&self) -> Option<ComponentHook> {
+        self.on_insert
+    }
+
+    #[inline]
+    pub fn on_remove(&self) -> Option<ComponentHook> {
+        self.on_remove
+    }
+}
+
+/// A component that is inserted with its default value whenever the component requiring it is
+/// added to an entity that does not have it yet.
+///
+/// Required components are inserted before the `on_add` hooks of the added components run, and
+/// can themselves require other components. Values inserted explicitly, e.g. by a bundle that
+/// contains both components, are kept.
+///
+/// ```
+/// use bevy_ecs::prelude::*;
+///
+/// struct Transform(f32);
+/// #[derive(Default)]
+/// struct GlobalTransform(f32);
+///
+/// let mut world = World::new();
+/// world.register_required_components::<Transform, GlobalTransform>();
+///
+/// let entity = world.spawn().insert(Transform(1.0)).id();
+/// assert!(world.get::<GlobalTransform>(entity).is_some());
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct RequiredComponent {
+    type_id: TypeId,
+    insert: ComponentHook,
+}
+
+impl RequiredComponent {
+    pub fn of<R: Component + Default>() -> Self {
+        fn insert<R: Component + Default>(world: &mut World, entity: Entity, _: ComponentId) {
+            let mut entity_mut = world.entity_mut(entity);
+            if !entity_mut.contains::<R>() {
+                entity_mut.insert(R::default());
+            }
+        }
+
+        Self {
+            type_id: TypeId::of::<R>(),
+            insert: insert::<R>,
+        }
+    }
+
+    /// The [`TypeId`] of the required component.
+    #[inline]
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    #[inline]
+    pub(crate) fn insert(&self) -> ComponentHook {
+        self.insert
+    }
+
+    fn add_to(self, required: &mut Vec<RequiredComponent>) {
+        if required
+            .iter()
+            .all(|existing| existing.type_id != self.type_id)
+        {
+            required.push(self);
         }
     }
 }
@@ -213,6 +485,109 @@ impl Components {
         Ok(ComponentId(index))
     }
 
+    /// Returns the id of the component that matches `info`, a component of another [`World`],
+    /// registering it if it does not exist yet. Components with a Rust type are matched by
+    /// [`TypeId`] and components without one by name. The hooks of `info` are copied unless it is
+    /// [world-local](ComponentInfo::is_world_local).
+    ///
+    /// # Panics
+    /// Panics if the matching component has a different layout.
+    pub(crate) fn init_matching(&mut self, info: &ComponentInfo) -> ComponentId {
+        let existing = match info.type_id {
+            Some(type_id) => self.get_id(type_id),
+            None => self
+                .components
+                .iter()
+                .find(|component| component.type_id.is_none() && component.name == info.name)
+                .map(|component| component.id),
+        };
+        if let Some(component_id) = existing {
+            let layout = self.components[component_id.0].layout;
+            assert_eq!(
+                layout, info.layout,
+                "Component {} has a different layout in each world",
+                info.name
+            );
+            return component_id;
+        }
+        self.add(ComponentDescriptor {
+            name: info.name.clone(),
+            storage_type: info.storage_type,
+            is_send_and_sync: info.is_send_and_sync,
+            type_id: info.type_id,
+            layout: info.layout,
+            drop: info.drop,
+            // the hooks of world-local components handle state that stays in the other world
+            hooks: if info.world_local {
+                ComponentHooks::default()
+            } else {
+                info.hooks
+            },
+            required: info.required.clone(),
+            clone: info.clone,
+            world_local: info.world_local,
+        })
+        .unwrap()
+    }
+
+    /// Makes `R` required by `T`, registering `T` if needed. Entities that already have a `T` are
+    /// not affected. See [`RequiredComponent`].
+    pub fn register_required<T: Component, R: Component + Default>(&mut self) {
+        let id = self.get_or_insert_id::<T>();
+        RequiredComponent::of::<R>().add_to(&mut self.components[id.0].required);
+    }
+
+    /// Sets the `on_insert` and `on_remove` hooks of an already registered component.
+    ///
+    /// # Panics
+    /// Panics if the component already has either hook.
+    pub(crate) fn set_insert_and_remove_hooks(
+        &mut self,
+        id: ComponentId,
+        on_insert: ComponentHook,
+        on_remove: ComponentHook,
+    ) {
+        let info = &mut self.components[id.0];
+        assert!(
+            info.hooks.on_insert.is_none() && info.hooks.on_remove.is_none(),
+            "{} already has on_insert or on_remove hooks",
+            info.name
+        );
+        info.hooks.on_insert = Some(on_insert);
+        info.hooks.on_remove = Some(on_remove);
+    }
+
+    /// Adds a hook removing entities from the index `I` of `T`, registering `T` if needed. Returns
+    /// `false`, without adding the hook, if `T` already has one for `I`.
+    pub(crate) fn register_index_hook<T: Component, I: 'static>(
+        &mut self,
+        hook: ComponentHook,
+    ) -> bool {
+        let id = self.get_or_insert_id::<T>();
+        let index = TypeId::of::<I>();
+        let index_hooks = &mut self.components[id.0].index_hooks;
+        if index_hooks
+            .iter()
+            .any(|(registered, _)| *registered == index)
+        {
+            return false;
+        }
+        index_hooks.push((index, hook));
+        true
+    }
+
+    /// Allows `T` to be captured by [`World::snapshot`], registering `T` if needed.
+    pub fn register_snapshot<T: Component + Clone>(&mut self) {
+        let id = self.get_or_insert_id::<T>();
+        self.components[id.0].clone = Some(TypeInfo::clone_ptr::<T>);
+    }
+
+    /// Allows the resource `T` to be captured by [`World::snapshot`], registering `T` if needed.
+    pub fn register_snapshot_resource<T: Component + Clone>(&mut self) {
+        let id = self.get_or_insert_resource_id::<T>();
+        self.components[id.0].clone = Some(TypeInfo::clone_ptr::<T>);
+    }
+
     #[inline]
     pub fn get_or_insert_id<T: Component>(&mut self) -> ComponentId {
         self.get_or_insert_with(TypeId::of::<T>(), TypeInfo::of::<T>)
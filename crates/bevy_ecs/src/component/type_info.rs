@@ -60,4 +60,8 @@ impl TypeInfo {
     pub(crate) unsafe fn drop_ptr<T>(x: *mut u8) {
         x.cast::<T>().drop_in_place()
     }
+
+    pub(crate) unsafe fn clone_ptr<T: Clone>(source: *const u8, destination: *mut u8) {
+        destination.cast::<T>().write((*source.cast::<T>()).clone())
+    }
 }
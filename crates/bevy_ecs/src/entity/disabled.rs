@@ -0,0 +1,29 @@
+/// Marks an entity as disabled.
+///
+/// Queries skip disabled entities unless they mention `Disabled` themselves, for example with
+/// `With<Disabled>` or `Option<&Disabled>`. Disabled entities keep all of their components, so
+/// enabling them again by removing `Disabled` restores them as they were. Direct access like
+/// [`World::get`](crate::world::World::get) still works on disabled entities.
+///
+/// Entities can be disabled and enabled with
+/// [`EntityCommands::disable`](crate::system::EntityCommands::disable) and
+/// [`EntityCommands::enable`](crate::system::EntityCommands::enable). `bevy_transform`'s
+/// `DisableRecursiveExt` does the same for an entity and all of its children.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::{entity::Disabled, prelude::*};
+/// struct Enemy;
+///
+/// let mut world = World::new();
+/// let enemy = world.spawn().insert(Enemy).id();
+/// world.spawn().insert_bundle((Enemy, Disabled));
+///
+/// let mut enemies = world.query_filtered::<Entity, With<Enemy>>();
+/// assert_eq!(enemies.iter(&world).collect::<Vec<_>>(), vec![enemy]);
+///
+/// let mut all_enemies = world.query_filtered::<Option<&Disabled>, With<Enemy>>();
+/// assert_eq!(all_enemies.iter(&world).count(), 2);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Disabled;
@@ -1,7 +1,9 @@
+mod disabled;
 mod map_entities;
 mod serde;
 
 pub use self::serde::*;
+pub use disabled::*;
 pub use map_entities::*;
 
 use crate::{archetype::ArchetypeId, storage::SparseSetIndex};
@@ -165,6 +167,24 @@ pub struct Entities {
     len: u32,
 }
 
+impl Clone for Entities {
+    fn clone(&self) -> Self {
+        Entities {
+            meta: self.meta.clone(),
+            pending: self.pending.clone(),
+            free_cursor: AtomicI64::new(self.free_cursor.load(Ordering::Relaxed)),
+            len: self.len,
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.meta.clone_from(&source.meta);
+        self.pending.clone_from(&source.pending);
+        *self.free_cursor.get_mut() = source.free_cursor.load(Ordering::Relaxed);
+        self.len = source.len;
+    }
+}
+
 impl Entities {
     /// Reserve entity IDs concurrently.
     ///
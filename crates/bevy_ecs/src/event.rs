@@ -2,12 +2,18 @@ use crate as bevy_ecs;
 use crate::{
     component::Component,
     system::{Local, Res, ResMut, SystemParam},
+    world::{FromWorld, World},
 };
-use bevy_utils::tracing::trace;
+use bevy_utils::tracing::{trace, warn};
 use std::{
+    collections::VecDeque,
     fmt::{self},
     hash::Hash,
     marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 /// An `EventId` uniquely identifies an event.
@@ -50,14 +56,30 @@ struct EventInstance<T> {
     pub event: T,
 }
 
-#[derive(Debug)]
-enum State {
-    A,
-    B,
+/// How long [`Events`] keeps the events sent to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRetention {
+    /// Events are dropped by the given number of [`Events::update`] calls after they were sent. The
+    /// default is `Frames(2)`: events sent during a frame are dropped at the end of the next one.
+    Frames(usize),
+    /// Events are kept until every registered reader has read them; see
+    /// [`Events::register_reader`]. [`EventReader`]s register themselves when their system is
+    /// initialized. Events are dropped in [`Events::update`], so a reader that never runs keeps
+    /// them alive indefinitely.
+    UntilConsumed,
+    /// Up to the given number of the most recent events are kept, regardless of
+    /// [`Events::update`]. Older events are dropped when new ones are sent, with a warning.
+    Bounded(usize),
+}
+
+impl Default for EventRetention {
+    fn default() -> Self {
+        EventRetention::Frames(2)
+    }
 }
 
 /// An event collection that represents the events that occurred within the last two
-/// [`Events::update`] calls.
+/// [`Events::update`] calls, by default.
 /// Events can be written to using an [`EventWriter`]
 /// and are typically cheaply read using an [`EventReader`].
 ///
@@ -73,7 +95,11 @@ enum State {
 /// Events will persist across a single frame boundary and so ordering of event producers and
 /// consumers is not critical (although poorly-planned ordering may cause accumulating lag).
 /// If events are not handled by the end of the frame after they are updated, they will be
-/// dropped silently.
+/// dropped, and readers that missed them log a warning the next time they read.
+///
+/// How long events are kept can be configured per event type with an [`EventRetention`] policy,
+/// e.g. for events read by systems that don't run every frame, such as systems behind a fixed
+/// timestep.
 ///
 /// # Example
 /// ```
@@ -104,13 +130,13 @@ enum State {
 ///
 /// # Details
 ///
-/// [Events] is implemented using a double buffer. Each call to [Events::update] swaps buffers and
-/// clears out the oldest buffer. [EventReader]s that read at least once per update will never drop
-/// events. [EventReader]s that read once within two updates might still receive some events.
+/// With the default retention policy, each call to [Events::update] drops the events sent before
+/// the previous call. [EventReader]s that read at least once per update will never drop events.
+/// [EventReader]s that read once within two updates might still receive some events.
 /// [EventReader]s that read after two updates are guaranteed to drop all events that occurred
 /// before those updates.
 ///
-/// The buffers in [Events] will grow indefinitely if [Events::update] is never called.
+/// The events in [Events] will grow indefinitely if [Events::update] is never called.
 ///
 /// An alternative call pattern would be to call [Events::update] manually across frames to control
 /// when events are cleared.
@@ -120,23 +146,36 @@ enum State {
 /// [`AppBuilder::add_event`]: https://docs.rs/bevy/*/bevy/app/struct.AppBuilder.html#method.add_event
 #[derive(Debug)]
 pub struct Events<T> {
-    events_a: Vec<EventInstance<T>>,
-    events_b: Vec<EventInstance<T>>,
-    a_start_event_count: usize,
-    b_start_event_count: usize,
+    events: VecDeque<EventInstance<T>>,
+    /// Id of the first event in `events`.
+    start_event_count: usize,
+    /// Events with a lower id were removed by [`Events::clear`] or [`Events::drain`], so readers
+    /// that never saw them did not lag behind.
+    cleared_event_count: usize,
     event_count: usize,
-    state: State,
+    /// Event count at each of the last updates, oldest first, used by [`EventRetention::Frames`].
+    update_event_counts: VecDeque<usize>,
+    /// Event count at the last update.
+    last_update_event_count: usize,
+    retention: EventRetention,
+    /// Event counts of the registered readers.
+    readers: Vec<Arc<AtomicUsize>>,
+    /// Whether an overflow of a [`EventRetention::Bounded`] queue was reported since the last update.
+    overflow_reported: bool,
 }
 
 impl<T> Default for Events<T> {
     fn default() -> Self {
         Events {
-            a_start_event_count: 0,
-            b_start_event_count: 0,
+            events: VecDeque::new(),
+            start_event_count: 0,
+            cleared_event_count: 0,
             event_count: 0,
-            events_a: Vec::new(),
-            events_b: Vec::new(),
-            state: State::A,
+            update_event_counts: VecDeque::new(),
+            last_update_event_count: 0,
+            retention: EventRetention::default(),
+            readers: Vec::new(),
+            overflow_reported: false,
         }
     }
 }
@@ -150,12 +189,27 @@ fn map_instance_event<T>(event_instance: &EventInstance<T>) -> &T {
 }
 
 /// Reads events of type `T` in order and tracks which events have already been read.
+///
+/// The reader is registered with the [`Events<T>`] resource when its system is initialized, so
+/// that [`EventRetention::UntilConsumed`] keeps events until it has read them.
 #[derive(SystemParam)]
 pub struct EventReader<'a, T: Component> {
-    last_event_count: Local<'a, (usize, PhantomData<T>)>,
+    reader: Local<'a, RegisteredEventReader<T>>,
     events: Res<'a, Events<T>>,
 }
 
+/// The [`ManualEventReader`] of an [`EventReader`], registered with [`Events::register_reader`].
+pub struct RegisteredEventReader<T>(ManualEventReader<T>);
+
+impl<T: Component> FromWorld for RegisteredEventReader<T> {
+    fn from_world(world: &mut World) -> Self {
+        Self(match world.get_resource_mut::<Events<T>>() {
+            Some(mut events) => events.register_reader(),
+            None => ManualEventReader::default(),
+        })
+    }
+}
+
 /// Sends events of type `T`.
 #[derive(SystemParam)]
 pub struct EventWriter<'a, T: Component> {
@@ -174,6 +228,8 @@ impl<'a, T: Component> EventWriter<'a, T> {
 
 pub struct ManualEventReader<T> {
     last_event_count: usize,
+    /// Shared with the [`Events`] this reader was registered with, if any.
+    registration: Option<Arc<AtomicUsize>>,
     _marker: PhantomData<T>,
 }
 
@@ -181,6 +237,7 @@ impl<T> Default for ManualEventReader<T> {
     fn default() -> Self {
         ManualEventReader {
             last_event_count: 0,
+            registration: None,
             _marker: Default::default(),
         }
     }
@@ -189,7 +246,7 @@ impl<T> Default for ManualEventReader<T> {
 impl<T> ManualEventReader<T> {
     /// See [`EventReader::iter`]
     pub fn iter<'a>(&mut self, events: &'a Events<T>) -> impl DoubleEndedIterator<Item = &'a T> {
-        internal_event_reader(&mut self.last_event_count, events).map(|(e, _)| e)
+        internal_event_reader(self, events).map(|(e, _)| e)
     }
 
     /// See [`EventReader::iter_with_id`]
@@ -197,59 +254,50 @@ impl<T> ManualEventReader<T> {
         &mut self,
         events: &'a Events<T>,
     ) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-        internal_event_reader(&mut self.last_event_count, events)
+        internal_event_reader(self, events)
+    }
+
+    /// The number of events the retention policy dropped before this reader saw them. Readers
+    /// that haven't read anything yet start at the oldest event, and events removed by
+    /// [`Events::clear`] or [`Events::drain`] were not missed, so neither counts.
+    fn missed_events(&self, events: &Events<T>) -> usize {
+        if self.last_event_count == 0 {
+            return 0;
+        }
+        events
+            .start_event_count
+            .saturating_sub(self.last_event_count.max(events.cleared_event_count))
     }
 }
 
 /// Like [`iter_with_id`](EventReader::iter_with_id) except not emitting any traces for read
 /// messages.
 fn internal_event_reader<'a, T>(
-    last_event_count: &mut usize,
+    reader: &mut ManualEventReader<T>,
     events: &'a Events<T>,
 ) -> impl DoubleEndedIterator<Item = (&'a T, EventId<T>)> {
-    // if the reader has seen some of the events in a buffer, find the proper index offset.
-    // otherwise read all events in the buffer
-    let a_index = if *last_event_count > events.a_start_event_count {
-        *last_event_count - events.a_start_event_count
-    } else {
-        0
-    };
-    let b_index = if *last_event_count > events.b_start_event_count {
-        *last_event_count - events.b_start_event_count
-    } else {
-        0
-    };
-    *last_event_count = events.event_count;
-    match events.state {
-        State::A => events
-            .events_b
-            .get(b_index..)
-            .unwrap_or_else(|| &[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_a
-                    .get(a_index..)
-                    .unwrap_or_else(|| &[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
-        State::B => events
-            .events_a
-            .get(a_index..)
-            .unwrap_or_else(|| &[])
-            .iter()
-            .map(map_instance_event_with_id)
-            .chain(
-                events
-                    .events_b
-                    .get(b_index..)
-                    .unwrap_or_else(|| &[])
-                    .iter()
-                    .map(map_instance_event_with_id),
-            ),
+    let missed = reader.missed_events(events);
+    if missed > 0 {
+        warn!(
+            "A reader of {} lagged behind and missed {} events. Read the events more often or \
+            change their retention policy.",
+            std::any::type_name::<T>(),
+            missed
+        );
     }
+    // if the reader has seen some of the events, find the proper index offset. otherwise read all
+    // events
+    let index = reader
+        .last_event_count
+        .saturating_sub(events.start_event_count);
+    reader.last_event_count = events.event_count;
+    if let Some(registration) = &reader.registration {
+        registration.store(events.event_count, Ordering::Relaxed);
+    }
+    events
+        .events
+        .range(index.min(events.events.len())..)
+        .map(map_instance_event_with_id)
 }
 
 impl<'a, T: Component> EventReader<'a, T> {
@@ -262,7 +310,7 @@ impl<'a, T: Component> EventReader<'a, T> {
 
     /// Like [`iter`](Self::iter), except also returning the [`EventId`] of the events.
     pub fn iter_with_id(&mut self) -> impl DoubleEndedIterator<Item = (&T, EventId<T>)> {
-        internal_event_reader(&mut self.last_event_count.0, &self.events).map(|(event, id)| {
+        internal_event_reader(&mut self.reader.0, &self.events).map(|(event, id)| {
             trace!("EventReader::iter() -> {}", id);
             (event, id)
         })
@@ -270,6 +318,27 @@ impl<'a, T: Component> EventReader<'a, T> {
 }
 
 impl<T: Component> Events<T> {
+    /// Creates an empty event collection with the given retention policy.
+    pub fn with_retention(retention: EventRetention) -> Self {
+        let mut events = Self::default();
+        events.set_retention(retention);
+        events
+    }
+
+    /// Returns the retention policy of this collection.
+    pub fn retention(&self) -> EventRetention {
+        self.retention
+    }
+
+    /// Changes the retention policy of this collection. Events that are already stored are dropped
+    /// by the next update or send, according to the new policy.
+    pub fn set_retention(&mut self, retention: EventRetention) {
+        if let EventRetention::Frames(0) | EventRetention::Bounded(0) = retention {
+            panic!("Events need to be retained for at least one frame or event.");
+        }
+        self.retention = retention;
+    }
+
     /// "Sends" an `event` by writing it to the current event buffer. [EventReader]s can then read
     /// the event.
     pub fn send(&mut self, event: T) {
@@ -280,19 +349,29 @@ impl<T: Component> Events<T> {
         trace!("Events::send() -> {}", event_id);
 
         let event_instance = EventInstance { event_id, event };
+        self.events.push_back(event_instance);
+        self.event_count += 1;
 
-        match self.state {
-            State::A => self.events_a.push(event_instance),
-            State::B => self.events_b.push(event_instance),
+        if let EventRetention::Bounded(capacity) = self.retention {
+            if self.events.len() > capacity {
+                if !self.overflow_reported {
+                    warn!(
+                        "More than {} {} events are queued, dropping the oldest ones.",
+                        capacity,
+                        std::any::type_name::<T>()
+                    );
+                    self.overflow_reported = true;
+                }
+                self.drop_events_before(self.event_count - capacity);
+            }
         }
-
-        self.event_count += 1;
     }
 
     /// Gets a new [ManualEventReader]. This will include all events already in the event buffers.
     pub fn get_reader(&self) -> ManualEventReader<T> {
         ManualEventReader {
             last_event_count: 0,
+            registration: None,
             _marker: PhantomData,
         }
     }
@@ -302,24 +381,59 @@ impl<T: Component> Events<T> {
     pub fn get_reader_current(&self) -> ManualEventReader<T> {
         ManualEventReader {
             last_event_count: self.event_count,
+            registration: None,
             _marker: PhantomData,
         }
     }
 
-    /// Swaps the event buffers and clears the oldest event buffer. In general, this should be
+    /// Gets a new [ManualEventReader] that includes all events already stored, and registers it:
+    /// with [`EventRetention::UntilConsumed`], events are kept until every registered reader that
+    /// still exists has read them.
+    pub fn register_reader(&mut self) -> ManualEventReader<T> {
+        let registration = Arc::new(AtomicUsize::new(self.start_event_count));
+        self.readers.push(registration.clone());
+        ManualEventReader {
+            last_event_count: 0,
+            registration: Some(registration),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Drops the events that the retention policy no longer keeps. In general, this should be
     /// called once per frame/update.
     pub fn update(&mut self) {
-        match self.state {
-            State::A => {
-                self.events_b = Vec::new();
-                self.state = State::B;
-                self.b_start_event_count = self.event_count;
+        self.last_update_event_count = self.event_count;
+        self.overflow_reported = false;
+        match self.retention {
+            EventRetention::Frames(frames) => {
+                self.update_event_counts.push_back(self.event_count);
+                while self.update_event_counts.len() > frames {
+                    self.update_event_counts.pop_front();
+                }
+                if self.update_event_counts.len() == frames {
+                    self.drop_events_before(self.update_event_counts[0]);
+                }
             }
-            State::B => {
-                self.events_a = Vec::new();
-                self.state = State::A;
-                self.a_start_event_count = self.event_count;
+            EventRetention::UntilConsumed => {
+                // readers that were dropped only hold a reference in `readers`
+                self.readers
+                    .retain(|registration| Arc::strong_count(registration) > 1);
+                let consumed = self
+                    .readers
+                    .iter()
+                    .map(|registration| registration.load(Ordering::Relaxed))
+                    .min()
+                    .unwrap_or(self.event_count);
+                self.drop_events_before(consumed);
             }
+            EventRetention::Bounded(_) => {}
+        }
+    }
+
+    /// Drops the events with an id lower than `event_count`.
+    fn drop_events_before(&mut self, event_count: usize) {
+        while self.start_event_count < event_count && self.events.pop_front().is_some() {
+            self.start_event_count += 1;
         }
     }
 
@@ -330,25 +444,16 @@ impl<T: Component> Events<T> {
 
     /// Removes all events.
     pub fn clear(&mut self) {
-        self.events_a.clear();
-        self.events_b.clear();
+        self.events.clear();
+        self.start_event_count = self.event_count;
+        self.cleared_event_count = self.event_count;
     }
 
     /// Creates a draining iterator that removes all events.
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
-        let map = |i: EventInstance<T>| i.event;
-        match self.state {
-            State::A => self
-                .events_b
-                .drain(..)
-                .map(map)
-                .chain(self.events_a.drain(..).map(map)),
-            State::B => self
-                .events_a
-                .drain(..)
-                .map(map)
-                .chain(self.events_b.drain(..).map(map)),
-        }
+        self.start_event_count = self.event_count;
+        self.cleared_event_count = self.event_count;
+        self.events.drain(..).map(|i: EventInstance<T>| i.event)
     }
 
     pub fn extend<I>(&mut self, events: I)
@@ -367,10 +472,12 @@ impl<T: Component> Events<T> {
     /// If events happen outside that window, they will not be handled. For example, any events that
     /// happen after this call and before the next `update()` call will be dropped.
     pub fn iter_current_update_events(&self) -> impl DoubleEndedIterator<Item = &T> {
-        match self.state {
-            State::A => self.events_a.iter().map(map_instance_event),
-            State::B => self.events_b.iter().map(map_instance_event),
-        }
+        let index = self
+            .last_update_event_count
+            .saturating_sub(self.start_event_count);
+        self.events
+            .range(index.min(self.events.len())..)
+            .map(map_instance_event)
     }
 }
 
@@ -480,4 +587,113 @@ mod tests {
     ) -> Vec<TestEvent> {
         reader.iter(events).cloned().collect::<Vec<TestEvent>>()
     }
+
+    #[test]
+    fn cleared_events_are_not_missed() {
+        let mut events = Events::<TestEvent>::default();
+        let mut reader = events.get_reader();
+        events.send(TestEvent { i: 0 });
+        assert_eq!(reader.iter(&events).count(), 1);
+
+        events.send(TestEvent { i: 1 });
+        events.clear();
+        assert_eq!(reader.missed_events(&events), 0);
+        events.send(TestEvent { i: 2 });
+        assert_eq!(reader.missed_events(&events), 0);
+        assert_eq!(reader.iter(&events).count(), 1);
+
+        events.send(TestEvent { i: 3 });
+        assert_eq!(events.drain().count(), 2);
+        assert_eq!(reader.missed_events(&events), 0);
+
+        // events dropped by updates are still missed
+        events.send(TestEvent { i: 4 });
+        events.update();
+        events.update();
+        assert_eq!(reader.missed_events(&events), 1);
+    }
+
+    #[test]
+    fn frames_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::Frames(3));
+        let mut reader = events.get_reader();
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.send(TestEvent { i: 1 });
+        events.update();
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 0 }, TestEvent { i: 1 }]
+        );
+        events.update();
+        assert_eq!(events.iter_current_update_events().count(), 0);
+        events.update();
+        assert_eq!(get_events(&events, &mut reader), vec![]);
+        assert_eq!(events.get_reader().iter(&events).count(), 0);
+    }
+
+    #[test]
+    fn until_consumed_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::UntilConsumed);
+        let mut fast = events.register_reader();
+        let mut slow = events.register_reader();
+        for i in 0..3 {
+            events.send(TestEvent { i });
+            assert_eq!(get_events(&events, &mut fast), vec![TestEvent { i }]);
+            events.update();
+        }
+        assert_eq!(get_events(&events, &mut slow).len(), 3);
+        events.send(TestEvent { i: 3 });
+        events.update();
+        // only the unread event is kept
+        assert_eq!(events.get_reader().iter(&events).count(), 1);
+
+        // dropped readers don't keep events alive
+        drop(slow);
+        assert_eq!(get_events(&events, &mut fast), vec![TestEvent { i: 3 }]);
+        events.update();
+        assert_eq!(events.get_reader().iter(&events).count(), 0);
+    }
+
+    #[test]
+    fn bounded_retention() {
+        let mut events = Events::<TestEvent>::with_retention(EventRetention::Bounded(2));
+        let mut reader = events.get_reader();
+        events.extend((0..3).map(|i| TestEvent { i }));
+        events.update();
+        events.update();
+        assert_eq!(
+            get_events(&events, &mut reader),
+            vec![TestEvent { i: 1 }, TestEvent { i: 2 }]
+        );
+    }
+
+    #[test]
+    fn event_readers_are_registered() {
+        use crate::{
+            schedule::{Stage, SystemStage},
+            system::IntoSystem,
+        };
+
+        fn read(mut reader: EventReader<TestEvent>, mut read: ResMut<Vec<TestEvent>>) {
+            read.extend(reader.iter().cloned());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Events::<TestEvent>::with_retention(
+            EventRetention::UntilConsumed,
+        ));
+        world.insert_resource(Vec::<TestEvent>::new());
+        let mut stage = SystemStage::single_threaded().with_system(read.system());
+        stage.run(&mut world);
+
+        // the reader doesn't run for a few frames, like a system behind a fixed timestep
+        for i in 0..4 {
+            let mut events = world.get_resource_mut::<Events<TestEvent>>().unwrap();
+            events.send(TestEvent { i });
+            events.update();
+        }
+        stage.run(&mut world);
+        assert_eq!(world.get_resource::<Vec<TestEvent>>().unwrap().len(), 4);
+    }
 }
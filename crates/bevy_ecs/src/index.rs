@@ -0,0 +1,509 @@
+use crate::{
+    archetype::Archetype,
+    component::{Component, ComponentId},
+    entity::Entity,
+    query::{ChangeTrackers, QueryState},
+    system::{ResMut, ResMutState, SystemParam, SystemParamFetch, SystemParamState, SystemState},
+    world::World,
+};
+use bevy_utils::HashMap;
+use smallvec::SmallVec;
+use std::{
+    collections::BTreeMap,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Deref, RangeBounds},
+};
+
+/// A component whose values can be looked up with an [`Index`] or an [`OrderedIndex`].
+///
+/// ```
+/// # use bevy_ecs::{index::IndexedComponent, prelude::*};
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+/// struct Tile(i32, i32);
+///
+/// impl IndexedComponent for Tile {
+///     type Key = Tile;
+///
+///     fn index_key(&self) -> Tile {
+///         *self
+///     }
+/// }
+///
+/// struct Unit;
+///
+/// fn units_on_origin(index: Index<Tile>, units: Query<&Unit>) {
+///     for entity in index.get(&Tile(0, 0)) {
+///         assert!(units.get(*entity).is_ok());
+///     }
+/// }
+/// # units_on_origin.system();
+/// ```
+pub trait IndexedComponent: Component {
+    type Key: Clone + Send + Sync + 'static;
+
+    /// The key this component is found under in its indexes.
+    fn index_key(&self) -> Self::Key;
+}
+
+/// The map from keys to entities used by a [`ComponentIndex`].
+pub trait IndexMap<K>: Default + Send + Sync + 'static {
+    fn insert(&mut self, key: K, entity: Entity);
+    fn remove(&mut self, key: &K, entity: Entity);
+    fn get(&self, key: &K) -> &[Entity];
+}
+
+/// The [`IndexMap`] of an [`Index`].
+pub type HashIndexMap<K> = HashMap<K, SmallVec<[Entity; 4]>>;
+
+/// The [`IndexMap`] of an [`OrderedIndex`].
+pub type OrderedIndexMap<K> = BTreeMap<K, SmallVec<[Entity; 4]>>;
+
+impl<K: Hash + Eq + Send + Sync + 'static> IndexMap<K> for HashIndexMap<K> {
+    fn insert(&mut self, key: K, entity: Entity) {
+        self.entry(key).or_default().push(entity);
+    }
+
+    fn remove(&mut self, key: &K, entity: Entity) {
+        if let Some(entities) = self.get_mut(key) {
+            entities.retain(|e| *e != entity);
+            if entities.is_empty() {
+                HashMap::remove(self, key);
+            }
+        }
+    }
+
+    fn get(&self, key: &K) -> &[Entity] {
+        HashMap::get(self, key).map_or(&[], |entities| entities)
+    }
+}
+
+impl<K: Ord + Send + Sync + 'static> IndexMap<K> for OrderedIndexMap<K> {
+    fn insert(&mut self, key: K, entity: Entity) {
+        self.entry(key).or_default().push(entity);
+    }
+
+    fn remove(&mut self, key: &K, entity: Entity) {
+        if let Some(entities) = self.get_mut(key) {
+            entities.retain(|e| *e != entity);
+            if entities.is_empty() {
+                BTreeMap::remove(self, key);
+            }
+        }
+    }
+
+    fn get(&self, key: &K) -> &[Entity] {
+        BTreeMap::get(self, key).map_or(&[], |entities| entities)
+    }
+}
+
+/// A resource mapping the keys of every `T` component to the entities that have them, created by
+/// [`World::init_index`] or by the first system using an [`Index<T>`] or [`OrderedIndex<T>`].
+///
+/// Entities are removed from the index as soon as their `T` is removed or they are despawned.
+/// Added, replaced and mutated components are picked up through change detection each time a
+/// system fetches the index, so looking up a key costs a single map access while keeping the
+/// index up to date costs a change tick check per `T`.
+pub struct ComponentIndex<
+    T: IndexedComponent,
+    M: IndexMap<T::Key> = HashIndexMap<<T as IndexedComponent>::Key>,
+> {
+    map: M,
+    keys: HashMap<Entity, T::Key>,
+    last_update: Option<u32>,
+    marker: PhantomData<T>,
+}
+
+impl<T: IndexedComponent, M: IndexMap<T::Key>> Default for ComponentIndex<T, M> {
+    fn default() -> Self {
+        Self {
+            map: M::default(),
+            keys: HashMap::default(),
+            last_update: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: IndexedComponent, M: IndexMap<T::Key>> ComponentIndex<T, M> {
+    /// Returns the entities whose `T` has the given key.
+    #[inline]
+    pub fn get(&self, key: &T::Key) -> &[Entity] {
+        self.map.get(key)
+    }
+
+    /// Returns the key of the `T` component of `entity`, as of the last update.
+    #[inline]
+    pub fn key(&self, entity: Entity) -> Option<&T::Key> {
+        self.keys.get(&entity)
+    }
+
+    /// Returns the number of indexed entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The underlying map from keys to entities.
+    #[inline]
+    pub fn map(&self) -> &M {
+        &self.map
+    }
+
+    /// Indexes the components changed since the last update, and returns whether there were any.
+    ///
+    /// # Safety
+    /// `query` must be up to date with the archetypes of `world`, and `T` must not be mutably
+    /// borrowed.
+    unsafe fn update_from(
+        &mut self,
+        world: &World,
+        query: &QueryState<(Entity, &T, ChangeTrackers<T>)>,
+        change_tick: u32,
+    ) -> bool {
+        let last_update = self.last_update;
+        let mut changed = false;
+        // the first update indexes every component, whatever its change ticks
+        for (entity, value, trackers) in
+            query.iter_unchecked_manual(world, last_update.unwrap_or(0), change_tick)
+        {
+            if last_update.is_none() || trackers.is_changed() {
+                self.insert(entity, value.index_key());
+                changed = true;
+            }
+        }
+        // changes made later in this tick, e.g. by commands applied right after the system that
+        // updated the index, have this same tick. Checking it again next time costs a redundant
+        // re-index at worst.
+        self.last_update = Some(change_tick.wrapping_sub(1));
+        changed
+    }
+
+    fn reset(&mut self) {
+        self.map = M::default();
+        self.keys.clear();
+        self.last_update = None;
+    }
+
+    fn insert(&mut self, entity: Entity, key: T::Key) {
+        self.remove(entity);
+        self.map.insert(key.clone(), entity);
+        self.keys.insert(entity, key);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(key) = self.keys.remove(&entity) {
+            self.map.remove(&key, entity);
+        }
+    }
+}
+
+impl<T: IndexedComponent> ComponentIndex<T, OrderedIndexMap<T::Key>>
+where
+    T::Key: Ord,
+{
+    /// Returns the keys in `range`, in order, with the entities that have them.
+    pub fn range(
+        &self,
+        range: impl RangeBounds<T::Key>,
+    ) -> impl Iterator<Item = (&T::Key, &[Entity])> + '_ {
+        self.map
+            .range(range)
+            .map(|(key, entities)| (key, entities.as_slice()))
+    }
+}
+
+/// Removes `entity` from the [`ComponentIndex<T, M>`], when its `T` is removed.
+pub(crate) fn remove_from_index<T: IndexedComponent, M: IndexMap<T::Key>>(
+    world: &mut World,
+    entity: Entity,
+    _: ComponentId,
+) {
+    if let Some(mut index) = world.get_resource_mut::<ComponentIndex<T, M>>() {
+        index.remove(entity);
+    }
+}
+
+/// Empties the [`ComponentIndex<T, M>`], so that its next update indexes every `T` again. Restoring
+/// a [`WorldSnapshot`](crate::world::WorldSnapshot) brings back components whose change ticks are
+/// older than the index's last update, which change detection would miss.
+pub(crate) fn reset_index<T: IndexedComponent, M: IndexMap<T::Key>>(world: &mut World) {
+    if let Some(mut index) = world.get_resource_mut::<ComponentIndex<T, M>>() {
+        index.reset();
+    }
+}
+
+/// A [`SystemParam`] looking up entities by the key of their `T` component in a hash map. See
+/// [`IndexedComponent`].
+///
+/// The index is brought up to date before the system runs. Using an `Index<T>` reads every `T`, so
+/// it conflicts with systems writing `T` and with other systems using the same index.
+pub struct Index<
+    'a,
+    T: IndexedComponent,
+    M: IndexMap<T::Key> = HashIndexMap<<T as IndexedComponent>::Key>,
+>(ResMut<'a, ComponentIndex<T, M>>);
+
+/// An [`Index`] that keeps its keys ordered, so that ranges of keys can be looked up with
+/// [`ComponentIndex::range`].
+pub type OrderedIndex<'a, T> = Index<'a, T, OrderedIndexMap<<T as IndexedComponent>::Key>>;
+
+impl<'a, T: IndexedComponent, M: IndexMap<T::Key>> Deref for Index<'a, T, M> {
+    type Target = ComponentIndex<T, M>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The [`SystemParamState`] of [`Index`].
+pub struct IndexState<T: IndexedComponent, M: IndexMap<T::Key>> {
+    index: ResMutState<ComponentIndex<T, M>>,
+    query: QueryState<(Entity, &'static T, ChangeTrackers<T>)>,
+}
+
+impl<'a, T: IndexedComponent, M: IndexMap<T::Key>> SystemParam for Index<'a, T, M> {
+    type Fetch = IndexState<T, M>;
+}
+
+// SAFE: the access of the index resource and of the query reading `T` is applied to the
+// SystemState, which panics if it conflicts with any prior access
+unsafe impl<T: IndexedComponent, M: IndexMap<T::Key>> SystemParamState for IndexState<T, M> {
+    type Config = ();
+
+    fn init(world: &mut World, system_state: &mut SystemState, _config: Self::Config) -> Self {
+        world.init_index::<T, M>();
+        Self {
+            index: ResMutState::init(world, system_state, ()),
+            query: SystemParamState::init(world, system_state, ()),
+        }
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype, system_state: &mut SystemState) {
+        SystemParamState::new_archetype(&mut self.query, archetype, system_state);
+    }
+
+    fn default_config() {}
+}
+
+impl<'a, T: IndexedComponent, M: IndexMap<T::Key>> SystemParamFetch<'a> for IndexState<T, M> {
+    type Item = Index<'a, T, M>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'a mut Self,
+        system_state: &'a SystemState,
+        world: &'a World,
+        change_tick: u32,
+    ) -> Self::Item {
+        let mut index = ResMutState::get_param(&mut state.index, system_state, world, change_tick);
+        // only actual updates mark the index as changed
+        if index
+            .bypass_change_detection()
+            .update_from(world, &state.query, change_tick)
+        {
+            index.set_changed();
+        }
+        Index(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentIndex, HashIndexMap, IndexedComponent, OrderedIndex};
+    use crate::{
+        entity::Entity,
+        prelude::*,
+        schedule::{Stage, SystemStage},
+    };
+    use std::any::TypeId;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct Tile(i32);
+
+    impl IndexedComponent for Tile {
+        type Key = Tile;
+
+        fn index_key(&self) -> Tile {
+            *self
+        }
+    }
+
+    fn lookup(world: &mut World, key: Tile) -> Vec<Entity> {
+        fn lookup_system(index: Index<Tile>, key: Res<Tile>, mut found: ResMut<Vec<Entity>>) {
+            *found = index.get(&key).to_vec();
+            found.sort();
+        }
+
+        world.insert_resource(key);
+        world.insert_resource(Vec::<Entity>::new());
+        let mut stage = SystemStage::single_threaded().with_system(lookup_system.system());
+        stage.run(world);
+        world.remove_resource::<Vec<Entity>>().unwrap()
+    }
+
+    #[test]
+    fn hash_index() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert(Tile(1)).id();
+        let e2 = world.spawn().insert(Tile(1)).id();
+        let e3 = world.spawn().insert(Tile(2)).id();
+
+        fn lookup_system(
+            index: Index<Tile>,
+            mut results: ResMut<Vec<Vec<Entity>>>,
+            tiles: Query<&Tile>,
+        ) {
+            for key in 0..3 {
+                let mut entities = index.get(&Tile(key)).to_vec();
+                entities.sort();
+                assert!(entities
+                    .iter()
+                    .all(|entity| *tiles.get(*entity).unwrap() == Tile(key)));
+                results.push(entities);
+            }
+        }
+        fn move_tiles(mut commands: Commands, mut tiles: Query<(Entity, &mut Tile)>) {
+            for (entity, mut tile) in tiles.iter_mut() {
+                match tile.0 {
+                    // mutated in place
+                    1 => tile.0 = 0,
+                    // replaced
+                    2 => {
+                        commands.entity(entity).insert(Tile(1));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        world.insert_resource(Vec::<Vec<Entity>>::new());
+        let mut stage = SystemStage::single_threaded()
+            .with_system(lookup_system.system().label("lookup"))
+            .with_system(move_tiles.system().after("lookup"));
+        stage.run(&mut world);
+        assert_eq!(
+            world.get_resource::<Vec<Vec<Entity>>>().unwrap(),
+            &vec![vec![], vec![e1, e2], vec![e3]]
+        );
+
+        world
+            .get_resource_mut::<Vec<Vec<Entity>>>()
+            .unwrap()
+            .clear();
+        world.entity_mut(e1).remove::<Tile>();
+        let e4 = world.spawn().insert(Tile(2)).id();
+        stage.run(&mut world);
+        assert_eq!(
+            world.get_resource::<Vec<Vec<Entity>>>().unwrap(),
+            &vec![vec![e2], vec![e3], vec![e4]]
+        );
+
+        world.despawn(e3);
+        world
+            .get_resource_mut::<Vec<Vec<Entity>>>()
+            .unwrap()
+            .clear();
+        stage.run(&mut world);
+        assert_eq!(
+            world.get_resource::<Vec<Vec<Entity>>>().unwrap(),
+            &vec![vec![e2], vec![e4], vec![]]
+        );
+    }
+
+    #[test]
+    fn index_created_after_spawning() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert(Tile(7)).id();
+        assert_eq!(lookup(&mut world, Tile(7)), vec![e1]);
+        world.get_mut::<Tile>(e1).unwrap().0 = 8;
+        assert_eq!(lookup(&mut world, Tile(7)), vec![]);
+        assert_eq!(lookup(&mut world, Tile(8)), vec![e1]);
+    }
+
+    #[test]
+    fn restoring_snapshot_rebuilds_index() {
+        let mut world = World::new();
+        world.register_snapshot_component::<Tile>();
+        let e1 = world.spawn().insert(Tile(1)).id();
+        let e2 = world.spawn().insert(Tile(2)).id();
+        assert_eq!(lookup(&mut world, Tile(1)), vec![e1]);
+        let snapshot = world.snapshot().unwrap();
+
+        world.get_mut::<Tile>(e1).unwrap().0 = 2;
+        world.despawn(e2);
+        let e3 = world.spawn().insert(Tile(1)).id();
+        assert_eq!(lookup(&mut world, Tile(1)), vec![e3]);
+        assert_eq!(lookup(&mut world, Tile(2)), vec![e1]);
+
+        world.restore(&snapshot);
+        assert_eq!(lookup(&mut world, Tile(1)), vec![e1]);
+        assert_eq!(lookup(&mut world, Tile(2)), vec![e2]);
+    }
+
+    #[test]
+    fn reinitializing_index() {
+        let mut world = World::new();
+        world.init_index::<Tile, HashIndexMap<Tile>>();
+        world.remove_resource::<ComponentIndex<Tile>>();
+        world.init_index::<Tile, HashIndexMap<Tile>>();
+        let tile_id = world.components().get_id(TypeId::of::<Tile>()).unwrap();
+        let info = world.components().get_info(tile_id).unwrap();
+        assert_eq!(info.index_hooks().count(), 1);
+        assert_eq!(world.restore_hooks.len(), 1);
+
+        let e1 = world.spawn().insert(Tile(1)).id();
+        assert_eq!(lookup(&mut world, Tile(1)), vec![e1]);
+    }
+
+    #[test]
+    fn unchanged_index_is_not_marked_changed() {
+        let mut world = World::new();
+        world.spawn().insert(Tile(1));
+        lookup(&mut world, Tile(1));
+        assert!(world.is_resource_changed::<ComponentIndex<Tile>>());
+        // components changed in the tick of an update are indexed again by the next one
+        lookup(&mut world, Tile(1));
+
+        world.clear_trackers();
+        lookup(&mut world, Tile(1));
+        assert!(!world.is_resource_changed::<ComponentIndex<Tile>>());
+
+        world.clear_trackers();
+        world.spawn().insert(Tile(2));
+        lookup(&mut world, Tile(1));
+        assert!(world.is_resource_changed::<ComponentIndex<Tile>>());
+    }
+
+    #[test]
+    fn ordered_index() {
+        fn in_range(index: OrderedIndex<Tile>, mut found: ResMut<Vec<(Tile, Entity)>>) {
+            for (tile, entities) in index.range(Tile(2)..Tile(5)) {
+                found.extend(entities.iter().map(|entity| (*tile, *entity)));
+            }
+        }
+
+        let mut world = World::new();
+        let entities = (0..6)
+            .map(|i| world.spawn().insert(Tile(5 - i)).id())
+            .collect::<Vec<_>>();
+        world.insert_resource(Vec::<(Tile, Entity)>::new());
+        SystemStage::single_threaded()
+            .with_system(in_range.system())
+            .run(&mut world);
+        assert_eq!(
+            world.get_resource::<Vec<(Tile, Entity)>>().unwrap(),
+            &vec![
+                (Tile(2), entities[3]),
+                (Tile(3), entities[2]),
+                (Tile(4), entities[1])
+            ]
+        );
+    }
+}
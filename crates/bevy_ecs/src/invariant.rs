@@ -0,0 +1,333 @@
+use crate::{
+    component::{Component, ComponentId},
+    entity::Entity,
+    world::World,
+};
+use bevy_utils::tracing::error;
+use std::{any::TypeId, fmt};
+
+type InvariantCheck = Box<dyn Fn(&World, &mut Vec<InvariantViolation>) + Send + Sync>;
+
+/// A resource holding consistency rules that every entity in the [`World`] must follow, such as
+/// "`A` requires `B`" or "the entities referenced by `Parent` must be alive".
+///
+/// In debug builds, a [`Schedule`](crate::schedule::Schedule) checks the invariants after each of
+/// its stages when this resource exists, and reports every violation with its entity and the
+/// names of the components involved. Violations are logged, or cause a panic if
+/// [`Self::panic_on_violation`] was set. [`Self::check`] can be called directly in any build.
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, invariant::Invariants};
+/// struct Parent(Entity);
+/// struct Player;
+/// struct Health(u32);
+/// struct Ghost;
+///
+/// let mut invariants = Invariants::default();
+/// invariants
+///     .requires::<Player, Health>()
+///     .excludes::<Health, Ghost>()
+///     .entities_alive(|parent: &Parent| vec![parent.0]);
+///
+/// let mut world = World::new();
+/// let parent = world.spawn().id();
+/// world.spawn().insert_bundle((Player, Health(10), Parent(parent)));
+/// assert!(invariants.check(&world).is_empty());
+///
+/// world.despawn(parent);
+/// let violations = invariants.check(&world);
+/// assert_eq!(violations.len(), 1);
+/// assert!(violations[0].message.contains("dead entity"));
+/// ```
+#[derive(Default)]
+pub struct Invariants {
+    checks: Vec<InvariantCheck>,
+    panic_on_violation: bool,
+}
+
+/// An entity that breaks one of the [`Invariants`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvariantViolation {
+    pub entity: Entity,
+    pub message: String,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.entity, self.message)
+    }
+}
+
+impl Invariants {
+    /// Makes violations found after a stage panic instead of being logged.
+    pub fn panic_on_violation(&mut self) -> &mut Self {
+        self.panic_on_violation = true;
+        self
+    }
+
+    /// Requires every entity with an `A` component to also have a `B` component.
+    pub fn requires<A: Component, B: Component>(&mut self) -> &mut Self {
+        self.add(|world, violations| {
+            let a = match component_id::<A>(world) {
+                Some(a) => a,
+                None => return,
+            };
+            let b = component_id::<B>(world);
+            for archetype in world.archetypes().iter() {
+                if archetype.contains(a) && !b.map_or(false, |b| archetype.contains(b)) {
+                    for entity in archetype.entities() {
+                        violations.push(InvariantViolation {
+                            entity: *entity,
+                            message: format!(
+                                "has `{}` but not the required `{}`",
+                                component_name::<A>(world),
+                                component_name::<B>(world)
+                            ),
+                        });
+                    }
+                }
+            }
+        })
+    }
+
+    /// Forbids entities from having both an `A` and a `B` component.
+    pub fn excludes<A: Component, B: Component>(&mut self) -> &mut Self {
+        self.add(|world, violations| {
+            let (a, b) = match (component_id::<A>(world), component_id::<B>(world)) {
+                (Some(a), Some(b)) => (a, b),
+                _ => return,
+            };
+            for archetype in world.archetypes().iter() {
+                if archetype.contains(a) && archetype.contains(b) {
+                    for entity in archetype.entities() {
+                        violations.push(InvariantViolation {
+                            entity: *entity,
+                            message: format!(
+                                "has both `{}` and `{}`, which exclude each other",
+                                component_name::<A>(world),
+                                component_name::<B>(world)
+                            ),
+                        });
+                    }
+                }
+            }
+        })
+    }
+
+    /// Requires the entities returned by `entities` for each `T` component to be alive.
+    pub fn entities_alive<T: Component>(
+        &mut self,
+        entities: impl Fn(&T) -> Vec<Entity> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.component(move |world, component: &T, violations| {
+            for referenced in entities(component) {
+                if !world.entities().contains(referenced) {
+                    violations.push(format!(
+                        "has a `{}` referring to dead entity {:?}",
+                        component_name::<T>(world),
+                        referenced
+                    ));
+                }
+            }
+        })
+    }
+
+    /// Requires `check` to hold for every `T` component. `description` is used to report
+    /// violations.
+    pub fn holds<T: Component>(
+        &mut self,
+        description: &'static str,
+        check: impl Fn(&World, &T) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.component(move |world, component: &T, violations| {
+            if !check(world, component) {
+                violations.push(format!(
+                    "has a `{}` that violates \"{}\"",
+                    component_name::<T>(world),
+                    description
+                ));
+            }
+        })
+    }
+
+    /// Adds an arbitrary check, which pushes the violations it finds.
+    pub fn add(
+        &mut self,
+        check: impl Fn(&World, &mut Vec<InvariantViolation>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Runs `check` on the `T` component of every entity that has one, turning the messages it
+    /// pushes into violations.
+    fn component<T: Component>(
+        &mut self,
+        check: impl Fn(&World, &T, &mut Vec<String>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.add(move |world, violations| {
+            let id = match component_id::<T>(world) {
+                Some(id) => id,
+                None => return,
+            };
+            let mut messages = Vec::new();
+            for archetype in world.archetypes().iter() {
+                if !archetype.contains(id) {
+                    continue;
+                }
+                for entity in archetype.entities() {
+                    check(world, world.get::<T>(*entity).unwrap(), &mut messages);
+                    violations.extend(messages.drain(..).map(|message| InvariantViolation {
+                        entity: *entity,
+                        message,
+                    }));
+                }
+            }
+        })
+    }
+
+    /// Returns every violation of the invariants in `world`.
+    pub fn check(&self, world: &World) -> Vec<InvariantViolation> {
+        let mut violations = Vec::new();
+        for check in self.checks.iter() {
+            check(world, &mut violations);
+        }
+        violations
+    }
+}
+
+/// Checks the [`Invariants`] resource of `world`, if any, after the stage labelled `stage` ran.
+pub(crate) fn check_invariants(world: &World, stage: &dyn fmt::Debug) {
+    let invariants = match world.get_resource::<Invariants>() {
+        Some(invariants) => invariants,
+        None => return,
+    };
+    let violations = invariants.check(world);
+    if violations.is_empty() {
+        return;
+    }
+    let mut message = format!(
+        "Found {} ECS invariant violations after stage {:?}:",
+        violations.len(),
+        stage
+    );
+    for violation in violations.iter() {
+        message.push_str(&format!("\n - {}", violation));
+    }
+    if invariants.panic_on_violation {
+        panic!("{}", message);
+    } else {
+        error!("{}", message);
+    }
+}
+
+fn component_id<T: Component>(world: &World) -> Option<ComponentId> {
+    world.components().get_id(TypeId::of::<T>())
+}
+
+fn component_name<T: Component>(world: &World) -> &str {
+    component_id::<T>(world)
+        .and_then(|id| world.components().get_info(id))
+        .map_or_else(|| std::any::type_name::<T>(), |info| info.name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InvariantViolation, Invariants};
+    use crate::{
+        component::{ComponentDescriptor, StorageType},
+        prelude::*,
+    };
+
+    struct A;
+    struct B;
+    struct C;
+    struct Children(Vec<Entity>);
+
+    #[test]
+    fn requires_and_excludes() {
+        let mut world = World::new();
+        world
+            .register_component(ComponentDescriptor::new::<C>(StorageType::SparseSet))
+            .unwrap();
+        let mut invariants = Invariants::default();
+        invariants.requires::<A, B>().excludes::<A, C>();
+
+        let valid = world.spawn().insert_bundle((A, B)).id();
+        world.spawn().insert(C);
+        assert!(invariants.check(&world).is_empty());
+
+        let missing = world.spawn().insert(A).id();
+        world.entity_mut(valid).insert(C);
+        let mut violations = invariants.check(&world);
+        violations.sort_by_key(|violation| violation.entity);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].entity, valid);
+        assert!(violations[0].message.contains("has both"));
+        assert_eq!(violations[1].entity, missing);
+        assert!(violations[1].message.contains("required"));
+        assert!(violations[1].message.contains(std::any::type_name::<B>()));
+    }
+
+    #[test]
+    fn component_checks() {
+        let mut world = World::new();
+        let mut invariants = Invariants::default();
+        invariants
+            .entities_alive(|children: &Children| children.0.clone())
+            .holds("no duplicate children", |_, children: &Children| {
+                let mut sorted = children.0.clone();
+                sorted.sort();
+                sorted.dedup();
+                sorted.len() == children.0.len()
+            });
+
+        let child = world.spawn().id();
+        let dead = world.spawn().id();
+        world.despawn(dead);
+        let parent = world
+            .spawn()
+            .insert(Children(vec![child, child, dead]))
+            .id();
+        let violations = invariants.check(&world);
+        assert_eq!(
+            violations,
+            vec![
+                InvariantViolation {
+                    entity: parent,
+                    message: format!(
+                        "has a `{}` referring to dead entity {:?}",
+                        std::any::type_name::<Children>(),
+                        dead
+                    ),
+                },
+                InvariantViolation {
+                    entity: parent,
+                    message: format!(
+                        "has a `{}` that violates \"no duplicate children\"",
+                        std::any::type_name::<Children>()
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invariant violations after stage \"update\"")]
+    fn checked_after_each_stage() {
+        fn spawn_a(mut commands: Commands) {
+            commands.spawn().insert(A);
+        }
+
+        let mut world = World::new();
+        let mut invariants = Invariants::default();
+        invariants.requires::<A, B>().panic_on_violation();
+        world.insert_resource(invariants);
+        let mut schedule = Schedule::default().with_stage(
+            "update",
+            SystemStage::single_threaded().with_system(spawn_a.system()),
+        );
+        schedule.run(&mut world);
+    }
+}
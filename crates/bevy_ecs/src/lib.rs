@@ -3,9 +3,13 @@ pub mod bundle;
 pub mod component;
 pub mod entity;
 pub mod event;
+pub mod index;
+pub mod invariant;
+pub mod observer;
 pub mod query;
 #[cfg(feature = "bevy_reflect")]
 pub mod reflect;
+pub mod relation;
 pub mod schedule;
 pub mod storage;
 pub mod system;
@@ -20,15 +24,19 @@ pub mod prelude {
         bundle::Bundle,
         entity::Entity,
         event::{EventReader, EventWriter},
+        index::{Index, OrderedIndex},
+        observer::Trigger,
         query::{Added, ChangeTrackers, Changed, Or, QueryState, With, WithBundle, Without},
+        relation::{Related, Relation},
         schedule::{
-            AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaLabel, RunCriteriaPiping,
-            Schedule, Stage, StageLabel, State, SystemLabel, SystemSet, SystemStage,
+            in_state, AmbiguitySetLabel, ExclusiveSystemDescriptorCoercion, LoopingStage,
+            NextState, ParallelSystemDescriptorCoercion, RunCriteria,
+            RunCriteriaDescriptorCoercion, RunCriteriaLabel, RunCriteriaPiping, Schedule, Stage,
+            StageLabel, State, SystemLabel, SystemSet, SystemStage,
         },
         system::{
             Commands, In, IntoChainSystem, IntoExclusiveSystem, IntoSystem, Local, NonSend,
-            NonSendMut, Query, QuerySet, RemovedComponents, Res, ResMut, System,
+            NonSendMut, Query, QuerySet, RemovedComponents, Res, ResMut, System, SystemRng,
         },
         world::{FromWorld, Mut, World},
     };
@@ -39,15 +47,25 @@ mod tests {
     use crate::{
         bundle::Bundle,
         component::{Component, ComponentDescriptor, ComponentId, StorageType, TypeInfo},
-        entity::Entity,
+        entity::{Disabled, Entity},
         query::{
-            Added, ChangeTrackers, Changed, FilterFetch, FilteredAccess, With, Without, WorldQuery,
+            Added, BatchSize, ChangeTrackers, Changed, DynamicQuery, FilterFetch, FilteredAccess,
+            QueryEntityError, With, Without, WorldQuery,
         },
-        world::{Mut, World},
+        relation::Relation,
+        system::{CommandQueue, Commands},
+        world::{Mut, SnapshotError, World},
     };
     use bevy_tasks::TaskPool;
     use parking_lot::Mutex;
-    use std::{any::TypeId, sync::Arc};
+    use std::{
+        alloc::Layout,
+        any::TypeId,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
 
     #[derive(Debug, PartialEq, Eq)]
     struct A(usize);
@@ -295,6 +313,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn par_iter() {
+        for storage_type in [StorageType::Table, StorageType::SparseSet] {
+            let mut world = World::new();
+            world
+                .register_component(ComponentDescriptor::new::<i32>(storage_type))
+                .unwrap();
+            let task_pool = TaskPool::new();
+            for i in 0..100 {
+                if i % 3 == 0 {
+                    world.spawn().insert_bundle((i, true));
+                } else {
+                    world.spawn().insert(i);
+                }
+            }
+            let mut query = world.query::<&i32>();
+            let expected = query.iter(&world).map(|i| i * 2).collect::<Vec<_>>();
+
+            let doubled = query.par_iter(&world, &task_pool).map(|i| i * 2);
+            assert_eq!(doubled, expected);
+            let doubled = query
+                .par_iter(&world, &task_pool)
+                .batch_size(7)
+                .map(|i| i * 2);
+            assert_eq!(doubled, expected);
+
+            let sums = query.par_iter(&world, &task_pool).fold(0, |sum, i| sum + i);
+            assert_eq!(sums.iter().sum::<i32>(), 4950);
+            let max = query.par_iter(&world, &task_pool).reduce(|i| *i, i32::max);
+            assert_eq!(max, Some(99));
+
+            world
+                .query::<&mut i32>()
+                .par_iter_mut(&mut world, &task_pool)
+                .for_each(|mut i| *i += 1);
+            assert_eq!(query.iter(&world).sum::<i32>(), 5050);
+
+            let pairs = world
+                .query_filtered::<&i32, With<bool>>()
+                .par_iter_combinations::<2>(&world, &task_pool)
+                .batch_size(1)
+                .map(|[a, b]| (*a, *b));
+            assert_eq!(pairs.len(), 34 * 33 / 2);
+            let mut sequential = world
+                .query_filtered::<&i32, With<bool>>()
+                .iter_combinations::<2>(&world)
+                .map(|[a, b]| (*a, *b))
+                .collect::<Vec<_>>();
+            sequential.sort_unstable();
+            let mut pairs = pairs;
+            pairs.sort_unstable();
+            assert_eq!(pairs, sequential);
+        }
+
+        let mut world = World::new();
+        assert_eq!(
+            world
+                .query::<&i32>()
+                .par_iter(&world, &TaskPool::new())
+                .reduce(|i| *i, i32::max),
+            None
+        );
+    }
+
+    #[test]
+    fn auto_batch_size() {
+        assert_eq!(BatchSize::Auto.get(1000, 4), 62);
+        assert_eq!(BatchSize::Auto.get(3, 8), 1);
+        assert_eq!(BatchSize::Auto.get(0, 0), 1);
+        assert_eq!(BatchSize::Fixed(0).get(10, 4), 1);
+        assert_eq!(BatchSize::from(16).get(1000, 4), 16);
+    }
+
     #[test]
     fn query_missing_component() {
         let mut world = World::new();
@@ -850,6 +941,37 @@ mod tests {
         assert_eq!(get_changed(&mut world), vec![e1]);
     }
 
+    #[test]
+    fn changed_since_tick() {
+        let mut world = World::default();
+        let e1 = world.spawn().insert(A(0)).id();
+        let e2 = world.spawn().insert(A(0)).id();
+        world.clear_trackers();
+        let snapshot_tick = world.read_change_tick();
+        world.increment_change_tick();
+
+        *world.get_mut(e1).unwrap() = A(1);
+        world.clear_trackers();
+        world.get_mut::<A>(e2).unwrap().bypass_change_detection().0 = 1;
+
+        // changes since the last `clear_trackers`
+        let mut query = world.query_filtered::<Entity, Changed<A>>();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![]);
+
+        let changed = world
+            .last_change_tick_scope(snapshot_tick, |world| query.iter(world).collect::<Vec<_>>());
+        assert_eq!(changed, vec![e1]);
+        assert_eq!(world.get::<A>(e2).unwrap(), &A(1));
+
+        let a = world.get_mut::<A>(e1).unwrap();
+        assert!(!a.is_changed());
+        assert!(a.is_changed_since(snapshot_tick));
+        assert!(!a.is_added_since(snapshot_tick));
+
+        world.get_mut::<A>(e2).unwrap().set_changed();
+        assert_eq!(query.iter(&world).collect::<Vec<_>>(), vec![e2]);
+    }
+
     #[test]
     fn resource() {
         let mut world = World::default();
@@ -1174,4 +1296,521 @@ mod tests {
         });
         assert_eq!(*world.get_resource::<i32>().unwrap(), 1);
     }
+
+    #[test]
+    fn components_without_rust_type() {
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+        unsafe fn count_drop(_ptr: *mut u8) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut world = World::new();
+        // SAFE: values are plain bytes and `count_drop` does not read them
+        let (table_id, sparse_id) = unsafe {
+            (
+                world
+                    .register_component(ComponentDescriptor::new_with_layout(
+                        "Table",
+                        StorageType::Table,
+                        Layout::new::<[u32; 2]>(),
+                        Some(count_drop),
+                    ))
+                    .unwrap(),
+                world
+                    .register_component(ComponentDescriptor::new_with_layout(
+                        "Sparse",
+                        StorageType::SparseSet,
+                        Layout::new::<u64>(),
+                        None,
+                    ))
+                    .unwrap(),
+            )
+        };
+
+        let mut entity = world.spawn();
+        let mut value = [1u32, 2];
+        let mut sparse_value = 7u64;
+        // SAFE: the values match the layouts and are not used afterwards
+        unsafe {
+            entity
+                .insert_by_id(table_id, value.as_mut_ptr().cast())
+                .insert_by_id(sparse_id, (&mut sparse_value as *mut u64).cast());
+        }
+        entity.insert(A(3));
+        // SAFE: the components have the layouts of `[u32; 2]` and `u64`
+        unsafe {
+            assert_eq!(
+                entity.get_by_id(table_id).unwrap().deref::<[u32; 2]>(),
+                &[1, 2]
+            );
+            *entity.get_mut_by_id(sparse_id).unwrap().deref_mut::<u64>() = 8;
+        }
+        let e = entity.id();
+        assert_eq!(
+            world
+                .entity(e)
+                .get_by_id(sparse_id)
+                .map(|ptr| unsafe { *ptr.deref::<u64>() }),
+            Some(8)
+        );
+        assert_eq!(world.entity(e).get::<A>(), Some(&A(3)));
+
+        let mut entity = world.entity_mut(e);
+        assert!(entity.remove_by_id(table_id));
+        assert!(!entity.remove_by_id(table_id));
+        assert!(entity.get_by_id(table_id).is_none());
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+
+        let mut value = [3u32, 4];
+        // SAFE: the value matches the layout and is not used afterwards
+        let e = unsafe {
+            world
+                .spawn()
+                .insert_by_id(table_id, value.as_mut_ptr().cast())
+                .id()
+        };
+        world.despawn(e);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn move_entity_between_worlds() {
+        struct DropCounter(Arc<AtomicUsize>);
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        struct Likes;
+
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let mut source = World::new();
+        source
+            .register_component(ComponentDescriptor::new::<B>(StorageType::SparseSet))
+            .unwrap();
+        let e1 = source
+            .spawn()
+            .insert_bundle((A(1), B(2), DropCounter(dropped.clone())))
+            .id();
+        let e2 = source
+            .spawn()
+            .insert_bundle((A(3), DropCounter(dropped.clone())))
+            .id();
+        source.entity_mut(e1).insert_relation(Likes, e2);
+
+        // the destination uses other component ids and another storage type for `A`
+        let mut destination = World::new();
+        destination.spawn().insert(C);
+        destination
+            .register_component(ComponentDescriptor::new::<A>(StorageType::SparseSet))
+            .unwrap();
+
+        let moved = source.move_entity_to(&mut destination, e1).unwrap();
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+        assert!(source.get_entity(e1).is_none());
+        assert!(source.move_entity_to(&mut destination, e1).is_none());
+        assert_eq!(source.get::<A>(e2), Some(&A(3)));
+        assert_eq!(source.removed::<A>().collect::<Vec<_>>(), vec![e1]);
+        assert_eq!(source.related_to::<Likes>(e2).count(), 0);
+
+        assert_eq!(destination.get::<A>(moved), Some(&A(1)));
+        assert_eq!(destination.get::<B>(moved).unwrap().0, 2);
+        assert!(destination.get::<DropCounter>(moved).is_some());
+        assert!(destination.get::<Relation<Likes>>(moved).is_none());
+        let mut added = destination.query_filtered::<Entity, Added<A>>();
+        assert_eq!(added.iter(&destination).collect::<Vec<_>>(), vec![moved]);
+
+        destination.despawn(moved);
+        assert_eq!(dropped.load(Ordering::SeqCst), 1);
+        drop(source);
+        assert_eq!(dropped.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn extract_entities_keeps_relations() {
+        struct ChildOf;
+        #[derive(Debug, PartialEq)]
+        struct Likes(u32);
+
+        let mut source = World::new();
+        let outside = source.spawn().id();
+        let child = source.spawn().id();
+        let parent = source.spawn().id();
+        source
+            .entity_mut(child)
+            .insert_relation(ChildOf, parent)
+            .insert_relation(Likes(1), outside)
+            .insert_relation(Likes(2), child);
+        source.entity_mut(outside).insert_relation(ChildOf, parent);
+
+        let mut destination = World::new();
+        destination.spawn().insert(C);
+        // the child is moved before its parent
+        let entity_map = source.extract_entities(&mut destination, &[child, parent]);
+        let new_child = entity_map.get(child).unwrap();
+        let new_parent = entity_map.get(parent).unwrap();
+
+        let relation = destination.get::<Relation<ChildOf>>(new_child).unwrap();
+        assert_eq!(relation.targets().collect::<Vec<_>>(), vec![new_parent]);
+        assert_eq!(
+            destination
+                .related_to::<ChildOf>(new_parent)
+                .collect::<Vec<_>>(),
+            vec![new_child]
+        );
+        // the edge to `outside` is dropped, the edge to itself is remapped
+        let likes = destination.get::<Relation<Likes>>(new_child).unwrap();
+        assert_eq!(
+            likes.iter().collect::<Vec<_>>(),
+            vec![(new_child, &Likes(2))]
+        );
+        assert_eq!(destination.related_to::<Likes>(new_child).count(), 1);
+
+        assert!(source.get::<Relation<ChildOf>>(outside).is_none());
+        assert_eq!(source.related_to::<Likes>(outside).count(), 0);
+
+        destination.despawn(new_parent);
+        assert!(destination.get::<Relation<ChildOf>>(new_child).is_none());
+    }
+
+    #[test]
+    fn extract_entities_without_rust_type() {
+        fn register(world: &mut World) -> ComponentId {
+            // SAFE: values are plain bytes
+            unsafe {
+                world
+                    .register_component(ComponentDescriptor::new_with_layout(
+                        "Scripted",
+                        StorageType::Table,
+                        Layout::new::<u32>(),
+                        None,
+                    ))
+                    .unwrap()
+            }
+        }
+
+        let mut source = World::new();
+        let source_id = register(&mut source);
+        let entities = (0..3u32)
+            .map(|i| {
+                let mut value = i;
+                // SAFE: the value matches the layout and is not used afterwards
+                unsafe {
+                    source
+                        .spawn()
+                        .insert_by_id(source_id, (&mut value as *mut u32).cast())
+                        .insert(A(i as usize))
+                        .id()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut destination = World::new();
+        destination.spawn().insert(C);
+        let destination_id = register(&mut destination);
+        let entity_map = source.extract_entities(&mut destination, &entities[1..]);
+        assert_eq!(entity_map.keys().count(), 2);
+        assert!(source.get_entity(entities[0]).is_some());
+        for (i, entity) in entities.iter().enumerate().skip(1) {
+            let moved = entity_map.get(*entity).unwrap();
+            assert_eq!(destination.get::<A>(moved), Some(&A(i)));
+            let value = destination.entity(moved).get_by_id(destination_id).unwrap();
+            // SAFE: the component has the layout of `u32`
+            assert_eq!(unsafe { *value.deref::<u32>() }, i as u32);
+        }
+        assert_eq!(
+            destination.components().len(),
+            source.components().len() + 1
+        );
+    }
+
+    #[test]
+    fn disabled_entities() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(1))).id();
+        let e2 = world.spawn().insert_bundle((A(2), B(2))).id();
+        let e3 = world.spawn().insert(A(3)).id();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(e2).disable();
+        commands.entity(e3).disable();
+        queue.apply(&mut world);
+
+        let mut query = world.query::<(Entity, &A)>();
+        assert_eq!(
+            query.iter(&world).map(|(e, _)| e).collect::<Vec<_>>(),
+            vec![e1]
+        );
+        assert!(matches!(
+            query.get(&world, e2),
+            Err(QueryEntityError::QueryDoesNotMatch)
+        ));
+        assert_eq!(world.get::<A>(e2), Some(&A(2)));
+
+        let mut disabled = world.query_filtered::<Entity, With<Disabled>>();
+        assert_eq!(disabled.iter(&world).collect::<Vec<_>>(), vec![e2, e3]);
+        let mut all = world.query::<(Entity, Option<&Disabled>)>();
+        assert_eq!(all.iter(&world).count(), 3);
+
+        let a_id = world.components().get_id(TypeId::of::<A>()).unwrap();
+        let mut dynamic = DynamicQuery::new().read(a_id).build(&world);
+        assert_eq!(
+            dynamic
+                .iter(&world)
+                .map(|item| item.entity())
+                .collect::<Vec<_>>(),
+            vec![e1]
+        );
+        let disabled_id = world.disabled_component_id();
+        let mut dynamic = DynamicQuery::new()
+            .read(a_id)
+            .with(disabled_id)
+            .build(&world);
+        assert_eq!(dynamic.iter(&world).count(), 2);
+
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(e2).enable();
+        queue.apply(&mut world);
+        assert_eq!(
+            query.iter(&world).map(|(e, _)| e).collect::<Vec<_>>(),
+            vec![e1, e2]
+        );
+    }
+
+    #[test]
+    fn component_hooks() {
+        type Log = Vec<(&'static str, Entity)>;
+
+        fn log(world: &mut World, entity: Entity, event: &'static str) {
+            world
+                .get_resource_mut::<Log>()
+                .unwrap()
+                .push((event, entity));
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Log::new());
+        world
+            .register_component(
+                ComponentDescriptor::new::<A>(StorageType::Table)
+                    .on_add(|world, entity, _| log(world, entity, "add"))
+                    .on_insert(|world, entity, _| log(world, entity, "insert"))
+                    .on_remove(|world, entity, _| {
+                        // the value is still readable while the hook runs
+                        assert!(world.get::<A>(entity).is_some());
+                        log(world, entity, "remove");
+                    }),
+            )
+            .unwrap();
+        world
+            .register_component(
+                ComponentDescriptor::new::<B>(StorageType::SparseSet).on_add(|world, entity, _| {
+                    // hooks can make structural changes to the entity
+                    world.entity_mut(entity).insert(C);
+                }),
+            )
+            .unwrap();
+
+        let e1 = world.spawn().insert_bundle((A(1), B(1))).id();
+        assert!(world.get::<C>(e1).is_some());
+        world.entity_mut(e1).insert(A(2));
+        assert_eq!(world.entity_mut(e1).remove::<A>(), Some(A(2)));
+        assert_eq!(world.entity_mut(e1).remove::<A>(), None);
+        world.entity_mut(e1).insert(A(3));
+        world.despawn(e1);
+        assert_eq!(
+            world
+                .get_resource_mut::<Log>()
+                .unwrap()
+                .drain(..)
+                .collect::<Log>(),
+            vec![
+                ("add", e1),
+                ("insert", e1),
+                ("insert", e1),
+                ("remove", e1),
+                ("add", e1),
+                ("insert", e1),
+                ("remove", e1),
+            ]
+        );
+
+        let entities = world
+            .spawn_batch(vec![(A(4), B(4)), (A(5), B(5))])
+            .collect::<Vec<_>>();
+        assert!(entities
+            .iter()
+            .all(|entity| world.get::<C>(*entity).is_some()));
+        world
+            .entity_mut(entities[0])
+            .remove_bundle_intersection::<(A, C)>();
+        assert_eq!(
+            world
+                .get_resource_mut::<Log>()
+                .unwrap()
+                .drain(..)
+                .collect::<Log>(),
+            vec![
+                ("add", entities[0]),
+                ("insert", entities[0]),
+                ("add", entities[1]),
+                ("insert", entities[1]),
+                ("remove", entities[0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn required_components() {
+        #[derive(Default, Debug, PartialEq)]
+        struct Transform(u32);
+        #[derive(Default, Debug, PartialEq)]
+        struct GlobalTransform(u32);
+        #[derive(Default, Debug, PartialEq)]
+        struct Visible(bool);
+        struct Mesh;
+
+        let mut world = World::new();
+        world
+            .register_component(
+                ComponentDescriptor::new::<Mesh>(StorageType::SparseSet)
+                    .require::<Transform>()
+                    .require::<Visible>()
+                    .on_add(|world, entity, _| {
+                        // required components are inserted before `on_add` hooks run
+                        assert!(world.get::<GlobalTransform>(entity).is_some());
+                    }),
+            )
+            .unwrap();
+        world.register_required_components::<Transform, GlobalTransform>();
+        world.register_required_components::<Transform, GlobalTransform>();
+        assert_eq!(
+            world
+                .components()
+                .get_info(
+                    world
+                        .components()
+                        .get_id(TypeId::of::<Transform>())
+                        .unwrap()
+                )
+                .unwrap()
+                .required_components()
+                .len(),
+            1
+        );
+
+        let e1 = world.spawn().insert(Mesh).id();
+        assert_eq!(world.get::<Transform>(e1), Some(&Transform(0)));
+        assert_eq!(world.get::<GlobalTransform>(e1), Some(&GlobalTransform(0)));
+        assert_eq!(world.get::<Visible>(e1), Some(&Visible(false)));
+
+        // explicitly inserted values are kept
+        let e2 = world
+            .spawn()
+            .insert_bundle((Mesh, Transform(2), Visible(true)))
+            .id();
+        assert_eq!(world.get::<Transform>(e2), Some(&Transform(2)));
+        assert_eq!(world.get::<Visible>(e2), Some(&Visible(true)));
+        world.entity_mut(e2).insert(GlobalTransform(3));
+        world.entity_mut(e2).insert(Transform(4));
+        assert_eq!(world.get::<GlobalTransform>(e2), Some(&GlobalTransform(3)));
+
+        let mut queue = CommandQueue::default();
+        let e3 = Commands::new(&mut queue, &world)
+            .spawn()
+            .insert(Transform(5))
+            .id();
+        queue.apply(&mut world);
+        assert!(world.get::<GlobalTransform>(e3).is_some());
+
+        let batch = world
+            .spawn_batch(vec![(Transform(6),), (Transform(7),)])
+            .collect::<Vec<_>>();
+        assert!(batch
+            .iter()
+            .all(|entity| world.get::<GlobalTransform>(*entity).is_some()));
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Position(u32);
+        #[derive(Clone, Debug, PartialEq)]
+        struct Velocity(String);
+        #[derive(Clone, Debug, PartialEq)]
+        struct Frame(u32);
+        #[derive(Clone)]
+        struct ChildOf;
+        struct Unregistered;
+
+        let mut world = World::new();
+        world
+            .register_component(ComponentDescriptor::new::<Velocity>(StorageType::SparseSet))
+            .unwrap();
+        world.register_snapshot_component::<Position>();
+        world.register_snapshot_component::<Velocity>();
+        world.register_snapshot_component::<Relation<ChildOf>>();
+        world.register_snapshot_resource::<Frame>();
+        world.insert_resource(Frame(1));
+        world.insert_resource(0usize);
+
+        let e1 = world.spawn().insert(Position(1)).id();
+        let e2 = world
+            .spawn()
+            .insert_bundle((Position(2), Velocity("two".to_string())))
+            .id();
+        let e3 = world.spawn().insert(Position(3)).id();
+        world.despawn(e3);
+        world.entity_mut(e2).insert_relation(ChildOf, e1);
+        let snapshot = world.snapshot().unwrap();
+        assert_eq!(snapshot.world_id(), world.id());
+
+        world.get_mut::<Position>(e1).unwrap().0 = 10;
+        world.entity_mut(e2).remove::<Velocity>();
+        world.entity_mut(e2).remove_relation::<ChildOf>(e1);
+        let spawned = world.spawn().insert(Position(4)).id();
+        world.despawn(e1);
+        *world.get_resource_mut::<Frame>().unwrap() = Frame(2);
+        *world.get_resource_mut::<usize>().unwrap() = 5;
+        let unregistered = world.spawn().insert(Unregistered).id();
+        assert!(matches!(
+            world.snapshot(),
+            Err(SnapshotError::ComponentNotRegistered { entity, .. }) if entity == unregistered
+        ));
+
+        world.restore(&snapshot);
+        assert_eq!(world.get::<Position>(e1), Some(&Position(1)));
+        assert_eq!(world.get::<Position>(e2), Some(&Position(2)));
+        assert_eq!(
+            world.get::<Velocity>(e2),
+            Some(&Velocity("two".to_string()))
+        );
+        assert_eq!(world.entities().len(), 2);
+        assert_eq!(
+            world.related_to::<ChildOf>(e1).collect::<Vec<_>>(),
+            vec![e2]
+        );
+        assert_eq!(world.get_resource::<Frame>(), Some(&Frame(1)));
+        // resources that were not registered are left untouched
+        assert_eq!(world.get_resource::<usize>(), Some(&5));
+        let mut query = world.query::<(Entity, &Position)>();
+        let mut positions = query.iter(&world).collect::<Vec<_>>();
+        positions.sort_by_key(|(entity, _)| *entity);
+        assert_eq!(positions, vec![(e1, &Position(1)), (e2, &Position(2))]);
+
+        // entity ids are allocated exactly like they were after the snapshot was taken
+        assert_eq!(world.spawn().insert(Position(4)).id(), spawned);
+        world.restore(&snapshot);
+        world.remove_resource::<Frame>();
+        world.restore(&snapshot);
+        assert_eq!(world.get_resource::<Frame>(), Some(&Frame(1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "can only be restored into the World it was taken from")]
+    fn restore_snapshot_of_other_world() {
+        let snapshot = World::new().snapshot().unwrap();
+        World::new().restore(&snapshot);
+    }
 }
@@ -0,0 +1,609 @@
+use crate::{
+    component::{Component, ComponentDescriptor, ComponentId, StorageType},
+    entity::Entity,
+    system::{
+        BoxedSystem, RegisteredSystem, ResMut, ResMutState, SystemParam, SystemParamFetch,
+        SystemParamState, SystemState,
+    },
+    world::World,
+};
+use bevy_utils::HashSet;
+use parking_lot::Mutex;
+use std::{
+    any::TypeId,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+/// A component that points from an entity to the next entity an event should propagate to, such
+/// as a "parent" component.
+///
+/// See [`World::trigger_propagated`].
+pub trait Traversal: Component {
+    /// Returns the entity the event should propagate to next, if any.
+    fn traverse(&self) -> Option<Entity>;
+}
+
+/// A [`SystemParam`] that gives an observer system access to the event it was triggered with.
+///
+/// Observers are registered with [`World::observe`] (to observe every event of type `E`) or
+/// [`EntityMut::observe`](crate::world::EntityMut::observe) (to observe events of type `E`
+/// targeting one entity). They run immediately when an event is triggered with [`World::trigger`],
+/// [`World::trigger_entity`], [`World::trigger_propagated`] or their
+/// [`Commands`](crate::system::Commands) equivalents.
+///
+/// ```
+/// use bevy_ecs::{observer::Trigger, prelude::*};
+///
+/// struct Damage(u32);
+/// struct Health(u32);
+///
+/// fn apply_damage(trigger: Trigger<Damage>, mut healths: Query<&mut Health>) {
+///     let mut health = healths.get_mut(trigger.entity().unwrap()).unwrap();
+///     health.0 = health.0.saturating_sub(trigger.0);
+/// }
+///
+/// let mut world = World::new();
+/// let player = world.spawn().insert(Health(10)).id();
+/// world.entity_mut(player).observe::<Damage>(apply_damage.system());
+///
+/// world.trigger_entity(Damage(3), player);
+/// assert_eq!(world.get::<Health>(player).unwrap().0, 7);
+/// ```
+pub struct Trigger<'a, E: Component> {
+    current: ResMut<'a, CurrentTrigger<E>>,
+}
+
+impl<'a, E: Component> Trigger<'a, E> {
+    /// Returns the event.
+    #[inline]
+    pub fn event(&self) -> &E {
+        &self.current.event
+    }
+
+    /// Returns the event mutably. Changes are visible to the observers that run after this one.
+    #[inline]
+    pub fn event_mut(&mut self) -> &mut E {
+        &mut self.current.event
+    }
+
+    /// Returns the entity the event currently targets. While an event propagates, this is the
+    /// entity it has propagated to. Returns `None` for events triggered without a target.
+    #[inline]
+    pub fn entity(&self) -> Option<Entity> {
+        self.current.entity
+    }
+
+    /// Sets whether the event should continue to propagate once the observers of the current
+    /// entity have run. Propagation is enabled by default for events triggered with
+    /// [`World::trigger_propagated`].
+    #[inline]
+    pub fn propagate(&mut self, propagate: bool) {
+        self.current.propagate = propagate;
+    }
+}
+
+impl<'a, E: Component> Deref for Trigger<'a, E> {
+    type Target = E;
+
+    #[inline]
+    fn deref(&self) -> &E {
+        self.event()
+    }
+}
+
+impl<'a, E: Component> DerefMut for Trigger<'a, E> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut E {
+        self.event_mut()
+    }
+}
+
+/// The [`SystemParamState`] of [`Trigger<E>`].
+pub struct TriggerState<E>(ResMutState<CurrentTrigger<E>>);
+
+impl<'a, E: Component> SystemParam for Trigger<'a, E> {
+    type Fetch = TriggerState<E>;
+}
+
+// SAFE: access to the CurrentTrigger<E> resource is registered by ResMutState
+unsafe impl<E: Component> SystemParamState for TriggerState<E> {
+    type Config = ();
+
+    fn init(world: &mut World, system_state: &mut SystemState, _config: Self::Config) -> Self {
+        Self(ResMutState::init(world, system_state, ()))
+    }
+
+    fn default_config() {}
+}
+
+impl<'a, E: Component> SystemParamFetch<'a> for TriggerState<E> {
+    type Item = Trigger<'a, E>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'a mut Self,
+        system_state: &'a SystemState,
+        world: &'a World,
+        change_tick: u32,
+    ) -> Self::Item {
+        if !world.contains_resource::<CurrentTrigger<E>>() {
+            panic!(
+                "Trigger<{}> in system {} can only be used by observers.",
+                std::any::type_name::<E>(),
+                system_state.name
+            );
+        }
+        Trigger {
+            current: ResMutState::get_param(&mut state.0, system_state, world, change_tick),
+        }
+    }
+}
+
+/// The event that is currently being triggered. Stored as a resource while observers run.
+struct CurrentTrigger<E> {
+    event: E,
+    entity: Option<Entity>,
+    propagate: bool,
+}
+
+/// The observers of events of type `E` that are not scoped to an entity.
+struct GlobalObservers<E> {
+    observers: Vec<Entity>,
+    marker: PhantomData<E>,
+}
+
+impl<E> Default for GlobalObservers<E> {
+    fn default() -> Self {
+        Self {
+            observers: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<E> Clone for GlobalObservers<E> {
+    fn clone(&self) -> Self {
+        Self {
+            observers: self.observers.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Marks an observer entity, created by [`World::observe`] or
+/// [`EntityMut::observe`](crate::world::EntityMut::observe).
+///
+/// Like [`Disabled`](crate::entity::Disabled) entities, observers are skipped by queries that don't
+/// mention `Observer` themselves, e.g. with `With<Observer>`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Observer;
+
+/// Stores the system of an observer entity.
+///
+/// Clones share the system, so that restoring a [`WorldSnapshot`](crate::world::WorldSnapshot)
+/// brings back the observers that existed when it was taken.
+struct ObserverState<E> {
+    /// `None` while the system is running.
+    system: Arc<Mutex<Option<RegisteredSystem>>>,
+    target: Option<Entity>,
+    marker: PhantomData<E>,
+}
+
+impl<E> Clone for ObserverState<E> {
+    fn clone(&self) -> Self {
+        Self {
+            system: self.system.clone(),
+            target: self.target,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// The observers scoped to an entity. Despawning the entity despawns them as well.
+#[derive(Clone)]
+struct ObservedBy(Vec<Entity>);
+
+/// Turns `observer` into an observer of events of type `E`, optionally scoped to `target`.
+pub(crate) fn insert_observer<E: Component>(
+    world: &mut World,
+    observer: Entity,
+    target: Option<Entity>,
+    system: BoxedSystem,
+) {
+    init_observer_components::<E>(world);
+    let system = RegisteredSystem::new(world, system);
+    world.entity_mut(observer).insert_bundle((
+        ObserverState::<E> {
+            system: Arc::new(Mutex::new(Some(system))),
+            target,
+            marker: PhantomData,
+        },
+        Observer,
+    ));
+    if let Some(target) = target {
+        let mut target = world.entity_mut(target);
+        if let Some(mut observed_by) = target.get_mut::<ObservedBy>() {
+            observed_by.0.push(observer);
+        } else {
+            target.insert(ObservedBy(vec![observer]));
+        }
+    } else {
+        world
+            .get_resource_or_insert_with(GlobalObservers::<E>::default)
+            .observers
+            .push(observer);
+    }
+}
+
+fn init_observer_components<E: Component>(world: &mut World) {
+    if world
+        .components
+        .get_id(TypeId::of::<ObserverState<E>>())
+        .is_none()
+    {
+        world
+            .register_component(
+                ComponentDescriptor::new::<ObserverState<E>>(StorageType::SparseSet)
+                    .on_remove(remove_observer::<E>)
+                    .world_local(),
+            )
+            .unwrap();
+        world.register_snapshot_component::<ObserverState<E>>();
+        world.register_snapshot_resource::<GlobalObservers<E>>();
+        world.restore_hooks.push(retain_global_observers::<E>);
+    }
+    if world
+        .components
+        .get_id(TypeId::of::<ObservedBy>())
+        .is_none()
+    {
+        world
+            .register_component(
+                ComponentDescriptor::new::<ObservedBy>(StorageType::SparseSet)
+                    .on_remove(despawn_observers)
+                    .world_local(),
+            )
+            .unwrap();
+        world.register_snapshot_component::<ObservedBy>();
+    }
+}
+
+/// Restore hook that forgets the global observers of `E` spawned after the restored snapshot was
+/// taken, in case the snapshot didn't capture [`GlobalObservers<E>`].
+fn retain_global_observers<E: Component>(world: &mut World) {
+    if let Some(mut global) = world.remove_resource::<GlobalObservers<E>>() {
+        global.observers.retain(|observer| {
+            world
+                .get::<ObserverState<E>>(*observer)
+                .map_or(false, |state| state.target.is_none())
+        });
+        world.insert_resource(global);
+    }
+}
+
+/// `on_remove` hook of [`ObserverState`] that unregisters the observer.
+fn remove_observer<E: Component>(world: &mut World, observer: Entity, _: ComponentId) {
+    let target = world.get::<ObserverState<E>>(observer).unwrap().target;
+    if let Some(target) = target {
+        if let Some(mut observed_by) = world.get_mut::<ObservedBy>(target) {
+            observed_by.0.retain(|entity| *entity != observer);
+        }
+    } else if let Some(mut global) = world.get_resource_mut::<GlobalObservers<E>>() {
+        global.observers.retain(|entity| *entity != observer);
+    }
+}
+
+/// `on_remove` hook of [`ObservedBy`] that despawns the observers of an entity.
+fn despawn_observers(world: &mut World, entity: Entity, _: ComponentId) {
+    let observers = world.get::<ObservedBy>(entity).unwrap().0.clone();
+    for observer in observers {
+        world.despawn(observer);
+    }
+}
+
+fn traverse<T: Traversal>(world: &World, entity: Entity) -> Option<Entity> {
+    world.get::<T>(entity).and_then(T::traverse)
+}
+
+/// Runs the observers of `event`: first the observers of `entity`, then (if the event propagates)
+/// the observers of each entity returned by `traversal`, and finally the global observers. An
+/// entity reached twice by `traversal` ends the propagation.
+pub(crate) fn trigger<E: Component>(
+    world: &mut World,
+    event: E,
+    entity: Option<Entity>,
+    traversal: Option<fn(&World, Entity) -> Option<Entity>>,
+) {
+    // events of the same type can be triggered by observers, so the outer trigger is put aside
+    let outer = world.remove_resource::<CurrentTrigger<E>>();
+    world.insert_resource(CurrentTrigger {
+        event,
+        entity,
+        propagate: traversal.is_some(),
+    });
+
+    // guards against traversal cycles, e.g. an entity that is its own ancestor
+    let mut visited = HashSet::default();
+    let mut current = entity;
+    while let Some(entity) = current {
+        if !visited.insert(entity) {
+            break;
+        }
+        let observers = world
+            .get::<ObservedBy>(entity)
+            .map(|observed_by| observed_by.0.clone())
+            .unwrap_or_default();
+        for observer in observers {
+            run_observer::<E>(world, observer);
+        }
+        let propagate = world.get_resource::<CurrentTrigger<E>>().unwrap().propagate;
+        current = traversal
+            .filter(|_| propagate)
+            .and_then(|traversal| traversal(world, entity));
+        if current.is_some() {
+            world
+                .get_resource_mut::<CurrentTrigger<E>>()
+                .unwrap()
+                .entity = current;
+        }
+    }
+
+    world
+        .get_resource_mut::<CurrentTrigger<E>>()
+        .unwrap()
+        .entity = entity;
+    let observers = world
+        .get_resource::<GlobalObservers<E>>()
+        .map(|global| global.observers.clone())
+        .unwrap_or_default();
+    for observer in observers {
+        run_observer::<E>(world, observer);
+    }
+
+    world.remove_resource::<CurrentTrigger<E>>();
+    if let Some(outer) = outer {
+        world.insert_resource(outer);
+    }
+}
+
+/// Triggers `event` on `entity` and propagates it along `T`.
+pub(crate) fn trigger_propagated<T: Traversal, E: Component>(
+    world: &mut World,
+    event: E,
+    entity: Entity,
+) {
+    trigger(world, event, Some(entity), Some(traverse::<T>));
+}
+
+fn run_observer<E: Component>(world: &mut World, observer: Entity) {
+    let slot = match world.get::<ObserverState<E>>(observer) {
+        Some(state) => state.system.clone(),
+        None => return,
+    };
+    // an observer that is already running does not observe the events it triggers itself
+    let mut system = match slot.lock().take() {
+        Some(system) => system,
+        None => return,
+    };
+    system.run(world);
+    // put back even if the observer despawned itself, as a snapshot may still refer to it
+    *slot.lock() = Some(system);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        observer::{Observer, Traversal, Trigger},
+        prelude::*,
+        system::CommandQueue,
+    };
+
+    struct Click(&'static str);
+    struct Parent(Entity);
+    #[derive(Default)]
+    struct Log(Vec<(&'static str, Option<Entity>)>);
+
+    impl Traversal for Parent {
+        fn traverse(&self) -> Option<Entity> {
+            Some(self.0)
+        }
+    }
+
+    fn log(trigger: &mut Trigger<Click>, log: &mut Log, name: &'static str) {
+        log.0.push((name, trigger.entity()));
+        if trigger.0 == "stop" && name == "child" {
+            trigger.propagate(false);
+        }
+    }
+
+    fn on_global(mut trigger: Trigger<Click>, mut log: ResMut<Log>) {
+        self::log(&mut trigger, &mut log, "global");
+    }
+
+    fn on_a(mut trigger: Trigger<Click>, mut log: ResMut<Log>) {
+        self::log(&mut trigger, &mut log, "a");
+    }
+
+    fn on_root(mut trigger: Trigger<Click>, mut log: ResMut<Log>) {
+        self::log(&mut trigger, &mut log, "root");
+    }
+
+    fn on_child(mut trigger: Trigger<Click>, mut log: ResMut<Log>) {
+        self::log(&mut trigger, &mut log, "child");
+    }
+
+    fn take_log(world: &mut World) -> Vec<(&'static str, Option<Entity>)> {
+        std::mem::take(&mut world.get_resource_mut::<Log>().unwrap().0)
+    }
+
+    #[test]
+    fn global_and_entity_observers() {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        world.observe::<Click>(on_global.system());
+        world.entity_mut(a).observe::<Click>(on_a.system());
+
+        world.trigger(Click(""));
+        assert_eq!(take_log(&mut world), vec![("global", None)]);
+        world.trigger_entity(Click(""), a);
+        assert_eq!(
+            take_log(&mut world),
+            vec![("a", Some(a)), ("global", Some(a))]
+        );
+        world.trigger_entity(Click(""), b);
+        assert_eq!(take_log(&mut world), vec![("global", Some(b))]);
+
+        // observers are skipped by queries that don't ask for them
+        assert_eq!(
+            world.query::<Entity>().iter(&world).collect::<Vec<_>>(),
+            vec![a, b]
+        );
+        assert_eq!(world.query::<&Observer>().iter(&world).count(), 2);
+
+        // despawning an entity despawns its observers
+        world.despawn(a);
+        assert_eq!(world.query::<&Observer>().iter(&world).count(), 1);
+        world.trigger(Click(""));
+        assert_eq!(take_log(&mut world), vec![("global", None)]);
+    }
+
+    #[test]
+    fn propagation() {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let root = world.spawn().id();
+        let parent = world.spawn().insert(Parent(root)).id();
+        let child = world.spawn().insert(Parent(parent)).id();
+        world.entity_mut(root).observe::<Click>(on_root.system());
+        world.entity_mut(child).observe::<Click>(on_child.system());
+
+        world.trigger_propagated::<Parent, _>(Click(""), child);
+        assert_eq!(
+            take_log(&mut world),
+            vec![("child", Some(child)), ("root", Some(root))]
+        );
+        world.trigger_propagated::<Parent, _>(Click("stop"), child);
+        assert_eq!(take_log(&mut world), vec![("child", Some(child))]);
+        world.trigger_entity(Click(""), child);
+        assert_eq!(take_log(&mut world), vec![("child", Some(child))]);
+    }
+
+    #[test]
+    fn propagation_cycle() {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let a = world.spawn().id();
+        let b = world.spawn().insert(Parent(a)).id();
+        world.entity_mut(a).insert(Parent(b));
+        world.entity_mut(a).observe::<Click>(on_a.system());
+
+        world.trigger_propagated::<Parent, _>(Click(""), a);
+        assert_eq!(take_log(&mut world), vec![("a", Some(a))]);
+    }
+
+    #[test]
+    fn move_observed_entity() {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let a = world.spawn().insert(Log::default()).id();
+        world.entity_mut(a).observe::<Click>(on_a.system());
+        let observer = world
+            .query::<(Entity, &Observer)>()
+            .iter(&world)
+            .next()
+            .unwrap()
+            .0;
+        world.observe::<Click>(on_global.system());
+
+        // the observers of an entity stay behind, and are despawned with it
+        let mut destination = World::new();
+        destination.insert_resource(Log::default());
+        let moved = world.entity_mut(a).move_to(&mut destination);
+        assert!(world.get_entity(observer).is_none());
+        assert!(destination.get::<Log>(moved).is_some());
+        destination.trigger_entity(Click(""), moved);
+        assert!(take_log(&mut destination).is_empty());
+
+        // a moved observer is a plain entity in the destination world
+        let global = world
+            .query::<(Entity, &Observer)>()
+            .iter(&world)
+            .next()
+            .unwrap()
+            .0;
+        let moved = world.entity_mut(global).move_to(&mut destination);
+        assert_eq!(
+            destination.query::<&Observer>().iter(&destination).count(),
+            0
+        );
+        assert!(destination
+            .query::<Entity>()
+            .iter(&destination)
+            .any(|e| e == moved));
+        world.trigger(Click(""));
+        destination.trigger(Click(""));
+        assert!(take_log(&mut world).is_empty());
+        assert!(take_log(&mut destination).is_empty());
+    }
+
+    #[test]
+    fn snapshot_with_observers() {
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let a = world.spawn().id();
+        world.entity_mut(a).observe::<Click>(on_a.system());
+        let snapshot = world.snapshot().unwrap();
+
+        world.observe::<Click>(on_global.system());
+        world.despawn(a);
+        world.trigger(Click(""));
+        assert_eq!(take_log(&mut world), vec![("global", None)]);
+
+        // observers of the snapshot are back, and those spawned since then are gone
+        world.restore(&snapshot);
+        world.trigger_entity(Click(""), a);
+        world.trigger(Click(""));
+        assert_eq!(take_log(&mut world), vec![("a", Some(a))]);
+        assert_eq!(world.query::<&Observer>().iter(&world).count(), 1);
+
+        // a snapshot taken before any observer of the event existed
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let snapshot = world.snapshot().unwrap();
+        world.observe::<Click>(on_global.system());
+        world.restore(&snapshot);
+        world.observe::<Click>(on_a.system());
+        world.trigger(Click(""));
+        assert_eq!(take_log(&mut world), vec![("a", None)]);
+    }
+
+    #[test]
+    fn commands() {
+        fn click_on_spawn(trigger: Trigger<Click>, mut commands: Commands) {
+            if trigger.0 == "spawn" {
+                let entity = commands.spawn().id();
+                commands.trigger_entity(Click("spawned"), entity);
+            }
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Log::default());
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.observe::<Click>(on_global.system());
+        commands.observe::<Click>(click_on_spawn.system());
+        commands.trigger(Click("spawn"));
+        queue.apply(&mut world);
+
+        let log = take_log(&mut world);
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0], ("global", None));
+        // commands of an observer are applied before `trigger` returns
+        assert_eq!(log[1].0, "global");
+        assert!(log[1].1.is_some());
+    }
+}
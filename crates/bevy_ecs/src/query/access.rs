@@ -69,6 +69,18 @@ impl<T: SparseSetIndex> Access<T> {
         self.reads_all
     }
 
+    /// Returns the indices this `Access` reads but does not write.
+    pub fn reads(&self) -> impl Iterator<Item = T> + '_ {
+        self.reads_and_writes
+            .difference(&self.writes)
+            .map(SparseSetIndex::get_sparse_set_index)
+    }
+
+    /// Returns the indices this `Access` writes.
+    pub fn writes(&self) -> impl Iterator<Item = T> + '_ {
+        self.writes.ones().map(SparseSetIndex::get_sparse_set_index)
+    }
+
     /// Clears all recorded accesses.
     pub fn clear(&mut self) {
         self.reads_all = false;
@@ -160,6 +172,14 @@ impl<T: SparseSetIndex> FilteredAccess<T> {
         self.without.insert(index.sparse_set_index());
     }
 
+    /// Returns true if this access requires the presence or absence of `index`. This includes
+    /// reads and writes, as [`FilteredAccess::add_read`] and [`FilteredAccess::add_write`] also
+    /// require the presence of the component.
+    pub fn contains(&self, index: T) -> bool {
+        let index = index.sparse_set_index();
+        self.with.contains(index) || self.without.contains(index)
+    }
+
     pub fn is_compatible(&self, other: &FilteredAccess<T>) -> bool {
         if self.access.is_compatible(&other.access) {
             true
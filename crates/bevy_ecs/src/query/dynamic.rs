@@ -0,0 +1,603 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
+    component::{ComponentId, ComponentTicks, StorageType},
+    entity::Entity,
+    query::{Access, FilteredAccess, QueryEntityError},
+    storage::Table,
+    world::{World, WorldId},
+};
+use fixedbitset::FixedBitSet;
+use std::ptr::NonNull;
+
+/// Builds a query from [`ComponentId`]s that are only known at runtime, for example in scripting
+/// or editor tooling.
+///
+/// Components added with [`DynamicQuery::read`] and [`DynamicQuery::write`] are fetched, in the
+/// order they were added. [`DynamicQuery::with`] and [`DynamicQuery::without`] only filter the
+/// matched entities. Like other queries, dynamic queries skip
+/// [`Disabled`](crate::entity::Disabled) entities and [`Observer`](crate::observer::Observer)s
+/// unless they mention these markers.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::{prelude::*, query::DynamicQuery};
+/// struct Health(f32);
+///
+/// let mut world = World::new();
+/// world.spawn().insert(Health(10.0));
+/// let health_id = world.components().get_id(std::any::TypeId::of::<Health>()).unwrap();
+///
+/// let mut query = DynamicQuery::new().write(health_id).build(&world);
+/// for mut item in query.iter_mut(&mut world) {
+///     // SAFE: `health_id` is the id of `Health`
+///     let health = unsafe { item.get_mut(0).deref_mut::<Health>() };
+///     health.0 += 5.0;
+/// }
+/// # assert_eq!(world.query::<&Health>().iter(&world).next().unwrap().0, 15.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DynamicQuery {
+    fetches: Vec<(ComponentId, bool)>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+}
+
+impl DynamicQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fetches shared access to the component with the given id.
+    pub fn read(mut self, component_id: ComponentId) -> Self {
+        self.fetches.push((component_id, false));
+        self
+    }
+
+    /// Fetches mutable access to the component with the given id.
+    pub fn write(mut self, component_id: ComponentId) -> Self {
+        self.fetches.push((component_id, true));
+        self
+    }
+
+    /// Only matches entities that have the component with the given id.
+    pub fn with(mut self, component_id: ComponentId) -> Self {
+        self.with.push(component_id);
+        self
+    }
+
+    /// Only matches entities that do not have the component with the given id.
+    pub fn without(mut self, component_id: ComponentId) -> Self {
+        self.without.push(component_id);
+        self
+    }
+
+    /// Creates a [`DynamicQueryState`] for the given `world`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a component id is not registered in `world`, or if a component is fetched more
+    /// than once.
+    pub fn build(&self, world: &World) -> DynamicQueryState {
+        let mut fetches = Vec::with_capacity(self.fetches.len());
+        let mut component_access = FilteredAccess::default();
+        for &(component_id, mutable) in self.fetches.iter() {
+            let info = world
+                .components()
+                .get_info(component_id)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "DynamicQuery fetches {:?}, which is not registered in the World",
+                        component_id
+                    )
+                });
+            if component_access.access().has_read(component_id) {
+                panic!(
+                    "DynamicQuery fetches component {} more than once",
+                    info.name()
+                );
+            }
+            if mutable {
+                component_access.add_write(component_id);
+            } else {
+                component_access.add_read(component_id);
+            }
+            fetches.push(DynamicFetch {
+                component_id,
+                storage_type: info.storage_type(),
+                mutable,
+            });
+        }
+        for &component_id in self.with.iter() {
+            component_access.add_with(component_id);
+        }
+        for &component_id in self.without.iter() {
+            component_access.add_without(component_id);
+        }
+        let mut without = self.without.clone();
+        // queries skip disabled entities and observers, unless they explicitly ask for them
+        for hidden_id in world.hidden_component_ids() {
+            if !component_access.contains(hidden_id) {
+                without.push(hidden_id);
+            }
+        }
+
+        let mut state = DynamicQueryState {
+            world_id: world.id(),
+            archetype_generation: ArchetypeGeneration::new(usize::MAX),
+            fetches,
+            with: self.with.clone(),
+            without,
+            component_access,
+            archetype_component_access: Default::default(),
+            matched_archetypes: Default::default(),
+            matched_archetype_ids: Vec::new(),
+        };
+        state.validate_world_and_update_archetypes(world);
+        state
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DynamicFetch {
+    component_id: ComponentId,
+    storage_type: StorageType,
+    mutable: bool,
+}
+
+/// The state of a [`DynamicQuery`], which caches the archetypes it matches.
+///
+/// Its [`component_access`](DynamicQueryState::component_access) is a regular
+/// [`FilteredAccess`], so dynamic queries are checked for conflicts with typed queries when used
+/// as a [`DynamicQueryParam`](crate::system::DynamicQueryParam) in a system.
+pub struct DynamicQueryState {
+    world_id: WorldId,
+    archetype_generation: ArchetypeGeneration,
+    fetches: Vec<DynamicFetch>,
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+    pub(crate) component_access: FilteredAccess<ComponentId>,
+    pub(crate) archetype_component_access: Access<ArchetypeComponentId>,
+    matched_archetypes: FixedBitSet,
+    // NOTE: we maintain both a ArchetypeId bitset and a vec because iterating the vec is faster
+    matched_archetype_ids: Vec<ArchetypeId>,
+}
+
+impl DynamicQueryState {
+    #[inline]
+    pub fn component_access(&self) -> &FilteredAccess<ComponentId> {
+        &self.component_access
+    }
+
+    #[inline]
+    pub fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        &self.archetype_component_access
+    }
+
+    /// Returns true if this query does not fetch any component mutably.
+    pub fn is_read_only(&self) -> bool {
+        self.fetches.iter().all(|fetch| !fetch.mutable)
+    }
+
+    /// The ids of the archetypes this query has matched so far.
+    #[inline]
+    pub fn matched_archetype_ids(&self) -> &[ArchetypeId] {
+        &self.matched_archetype_ids
+    }
+
+    pub fn validate_world_and_update_archetypes(&mut self, world: &World) {
+        if world.id() != self.world_id {
+            panic!("Attempted to use a DynamicQueryState with a mismatched World. DynamicQueryStates can only be used with the World they were created from.");
+        }
+        let archetypes = world.archetypes();
+        let old_generation = self.archetype_generation;
+        let archetype_index_range = if old_generation == archetypes.generation() {
+            0..0
+        } else {
+            self.archetype_generation = archetypes.generation();
+            if old_generation.value() == usize::MAX {
+                0..archetypes.len()
+            } else {
+                old_generation.value()..archetypes.len()
+            }
+        };
+        for archetype_index in archetype_index_range {
+            self.new_archetype(&archetypes[ArchetypeId::new(archetype_index)]);
+        }
+    }
+
+    pub fn new_archetype(&mut self, archetype: &Archetype) {
+        if !self.matches_archetype(archetype) {
+            return;
+        }
+        for fetch in self.fetches.iter() {
+            if let Some(archetype_component_id) =
+                archetype.get_archetype_component_id(fetch.component_id)
+            {
+                if fetch.mutable {
+                    self.archetype_component_access
+                        .add_write(archetype_component_id);
+                } else {
+                    self.archetype_component_access
+                        .add_read(archetype_component_id);
+                }
+            }
+        }
+        let archetype_index = archetype.id().index();
+        if !self.matched_archetypes.contains(archetype_index) {
+            self.matched_archetypes.grow(archetype_index + 1);
+            self.matched_archetypes.set(archetype_index, true);
+            self.matched_archetype_ids.push(archetype.id());
+        }
+    }
+
+    fn matches_archetype(&self, archetype: &Archetype) -> bool {
+        self.fetches
+            .iter()
+            .all(|fetch| archetype.contains(fetch.component_id))
+            && self.with.iter().all(|&id| archetype.contains(id))
+            && !self.without.iter().any(|&id| archetype.contains(id))
+    }
+
+    /// Returns the components of `entity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this query fetches a component mutably. Use [`DynamicQueryState::get_mut`]
+    /// instead.
+    #[inline]
+    pub fn get<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+        entity: Entity,
+    ) -> Result<DynamicItem<'w, 's>, QueryEntityError> {
+        self.assert_read_only();
+        self.validate_world_and_update_archetypes(world);
+        // SAFE: query is read only
+        unsafe {
+            self.get_unchecked_manual(
+                world,
+                entity,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    #[inline]
+    pub fn get_mut<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+        entity: Entity,
+    ) -> Result<DynamicItem<'w, 's>, QueryEntityError> {
+        self.validate_world_and_update_archetypes(world);
+        // SAFE: query has unique world access
+        unsafe {
+            self.get_unchecked_manual(
+                world,
+                entity,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    pub unsafe fn get_unchecked_manual<'w, 's>(
+        &'s self,
+        world: &'w World,
+        entity: Entity,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Result<DynamicItem<'w, 's>, QueryEntityError> {
+        let location = world
+            .entities
+            .get(entity)
+            .ok_or(QueryEntityError::NoSuchEntity)?;
+        if !self
+            .matched_archetypes
+            .contains(location.archetype_id.index())
+        {
+            return Err(QueryEntityError::QueryDoesNotMatch);
+        }
+        let archetype = &world.archetypes[location.archetype_id];
+        Ok(DynamicItem {
+            world,
+            fetches: &self.fetches,
+            entity,
+            table: &world.storages().tables[archetype.table_id()],
+            table_row: archetype.entity_table_row(location.index),
+            last_change_tick,
+            change_tick,
+        })
+    }
+
+    /// Returns an iterator over the matched entities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this query fetches a component mutably. Use [`DynamicQueryState::iter_mut`]
+    /// instead.
+    #[inline]
+    pub fn iter<'w, 's>(&'s mut self, world: &'w World) -> DynamicQueryIter<'w, 's> {
+        self.assert_read_only();
+        self.validate_world_and_update_archetypes(world);
+        // SAFE: query is read only
+        unsafe {
+            self.iter_unchecked_manual(world, world.last_change_tick(), world.read_change_tick())
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut<'w, 's>(&'s mut self, world: &'w mut World) -> DynamicQueryIter<'w, 's> {
+        self.validate_world_and_update_archetypes(world);
+        // SAFE: query has unique world access
+        unsafe {
+            self.iter_unchecked_manual(world, world.last_change_tick(), world.read_change_tick())
+        }
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsound.
+    #[inline]
+    pub unsafe fn iter_unchecked_manual<'w, 's>(
+        &'s self,
+        world: &'w World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> DynamicQueryIter<'w, 's> {
+        DynamicQueryIter {
+            world,
+            fetches: &self.fetches,
+            archetype_ids: self.matched_archetype_ids.iter(),
+            archetype: None,
+            table: None,
+            index: 0,
+            last_change_tick,
+            change_tick,
+        }
+    }
+
+    fn assert_read_only(&self) {
+        if !self.is_read_only() {
+            panic!("DynamicQuery fetches components mutably, which requires mutable World access.");
+        }
+    }
+}
+
+/// An iterator over the entities matched by a [`DynamicQueryState`].
+pub struct DynamicQueryIter<'w, 's> {
+    world: &'w World,
+    fetches: &'s [DynamicFetch],
+    archetype_ids: std::slice::Iter<'s, ArchetypeId>,
+    archetype: Option<&'w Archetype>,
+    table: Option<&'w Table>,
+    index: usize,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, 's> Iterator for DynamicQueryIter<'w, 's> {
+    type Item = DynamicItem<'w, 's>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let (Some(archetype), Some(table)) = (self.archetype, self.table) {
+                if self.index < archetype.len() {
+                    let item = DynamicItem {
+                        world: self.world,
+                        fetches: self.fetches,
+                        entity: archetype.entities()[self.index],
+                        table,
+                        table_row: archetype.entity_table_row(self.index),
+                        last_change_tick: self.last_change_tick,
+                        change_tick: self.change_tick,
+                    };
+                    self.index += 1;
+                    return Some(item);
+                }
+            }
+            let archetype = &self.world.archetypes[*self.archetype_ids.next()?];
+            self.archetype = Some(archetype);
+            self.table = Some(&self.world.storages().tables[archetype.table_id()]);
+            self.index = 0;
+        }
+    }
+}
+
+/// The components of a single entity matched by a [`DynamicQueryState`], indexed in the order
+/// they were added to the [`DynamicQuery`].
+pub struct DynamicItem<'w, 's> {
+    world: &'w World,
+    fetches: &'s [DynamicFetch],
+    entity: Entity,
+    table: &'w Table,
+    table_row: usize,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, 's> DynamicItem<'w, 's> {
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// The number of fetched components.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fetches.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fetches.is_empty()
+    }
+
+    /// The id of the fetched component at `index`.
+    #[inline]
+    pub fn component_id(&self, index: usize) -> ComponentId {
+        self.fetches[index].component_id
+    }
+
+    /// Returns shared access to the fetched component at `index`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Ptr<'_> {
+        // SAFE: the component is part of the query's access, and `&self` prevents mutable access
+        // through this item
+        let (ptr, ticks) = unsafe { self.fetch(index) };
+        Ptr {
+            ptr,
+            // SAFE: see above
+            ticks: unsafe { &*ticks },
+            last_change_tick: self.last_change_tick,
+            change_tick: self.change_tick,
+        }
+    }
+
+    /// Returns mutable access to the fetched component at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component was added with [`DynamicQuery::read`].
+    #[inline]
+    pub fn get_mut(&mut self, index: usize) -> PtrMut<'_> {
+        if !self.fetches[index].mutable {
+            panic!(
+                "Component {:?} is fetched by the DynamicQuery with read access only.",
+                self.fetches[index].component_id
+            );
+        }
+        // SAFE: the component is part of the query's mutable access, and `&mut self` prevents any
+        // other access through this item
+        let (ptr, ticks) = unsafe { self.fetch(index) };
+        PtrMut {
+            ptr,
+            // SAFE: see above
+            ticks: unsafe { &mut *ticks },
+            last_change_tick: self.last_change_tick,
+            change_tick: self.change_tick,
+        }
+    }
+
+    unsafe fn fetch(&self, index: usize) -> (NonNull<u8>, *mut ComponentTicks) {
+        let fetch = &self.fetches[index];
+        match fetch.storage_type {
+            StorageType::Table => {
+                let column = self.table.get_column(fetch.component_id).unwrap();
+                (
+                    NonNull::new_unchecked(column.get_unchecked(self.table_row)),
+                    column.get_ticks_unchecked(self.table_row),
+                )
+            }
+            StorageType::SparseSet => {
+                let (ptr, ticks) = self
+                    .world
+                    .storages()
+                    .sparse_sets
+                    .get(fetch.component_id)
+                    .unwrap()
+                    .get_with_ticks(self.entity)
+                    .unwrap();
+                (NonNull::new_unchecked(ptr), ticks)
+            }
+        }
+    }
+}
+
+/// Shared access to a component fetched by a [`DynamicQuery`] or
+/// [`EntityRef::get_by_id`](crate::world::EntityRef::get_by_id).
+pub struct Ptr<'a> {
+    pub(crate) ptr: NonNull<u8>,
+    pub(crate) ticks: &'a ComponentTicks,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+}
+
+impl<'a> Ptr<'a> {
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// # Safety
+    /// `T` must be the type of the fetched component.
+    #[inline]
+    pub unsafe fn deref<T>(self) -> &'a T {
+        &*self.ptr.as_ptr().cast::<T>()
+    }
+
+    /// Returns true if (and only if) this component been added since the last execution of this
+    /// system.
+    #[inline]
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_change_tick, self.change_tick)
+    }
+
+    /// Returns true if (and only if) this component been changed since the last execution of this
+    /// system.
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.ticks
+            .is_changed(self.last_change_tick, self.change_tick)
+    }
+}
+
+/// Mutable access to a component fetched by a [`DynamicQuery`] or
+/// [`EntityMut::get_mut_by_id`](crate::world::EntityMut::get_mut_by_id). Taking a mutable pointer
+/// marks the component as changed.
+pub struct PtrMut<'a> {
+    pub(crate) ptr: NonNull<u8>,
+    pub(crate) ticks: &'a mut ComponentTicks,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+}
+
+impl<'a> PtrMut<'a> {
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Returns a mutable pointer to the component and marks it as changed.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.set_changed();
+        self.ptr.as_ptr()
+    }
+
+    /// Marks the component as changed and returns a mutable reference to it.
+    ///
+    /// # Safety
+    /// `T` must be the type of the fetched component.
+    #[inline]
+    pub unsafe fn deref_mut<T>(mut self) -> &'a mut T {
+        self.set_changed();
+        &mut *self.ptr.as_ptr().cast::<T>()
+    }
+
+    /// Manually marks the component as changed.
+    #[inline]
+    pub fn set_changed(&mut self) {
+        self.ticks.set_changed(self.change_tick);
+    }
+
+    /// Returns true if (and only if) this component been added since the last execution of this
+    /// system.
+    #[inline]
+    pub fn is_added(&self) -> bool {
+        self.ticks.is_added(self.last_change_tick, self.change_tick)
+    }
+
+    /// Returns true if (and only if) this component been changed since the last execution of this
+    /// system.
+    #[inline]
+    pub fn is_changed(&self) -> bool {
+        self.ticks
+            .is_changed(self.last_change_tick, self.change_tick)
+    }
+}
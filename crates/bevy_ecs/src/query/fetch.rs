@@ -705,6 +705,16 @@ impl<T: Component> ChangeTrackers<T> {
         self.component_ticks
             .is_changed(self.last_change_tick, self.change_tick)
     }
+
+    /// Returns true if this component has been added since the given change tick.
+    pub fn is_added_since(&self, tick: u32) -> bool {
+        self.component_ticks.is_added(tick, self.change_tick)
+    }
+
+    /// Returns true if this component has been changed since the given change tick.
+    pub fn is_changed_since(&self, tick: u32) -> bool {
+        self.component_ticks.is_changed(tick, self.change_tick)
+    }
 }
 
 impl<T: Component> WorldQuery for ChangeTrackers<T> {
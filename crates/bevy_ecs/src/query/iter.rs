@@ -1,6 +1,7 @@
 use crate::{
     archetype::{ArchetypeId, Archetypes},
-    query::{Fetch, FilterFetch, QueryState, WorldQuery},
+    entity::Entity,
+    query::{Fetch, FilterFetch, QueryState, ReadOnlyFetch, WorldQuery},
     storage::{TableId, Tables},
     world::World,
 };
@@ -157,3 +158,132 @@ impl<'w, 's, Q: WorldQuery> ExactSizeIterator for QueryIter<'w, 's, Q, ()> {
             .sum()
     }
 }
+
+/// An iterator over every unordered combination of `K` distinct query results, created by
+/// [`Query::iter_combinations`](crate::system::Query::iter_combinations) and
+/// [`Query::iter_combinations_mut`](crate::system::Query::iter_combinations_mut).
+///
+/// For read-only queries this is an [`Iterator`]. Mutable queries must use
+/// [`QueryCombinationIter::fetch_next`] instead, which makes sure the results of one combination are
+/// dropped before the next one (that may contain the same entities) is fetched.
+pub struct QueryCombinationIter<'w, 's, Q: WorldQuery, F: WorldQuery, const K: usize>
+where
+    F::Fetch: FilterFetch,
+{
+    world: &'w World,
+    query_state: &'s QueryState<Q, F>,
+    entities: Vec<Entity>,
+    indices: [usize; K],
+    started: bool,
+    last_change_tick: u32,
+    change_tick: u32,
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery, const K: usize> QueryCombinationIter<'w, 's, Q, F, K>
+where
+    F::Fetch: FilterFetch,
+{
+    pub(crate) unsafe fn new(
+        world: &'w World,
+        query_state: &'s QueryState<Q, F>,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Self {
+        let entities = query_state.matching_entities(world, last_change_tick, change_tick);
+
+        let mut indices = [0; K];
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = i;
+        }
+        QueryCombinationIter {
+            world,
+            query_state,
+            entities,
+            indices,
+            started: false,
+            last_change_tick,
+            change_tick,
+        }
+    }
+
+    /// Fetches the next combination of query results. The results borrow the iterator, so unlike
+    /// [`Iterator::next`] this can be used with mutable queries.
+    #[inline]
+    pub fn fetch_next(&mut self) -> Option<[<Q::Fetch as Fetch<'_>>::Item; K]> {
+        if !self.advance() {
+            return None;
+        }
+        // SAFE: the entities of a combination are distinct, and the results borrow `self` mutably,
+        // so they cannot alias the results of another combination
+        unsafe { Some(self.fetch_unchecked(self.world)) }
+    }
+
+    /// Moves `indices` to the next combination, in lexicographic order.
+    fn advance(&mut self) -> bool {
+        let len = self.entities.len();
+        if K == 0 || K > len {
+            return false;
+        }
+        if !self.started {
+            self.started = true;
+            return true;
+        }
+        advance_combination(&mut self.indices, len)
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness, nor that the returned results do not
+    /// alias previously returned results.
+    unsafe fn fetch_unchecked<'a>(&self, world: &'a World) -> [<Q::Fetch as Fetch<'a>>::Item; K] {
+        self.indices.map(|index| {
+            match self.query_state.get_unchecked_manual(
+                world,
+                self.entities[index],
+                self.last_change_tick,
+                self.change_tick,
+            ) {
+                Ok(item) => item,
+                Err(_) => unreachable!("entities matching the query cannot change while iterating"),
+            }
+        })
+    }
+}
+
+/// Moves `indices`, a combination of distinct indices into `0..len`, to the next combination in
+/// lexicographic order. Returns false if it was the last one.
+pub(crate) fn advance_combination<const K: usize>(indices: &mut [usize; K], len: usize) -> bool {
+    // find the rightmost index that can still be incremented
+    let mut i = K;
+    loop {
+        if i == 0 {
+            return false;
+        }
+        i -= 1;
+        if indices[i] < len - K + i {
+            break;
+        }
+    }
+    indices[i] += 1;
+    for j in i + 1..K {
+        indices[j] = indices[j - 1] + 1;
+    }
+    true
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery, const K: usize> Iterator
+    for QueryCombinationIter<'w, 's, Q, F, K>
+where
+    Q::Fetch: ReadOnlyFetch,
+    F::Fetch: FilterFetch,
+{
+    type Item = [<Q::Fetch as Fetch<'w>>::Item; K];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.advance() {
+            return None;
+        }
+        // SAFE: the query is read only
+        unsafe { Some(self.fetch_unchecked(self.world)) }
+    }
+}
@@ -1,21 +1,27 @@
 mod access;
+mod dynamic;
 mod fetch;
 mod filter;
 mod iter;
+mod par_iter;
 mod state;
 
 pub use access::*;
+pub use dynamic::*;
 pub use fetch::*;
 pub use filter::*;
 pub use iter::*;
+pub use par_iter::*;
 pub use state::*;
 
 #[cfg(test)]
 mod tests {
     use crate::{
         component::{ComponentDescriptor, StorageType},
+        query::{DynamicQuery, QueryEntityError},
         world::World,
     };
+    use std::any::TypeId;
 
     #[derive(Debug, Eq, PartialEq)]
     struct A(usize);
@@ -57,4 +63,121 @@ mod tests {
         let values = world.query::<&B>().iter(&world).collect::<Vec<&B>>();
         assert_eq!(values, vec![&B(3)]);
     }
+
+    #[test]
+    fn dynamic_query() {
+        let mut world = World::new();
+        let a_id = world
+            .register_component(ComponentDescriptor::new::<A>(StorageType::SparseSet))
+            .unwrap();
+        let e1 = world.spawn().insert_bundle((A(1), B(1))).id();
+        let e2 = world.spawn().insert_bundle((A(2),)).id();
+        let e3 = world.spawn().insert_bundle((B(3),)).id();
+        let b_id = world.components().get_id(TypeId::of::<B>()).unwrap();
+
+        let mut query = DynamicQuery::new().read(a_id).without(b_id).build(&world);
+        let values = query
+            .iter(&world)
+            // SAFE: a_id is the id of A
+            .map(|item| (item.entity(), unsafe { item.get(0).deref::<A>().0 }))
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![(e2, 2)]);
+
+        let mut query = DynamicQuery::new().write(b_id).read(a_id).build(&world);
+        for mut item in query.iter_mut(&mut world) {
+            // SAFE: a_id is the id of A and b_id is the id of B
+            let a = unsafe { item.get(1).deref::<A>() }.0;
+            let b = unsafe { item.get_mut(0).deref_mut::<B>() };
+            b.0 += a * 10;
+        }
+        let values = world.query::<&B>().iter(&world).collect::<Vec<&B>>();
+        assert_eq!(values, vec![&B(11), &B(3)]);
+
+        let item = query.get_mut(&mut world, e1).unwrap();
+        assert_eq!(item.len(), 2);
+        assert_eq!(item.component_id(1), a_id);
+        assert!(query.get_mut(&mut world, e3).is_err());
+
+        world.despawn(e2);
+        let query = DynamicQuery::new().read(a_id).build(&world);
+        assert!(query.component_access().access().has_read(a_id));
+        assert!(!query.component_access().access().has_write(a_id));
+    }
+
+    #[test]
+    #[should_panic]
+    fn dynamic_query_iter_requires_mutable_world() {
+        let mut world = World::new();
+        world.spawn().insert(A(1));
+        let a_id = world.components().get_id(TypeId::of::<A>()).unwrap();
+        DynamicQuery::new().write(a_id).build(&world).iter(&world);
+    }
+
+    #[test]
+    fn query_get_many() {
+        let mut world = World::new();
+        let e1 = world.spawn().insert_bundle((A(1), B(1))).id();
+        let e2 = world.spawn().insert_bundle((A(2),)).id();
+        let e3 = world.spawn().insert_bundle((B(3),)).id();
+
+        let mut query = world.query::<&A>();
+        assert_eq!(query.get_many(&world, [e2, e1]), Ok([&A(2), &A(1)]));
+        assert_eq!(query.get_many(&world, [e1, e1]), Ok([&A(1), &A(1)]));
+        assert_eq!(
+            query.get_many(&world, [e1, e3]),
+            Err(QueryEntityError::QueryDoesNotMatch)
+        );
+
+        let mut query = world.query::<&mut A>();
+        assert_eq!(
+            query.get_many_mut(&mut world, [e1, e2, e1]).err(),
+            Some(QueryEntityError::AliasedMutability(e1))
+        );
+        let [mut a1, mut a2] = query.get_many_mut(&mut world, [e1, e2]).unwrap();
+        std::mem::swap(&mut a1.0, &mut a2.0);
+        assert_eq!(world.get::<A>(e1), Some(&A(2)));
+        assert_eq!(world.get::<A>(e2), Some(&A(1)));
+    }
+
+    #[test]
+    fn query_iter_combinations() {
+        let mut world = World::new();
+        world
+            .register_component(ComponentDescriptor::new::<B>(StorageType::SparseSet))
+            .unwrap();
+        world.spawn().insert_bundle((A(1), B(1)));
+        world.spawn().insert_bundle((A(2),));
+        world.spawn().insert_bundle((A(3), B(3)));
+        world.spawn().insert_bundle((A(4),));
+
+        let mut query = world.query::<&A>();
+        let pairs = query
+            .iter_combinations(&world)
+            .map(|[a, b]| (a.0, b.0))
+            .collect::<Vec<_>>();
+        assert_eq!(pairs.len(), 6);
+        for (a, b) in pairs.iter() {
+            assert_ne!(a, b);
+            assert!(!pairs.contains(&(*b, *a)));
+        }
+        assert_eq!(query.iter_combinations::<4>(&world).count(), 1);
+        assert_eq!(query.iter_combinations::<5>(&world).count(), 0);
+        assert_eq!(query.iter_combinations::<0>(&world).count(), 0);
+
+        let mut query = world.query::<&mut A>();
+        let mut combinations = query.iter_combinations_mut(&mut world);
+        while let Some([mut a, mut b, mut c]) = combinations.fetch_next() {
+            a.0 += 1;
+            b.0 += 1;
+            c.0 += 1;
+        }
+        let mut values = world
+            .query::<&A>()
+            .iter(&world)
+            .map(|a| a.0)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        // every entity is part of 3 of the 4 triples
+        assert_eq!(values, vec![4, 5, 6, 7]);
+    }
 }
@@ -0,0 +1,244 @@
+use crate::{
+    query::{Fetch, FilterFetch, QueryState, WorldQuery},
+    world::World,
+};
+use bevy_tasks::TaskPool;
+
+/// The number of batches [`BatchSize::Auto`] aims to give each thread, so that threads that finish
+/// early can pick up the remaining work.
+const AUTO_BATCHES_PER_THREAD: usize = 4;
+
+/// Decides how many query results each task of a parallel query processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSize {
+    /// Each task processes at most this many results.
+    Fixed(usize),
+    /// Picks the batch size from the number of matched entities and the thread count of the
+    /// [`TaskPool`].
+    Auto,
+}
+
+impl Default for BatchSize {
+    fn default() -> Self {
+        BatchSize::Auto
+    }
+}
+
+impl From<usize> for BatchSize {
+    fn from(batch_size: usize) -> Self {
+        BatchSize::Fixed(batch_size)
+    }
+}
+
+impl BatchSize {
+    /// Returns the batch size to use for `len` results on a task pool with `thread_num` threads.
+    pub fn get(self, len: usize, thread_num: usize) -> usize {
+        match self {
+            BatchSize::Fixed(batch_size) => batch_size.max(1),
+            BatchSize::Auto => {
+                let batch_count = thread_num.max(1) * AUTO_BATCHES_PER_THREAD;
+                (len / batch_count).max(1)
+            }
+        }
+    }
+}
+
+/// A parallel iterator over query results, created by
+/// [`Query::par_iter`](crate::system::Query::par_iter) and
+/// [`Query::par_iter_mut`](crate::system::Query::par_iter_mut).
+///
+/// Results are split into batches, which run as separate tasks on the [`TaskPool`]. The batch size
+/// defaults to [`BatchSize::Auto`].
+pub struct QueryParIter<'w, 's, Q: WorldQuery, F: WorldQuery>
+where
+    F::Fetch: FilterFetch,
+{
+    pub(crate) world: &'w World,
+    pub(crate) state: &'s QueryState<Q, F>,
+    pub(crate) task_pool: &'s TaskPool,
+    pub(crate) batch_size: BatchSize,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery> QueryParIter<'w, 's, Q, F>
+where
+    F::Fetch: FilterFetch,
+{
+    #[inline]
+    pub fn batch_size(mut self, batch_size: impl Into<BatchSize>) -> Self {
+        self.batch_size = batch_size.into();
+        self
+    }
+
+    /// Runs `f` on each query result.
+    pub fn for_each(self, f: impl Fn(<Q::Fetch as Fetch<'w>>::Item) + Send + Sync + Clone) {
+        self.fold_with(|| (), move |_, item| f(item));
+    }
+
+    /// Runs `f` on each query result and returns the outputs in iteration order.
+    pub fn map<T: Send + 'static>(
+        self,
+        f: impl Fn(<Q::Fetch as Fetch<'w>>::Item) -> T + Send + Sync + Clone,
+    ) -> Vec<T> {
+        self.fold_with(Vec::new, move |mut outputs, item| {
+            outputs.push(f(item));
+            outputs
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Folds the results of each batch, starting from `init`, and returns the result of every
+    /// batch.
+    pub fn fold<C: Clone + Send + Sync + 'static>(
+        self,
+        init: C,
+        f: impl Fn(C, <Q::Fetch as Fetch<'w>>::Item) -> C + Send + Sync + Clone,
+    ) -> Vec<C> {
+        self.fold_with(move || init.clone(), f)
+    }
+
+    /// Maps each query result with `map` and combines the outputs with `reduce`. Returns `None` if
+    /// the query has no results.
+    pub fn reduce<T: Send + 'static>(
+        self,
+        map: impl Fn(<Q::Fetch as Fetch<'w>>::Item) -> T + Send + Sync + Clone,
+        reduce: impl Fn(T, T) -> T + Send + Sync + Clone,
+    ) -> Option<T> {
+        let batch_reduce = reduce.clone();
+        self.fold_with(
+            || None,
+            move |accum, item| {
+                let value = map(item);
+                Some(match accum {
+                    Some(accum) => batch_reduce(accum, value),
+                    None => value,
+                })
+            },
+        )
+        .into_iter()
+        .flatten()
+        .reduce(reduce)
+    }
+
+    fn fold_with<C: Send + 'static>(
+        self,
+        init: impl Fn() -> C + Send + Sync + Clone,
+        f: impl Fn(C, <Q::Fetch as Fetch<'w>>::Item) -> C + Send + Sync + Clone,
+    ) -> Vec<C> {
+        // SAFE: the iterator is only created from a read-only query or with unique access to the
+        // query's components
+        unsafe {
+            self.state.par_fold_unchecked_manual(
+                self.world,
+                self.task_pool,
+                self.batch_size,
+                init,
+                f,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+}
+
+/// A parallel iterator over every unordered combination of `K` distinct query results, created
+/// by [`Query::par_iter_combinations`](crate::system::Query::par_iter_combinations).
+///
+/// Combinations are split into batches by their first result, so batches that start earlier
+/// contain more combinations. The batch size defaults to [`BatchSize::Auto`].
+pub struct QueryParCombinationIter<'w, 's, Q: WorldQuery, F: WorldQuery, const K: usize>
+where
+    F::Fetch: FilterFetch,
+{
+    pub(crate) world: &'w World,
+    pub(crate) state: &'s QueryState<Q, F>,
+    pub(crate) task_pool: &'s TaskPool,
+    pub(crate) batch_size: BatchSize,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+}
+
+impl<'w, 's, Q: WorldQuery, F: WorldQuery, const K: usize> QueryParCombinationIter<'w, 's, Q, F, K>
+where
+    F::Fetch: FilterFetch,
+{
+    #[inline]
+    pub fn batch_size(mut self, batch_size: impl Into<BatchSize>) -> Self {
+        self.batch_size = batch_size.into();
+        self
+    }
+
+    /// Runs `f` on each combination.
+    pub fn for_each(self, f: impl Fn([<Q::Fetch as Fetch<'w>>::Item; K]) + Send + Sync + Clone) {
+        self.fold_with(|| (), move |_, items| f(items));
+    }
+
+    /// Runs `f` on each combination and returns the outputs in iteration order.
+    pub fn map<T: Send + 'static>(
+        self,
+        f: impl Fn([<Q::Fetch as Fetch<'w>>::Item; K]) -> T + Send + Sync + Clone,
+    ) -> Vec<T> {
+        self.fold_with(Vec::new, move |mut outputs, items| {
+            outputs.push(f(items));
+            outputs
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Folds the combinations of each batch, starting from `init`, and returns the result of every
+    /// batch.
+    pub fn fold<C: Clone + Send + Sync + 'static>(
+        self,
+        init: C,
+        f: impl Fn(C, [<Q::Fetch as Fetch<'w>>::Item; K]) -> C + Send + Sync + Clone,
+    ) -> Vec<C> {
+        self.fold_with(move || init.clone(), f)
+    }
+
+    /// Maps each combination with `map` and combines the outputs with `reduce`. Returns `None` if
+    /// there are no combinations.
+    pub fn reduce<T: Send + 'static>(
+        self,
+        map: impl Fn([<Q::Fetch as Fetch<'w>>::Item; K]) -> T + Send + Sync + Clone,
+        reduce: impl Fn(T, T) -> T + Send + Sync + Clone,
+    ) -> Option<T> {
+        let batch_reduce = reduce.clone();
+        self.fold_with(
+            || None,
+            move |accum, items| {
+                let value = map(items);
+                Some(match accum {
+                    Some(accum) => batch_reduce(accum, value),
+                    None => value,
+                })
+            },
+        )
+        .into_iter()
+        .flatten()
+        .reduce(reduce)
+    }
+
+    fn fold_with<C: Send + 'static>(
+        self,
+        init: impl Fn() -> C + Send + Sync + Clone,
+        f: impl Fn(C, [<Q::Fetch as Fetch<'w>>::Item; K]) -> C + Send + Sync + Clone,
+    ) -> Vec<C> {
+        // SAFE: the iterator is only created from read-only queries
+        unsafe {
+            self.state.par_fold_combinations_unchecked_manual(
+                self.world,
+                self.task_pool,
+                self.batch_size,
+                init,
+                f,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+}
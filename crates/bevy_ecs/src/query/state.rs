@@ -1,9 +1,10 @@
 use crate::{
     archetype::{Archetype, ArchetypeComponentId, ArchetypeGeneration, ArchetypeId},
-    component::ComponentId,
+    component::{Component, ComponentId},
     entity::Entity,
     query::{
-        Access, Fetch, FetchState, FilterFetch, FilteredAccess, QueryIter, ReadOnlyFetch,
+        advance_combination, Access, BatchSize, Fetch, FetchState, FilterFetch, FilteredAccess,
+        QueryCombinationIter, QueryIter, QueryParCombinationIter, QueryParIter, ReadOnlyFetch,
         WorldQuery,
     },
     storage::TableId,
@@ -11,6 +12,7 @@ use crate::{
 };
 use bevy_tasks::TaskPool;
 use fixedbitset::FixedBitSet;
+use std::convert::TryInto;
 use thiserror::Error;
 
 pub struct QueryState<Q: WorldQuery, F: WorldQuery = ()>
@@ -29,6 +31,10 @@ where
     pub(crate) matched_archetype_ids: Vec<ArchetypeId>,
     pub(crate) fetch_state: Q::State,
     pub(crate) filter_state: F::State,
+    /// The ids of the markers of entities this query skips, i.e. of
+    /// [`Disabled`](crate::entity::Disabled) and [`Observer`](crate::observer::Observer) unless
+    /// the query mentions them.
+    hidden_ids: Vec<ComponentId>,
 }
 
 impl<Q: WorldQuery, F: WorldQuery> QueryState<Q, F>
@@ -52,6 +58,14 @@ where
         // properly considered in a global "cross-query" context (both within systems and across systems).
         component_access.extend(&filter_component_access);
 
+        // queries skip disabled entities and observers, unless they explicitly ask for them
+        let hidden_ids = world
+            .hidden_component_ids()
+            .iter()
+            .copied()
+            .filter(|id| !component_access.contains(*id))
+            .collect();
+
         let mut state = Self {
             world_id: world.id(),
             archetype_generation: ArchetypeGeneration::new(usize::MAX),
@@ -60,6 +74,7 @@ where
             fetch_state,
             filter_state,
             component_access,
+            hidden_ids,
             matched_tables: Default::default(),
             matched_archetypes: Default::default(),
             archetype_component_access: Default::default(),
@@ -93,6 +108,7 @@ where
     pub fn new_archetype(&mut self, archetype: &Archetype) {
         if self.fetch_state.matches_archetype(archetype)
             && self.filter_state.matches_archetype(archetype)
+            && !self.hidden_ids.iter().any(|id| archetype.contains(*id))
         {
             self.fetch_state
                 .update_archetype_component_access(archetype, &mut self.archetype_component_access);
@@ -190,6 +206,68 @@ where
         }
     }
 
+    /// Returns the read-only query results for the given array of [`Entity`]s.
+    #[inline]
+    pub fn get_many<'w, const N: usize>(
+        &mut self,
+        world: &'w World,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch<'w>>::Item; N], QueryEntityError>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        self.validate_world_and_update_archetypes(world);
+        // SAFETY: query is read only
+        unsafe {
+            self.get_many_unchecked_manual(
+                world,
+                entities,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// Returns the query results for the given array of [`Entity`]s, which must all be distinct.
+    #[inline]
+    pub fn get_many_mut<'w, const N: usize>(
+        &mut self,
+        world: &'w mut World,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch<'w>>::Item; N], QueryEntityError> {
+        verify_entities_unique(&entities)?;
+        self.validate_world_and_update_archetypes(world);
+        // SAFETY: query has unique world access and the entities are distinct
+        unsafe {
+            self.get_many_unchecked_manual(
+                world,
+                entities,
+                world.last_change_tick(),
+                world.read_change_tick(),
+            )
+        }
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query, and that `entities` are distinct.
+    pub(crate) unsafe fn get_many_unchecked_manual<'w, const N: usize>(
+        &self,
+        world: &'w World,
+        entities: [Entity; N],
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Result<[<Q::Fetch as Fetch<'w>>::Item; N], QueryEntityError> {
+        let items = entities
+            .iter()
+            .map(|entity| self.get_unchecked_manual(world, *entity, last_change_tick, change_tick))
+            .collect::<Result<Vec<_>, _>>()?;
+        match items.try_into() {
+            Ok(items) => Ok(items),
+            Err(_) => unreachable!("one item was fetched per entity"),
+        }
+    }
+
     #[inline]
     pub fn iter<'w, 's>(&'s mut self, world: &'w World) -> QueryIter<'w, 's, Q, F>
     where
@@ -234,6 +312,61 @@ where
         QueryIter::new(world, self, last_change_tick, change_tick)
     }
 
+    /// Returns an iterator over every unordered combination of `K` distinct query results.
+    #[inline]
+    pub fn iter_combinations<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w World,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        // SAFETY: query is read only
+        unsafe { self.iter_combinations_unchecked(world) }
+    }
+
+    /// Returns an iterator over every unordered combination of `K` distinct query results. Use
+    /// [`QueryCombinationIter::fetch_next`] to get the results.
+    #[inline]
+    pub fn iter_combinations_mut<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w mut World,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K> {
+        // SAFETY: query has unique world access
+        unsafe { self.iter_combinations_unchecked(world) }
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    #[inline]
+    pub unsafe fn iter_combinations_unchecked<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w World,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K> {
+        self.validate_world_and_update_archetypes(world);
+        self.iter_combinations_unchecked_manual(
+            world,
+            world.last_change_tick(),
+            world.read_change_tick(),
+        )
+    }
+
+    /// # Safety
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsafe.
+    #[inline]
+    pub(crate) unsafe fn iter_combinations_unchecked_manual<'w, 's, const K: usize>(
+        &'s self,
+        world: &'w World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> QueryCombinationIter<'w, 's, Q, F, K> {
+        QueryCombinationIter::new(world, self, last_change_tick, change_tick)
+    }
+
     #[inline]
     pub fn for_each<'w>(
         &mut self,
@@ -279,6 +412,32 @@ where
         );
     }
 
+    /// Returns an [`Iterator`] over the query results of every entity that has a
+    /// [`Relation`](crate::relation::Relation) of kind `K` to `target`.
+    #[inline]
+    pub fn iter_related_to<'w, 's, K: Component>(
+        &'s mut self,
+        world: &'w World,
+        target: Entity,
+    ) -> impl Iterator<Item = <Q::Fetch as Fetch<'w>>::Item> + 's
+    where
+        'w: 's,
+        Q::Fetch: ReadOnlyFetch,
+    {
+        self.validate_world_and_update_archetypes(world);
+        let state = &*self;
+        let last_change_tick = world.last_change_tick();
+        let change_tick = world.read_change_tick();
+        world.related_to::<K>(target).filter_map(move |entity| {
+            // SAFETY: query is read only
+            unsafe {
+                state
+                    .get_unchecked_manual(world, entity, last_change_tick, change_tick)
+                    .ok()
+            }
+        })
+    }
+
     #[inline]
     pub fn par_for_each<'w>(
         &mut self,
@@ -332,6 +491,66 @@ where
         );
     }
 
+    /// Returns a parallel iterator over the query results, which runs on `task_pool`.
+    #[inline]
+    pub fn par_iter<'w, 's>(
+        &'s mut self,
+        world: &'w World,
+        task_pool: &'s TaskPool,
+    ) -> QueryParIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        self.validate_world_and_update_archetypes(world);
+        QueryParIter {
+            world,
+            state: self,
+            task_pool,
+            batch_size: BatchSize::Auto,
+            last_change_tick: world.last_change_tick(),
+            change_tick: world.read_change_tick(),
+        }
+    }
+
+    #[inline]
+    pub fn par_iter_mut<'w, 's>(
+        &'s mut self,
+        world: &'w mut World,
+        task_pool: &'s TaskPool,
+    ) -> QueryParIter<'w, 's, Q, F> {
+        self.validate_world_and_update_archetypes(world);
+        QueryParIter {
+            last_change_tick: world.last_change_tick(),
+            change_tick: world.read_change_tick(),
+            world,
+            state: self,
+            task_pool,
+            batch_size: BatchSize::Auto,
+        }
+    }
+
+    /// Returns a parallel iterator over every unordered combination of `K` distinct query results,
+    /// which runs on `task_pool`.
+    #[inline]
+    pub fn par_iter_combinations<'w, 's, const K: usize>(
+        &'s mut self,
+        world: &'w World,
+        task_pool: &'s TaskPool,
+    ) -> QueryParCombinationIter<'w, 's, Q, F, K>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        self.validate_world_and_update_archetypes(world);
+        QueryParCombinationIter {
+            world,
+            state: self,
+            task_pool,
+            batch_size: BatchSize::Auto,
+            last_change_tick: world.last_change_tick(),
+            change_tick: world.read_change_tick(),
+        }
+    }
+
     /// # Safety
     ///
     /// This does not check for mutable query correctness. To be safe, make sure mutable queries
@@ -397,6 +616,37 @@ where
         last_change_tick: u32,
         change_tick: u32,
     ) {
+        self.par_fold_unchecked_manual(
+            world,
+            task_pool,
+            BatchSize::Fixed(batch_size),
+            || (),
+            move |_, item| func(item),
+            last_change_tick,
+            change_tick,
+        );
+    }
+
+    /// Folds the query results of each batch, starting from `init()`, and returns the result of
+    /// each batch in iteration order.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness. To be safe, make sure mutable queries
+    /// have unique access to the components they query.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsound.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) unsafe fn par_fold_unchecked_manual<'w, 's, C: Send + 'static>(
+        &'s self,
+        world: &'w World,
+        task_pool: &TaskPool,
+        batch_size: BatchSize,
+        init: impl Fn() -> C + Send + Sync + Clone,
+        func: impl Fn(C, <Q::Fetch as Fetch<'w>>::Item) -> C + Send + Sync + Clone,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Vec<C> {
         task_pool.scope(|scope| {
             let fetch =
                 <Q::Fetch as Fetch>::init(world, &self.fetch_state, last_change_tick, change_tick);
@@ -405,10 +655,17 @@ where
 
             if fetch.is_dense() && filter.is_dense() {
                 let tables = &world.storages().tables;
+                let len = self
+                    .matched_table_ids
+                    .iter()
+                    .map(|table_id| tables[*table_id].len())
+                    .sum();
+                let batch_size = batch_size.get(len, task_pool.thread_num());
                 for table_id in self.matched_table_ids.iter() {
                     let table = &tables[*table_id];
                     let mut offset = 0;
                     while offset < table.len() {
+                        let init = init.clone();
                         let func = func.clone();
                         scope.spawn(async move {
                             let mut fetch = <Q::Fetch as Fetch>::init(
@@ -428,23 +685,32 @@ where
                             fetch.set_table(&self.fetch_state, table);
                             filter.set_table(&self.filter_state, table);
                             let len = batch_size.min(table.len() - offset);
+                            let mut accum = init();
                             for table_index in offset..offset + len {
                                 if !filter.table_filter_fetch(table_index) {
                                     continue;
                                 }
                                 let item = fetch.table_fetch(table_index);
-                                func(item);
+                                accum = func(accum, item);
                             }
+                            accum
                         });
                         offset += batch_size;
                     }
                 }
             } else {
                 let archetypes = &world.archetypes;
+                let len = self
+                    .matched_archetype_ids
+                    .iter()
+                    .map(|archetype_id| archetypes[*archetype_id].len())
+                    .sum();
+                let batch_size = batch_size.get(len, task_pool.thread_num());
                 for archetype_id in self.matched_archetype_ids.iter() {
                     let mut offset = 0;
                     let archetype = &archetypes[*archetype_id];
                     while offset < archetype.len() {
+                        let init = init.clone();
                         let func = func.clone();
                         scope.spawn(async move {
                             let mut fetch = <Q::Fetch as Fetch>::init(
@@ -465,26 +731,138 @@ where
                             filter.set_archetype(&self.filter_state, archetype, tables);
 
                             let len = batch_size.min(archetype.len() - offset);
+                            let mut accum = init();
                             for archetype_index in offset..offset + len {
                                 if !filter.archetype_filter_fetch(archetype_index) {
                                     continue;
                                 }
-                                func(fetch.archetype_fetch(archetype_index));
+                                accum = func(accum, fetch.archetype_fetch(archetype_index));
                             }
+                            accum
                         });
                         offset += batch_size;
                     }
                 }
             }
-        });
+        })
+    }
+
+    /// Folds every unordered combination of `K` distinct query results in parallel, like
+    /// [`QueryState::par_fold_unchecked_manual`]. Combinations are split into batches by their
+    /// first result.
+    ///
+    /// # Safety
+    ///
+    /// This does not check for mutable query correctness, nor that the results of different
+    /// combinations do not alias. It should only be used with read-only queries.
+    /// This does not validate that `world.id()` matches `self.world_id`. Calling this on a `world`
+    /// with a mismatched WorldId is unsound.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) unsafe fn par_fold_combinations_unchecked_manual<
+        'w,
+        's,
+        C: Send + 'static,
+        const K: usize,
+    >(
+        &'s self,
+        world: &'w World,
+        task_pool: &TaskPool,
+        batch_size: BatchSize,
+        init: impl Fn() -> C + Send + Sync + Clone,
+        func: impl Fn(C, [<Q::Fetch as Fetch<'w>>::Item; K]) -> C + Send + Sync + Clone,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Vec<C> {
+        let entities = self.matching_entities(world, last_change_tick, change_tick);
+        let len = entities.len();
+        if K == 0 || K > len {
+            return Vec::new();
+        }
+        // only the first `len - K + 1` results can start a combination
+        let first_len = len - K + 1;
+        let batch_size = batch_size.get(first_len, task_pool.thread_num());
+        let entities = &entities;
+        task_pool.scope(|scope| {
+            let mut offset = 0;
+            while offset < first_len {
+                let init = init.clone();
+                let func = func.clone();
+                let end = first_len.min(offset + batch_size);
+                scope.spawn(async move {
+                    let mut indices = [0; K];
+                    for (i, index) in indices.iter_mut().enumerate() {
+                        *index = offset + i;
+                    }
+                    let mut accum = init();
+                    loop {
+                        let items = indices.map(|index| {
+                            match self.get_unchecked_manual(
+                                world,
+                                entities[index],
+                                last_change_tick,
+                                change_tick,
+                            ) {
+                                Ok(item) => item,
+                                Err(_) => unreachable!(
+                                    "entities matching the query cannot change while iterating"
+                                ),
+                            }
+                        });
+                        accum = func(accum, items);
+                        if !advance_combination(&mut indices, len) || indices[0] >= end {
+                            break;
+                        }
+                    }
+                    accum
+                });
+                offset += batch_size;
+            }
+        })
+    }
+
+    /// Returns the entities that match the query, in iteration order.
+    ///
+    /// # Safety
+    /// This does not validate that `world.id()` matches `self.world_id`.
+    pub(crate) unsafe fn matching_entities(
+        &self,
+        world: &World,
+        last_change_tick: u32,
+        change_tick: u32,
+    ) -> Vec<Entity> {
+        let mut filter =
+            <F::Fetch as Fetch>::init(world, &self.filter_state, last_change_tick, change_tick);
+        let mut entities = Vec::new();
+        for archetype_id in self.matched_archetype_ids.iter() {
+            let archetype = &world.archetypes[*archetype_id];
+            filter.set_archetype(&self.filter_state, archetype, &world.storages().tables);
+            for (index, entity) in archetype.entities().iter().enumerate() {
+                if filter.archetype_filter_fetch(index) {
+                    entities.push(*entity);
+                }
+            }
+        }
+        entities
     }
 }
 
 /// An error that occurs when retrieving a specific [`Entity`]'s query result.
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum QueryEntityError {
     #[error("The given entity does not have the requested component.")]
     QueryDoesNotMatch,
     #[error("The requested entity does not exist.")]
     NoSuchEntity,
+    #[error("The entity {0:?} was requested mutably more than once.")]
+    AliasedMutability(Entity),
+}
+
+/// Returns an error if any entity appears more than once in `entities`.
+pub(crate) fn verify_entities_unique(entities: &[Entity]) -> Result<(), QueryEntityError> {
+    for (i, entity) in entities.iter().enumerate() {
+        if entities[..i].contains(entity) {
+            return Err(QueryEntityError::AliasedMutability(*entity));
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,583 @@
+use crate::{
+    component::{Component, ComponentId},
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    query::With,
+    storage::SparseSet,
+    world::World,
+};
+use bevy_utils::{HashMap, HashSet};
+
+/// A typed, directed edge from one [`Entity`] (the "source") to any number of other entities (the
+/// "targets").
+///
+/// The relation "kind" `K` is any [`Component`] type. Each edge stores its own `K` value, so
+/// marker kinds like `struct ChildOf;` and data-carrying kinds like `struct Likes(f32);` are both
+/// supported. A source can have many targets of the same kind and a target can be pointed at by
+/// many sources.
+///
+/// Relations are added and removed with [`EntityMut::insert_relation`](crate::world::EntityMut::insert_relation)
+/// and [`EntityMut::remove_relation`](crate::world::EntityMut::remove_relation) (or their
+/// [`EntityCommands`](crate::system::EntityCommands) equivalents). The [`World`] keeps a reverse
+/// index of every edge, so despawning a target automatically removes all edges pointing at it.
+///
+/// `Relation<K>` is stored as a regular component on the source entity, which means it can be read
+/// in queries (`Query<&Relation<K>>`) and matched with the [`Related`] filter. Its hooks keep the
+/// reverse index in sync, so it can also be removed or cloned onto another entity directly.
+///
+/// ```
+/// use bevy_ecs::{prelude::*, relation::Relation};
+///
+/// struct Likes(u32);
+///
+/// let mut world = World::new();
+/// let alice = world.spawn().id();
+/// let bob = world.spawn().id();
+/// let carol = world.spawn().id();
+/// world.entity_mut(alice).insert_relation(Likes(3), bob);
+/// world.entity_mut(alice).insert_relation(Likes(5), carol);
+///
+/// let likes = world.get::<Relation<Likes>>(alice).unwrap();
+/// assert_eq!(likes.get(carol).unwrap().0, 5);
+///
+/// world.despawn(carol);
+/// let likes = world.get::<Relation<Likes>>(alice).unwrap();
+/// assert_eq!(likes.targets().collect::<Vec<_>>(), vec![bob]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Relation<K: Component> {
+    edges: Vec<(Entity, K)>,
+}
+
+impl<K: Component> Relation<K> {
+    pub(crate) fn new(target: Entity, kind: K) -> Self {
+        Self {
+            edges: vec![(target, kind)],
+        }
+    }
+
+    /// Returns an iterator over every target of this relation.
+    pub fn targets(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.edges.iter().map(|(target, _)| *target)
+    }
+
+    /// Returns an iterator over every target of this relation, along with the edge data.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &K)> {
+        self.edges.iter().map(|(target, kind)| (*target, kind))
+    }
+
+    /// Returns an iterator over every target of this relation, along with mutable edge data.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut K)> {
+        self.edges.iter_mut().map(|(target, kind)| (*target, kind))
+    }
+
+    /// Returns `true` if this relation has an edge to `target`.
+    #[inline]
+    pub fn contains(&self, target: Entity) -> bool {
+        self.edges
+            .iter()
+            .any(|(edge_target, _)| *edge_target == target)
+    }
+
+    /// Returns the edge data for `target`, if there is an edge to it.
+    pub fn get(&self, target: Entity) -> Option<&K> {
+        self.edges
+            .iter()
+            .find(|(edge_target, _)| *edge_target == target)
+            .map(|(_, kind)| kind)
+    }
+
+    /// Returns the mutable edge data for `target`, if there is an edge to it.
+    pub fn get_mut(&mut self, target: Entity) -> Option<&mut K> {
+        self.edges
+            .iter_mut()
+            .find(|(edge_target, _)| *edge_target == target)
+            .map(|(_, kind)| kind)
+    }
+
+    /// Returns the number of targets.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Inserts an edge to `target`, returning the previous edge data if the edge already existed.
+    pub(crate) fn insert(&mut self, target: Entity, kind: K) -> Option<K> {
+        if let Some(existing) = self.get_mut(target) {
+            Some(std::mem::replace(existing, kind))
+        } else {
+            self.edges.push((target, kind));
+            None
+        }
+    }
+
+    /// Removes the edge to `target`, returning its edge data if it existed.
+    pub(crate) fn remove(&mut self, target: Entity) -> Option<K> {
+        let index = self
+            .edges
+            .iter()
+            .position(|(edge_target, _)| *edge_target == target)?;
+        Some(self.edges.remove(index).1)
+    }
+}
+
+impl<K: Component> MapEntities for Relation<K> {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for (target, _) in self.edges.iter_mut() {
+            *target = entity_map.get(*target)?;
+        }
+        Ok(())
+    }
+}
+
+/// Filter that selects entities with at least one [`Relation`] of kind `K`.
+///
+/// To select the entities related to one specific target, use
+/// [`Query::iter_related_to`](crate::system::Query::iter_related_to).
+pub type Related<K> = With<Relation<K>>;
+
+/// The edges of a single relation kind, indexed in both directions.
+#[derive(Clone, Default)]
+pub(crate) struct RelationIndex {
+    /// Maps each target to the sources that have an edge to it.
+    sources: HashMap<Entity, Vec<Entity>>,
+    /// Maps each source to the targets it has an edge to.
+    targets: HashMap<Entity, Vec<Entity>>,
+}
+
+impl RelationIndex {
+    fn add_edge(&mut self, source: Entity, target: Entity) {
+        let sources = self.sources.entry(target).or_default();
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+        let targets = self.targets.entry(source).or_default();
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+
+    fn remove_edge(&mut self, source: Entity, target: Entity) {
+        remove_from(&mut self.sources, target, source);
+        remove_from(&mut self.targets, source, target);
+    }
+
+    /// Replaces every edge starting at `source` with edges to `targets`.
+    fn set_targets(&mut self, source: Entity, targets: Vec<Entity>) {
+        for target in self.targets.remove(&source).unwrap_or_default() {
+            remove_from(&mut self.sources, target, source);
+        }
+        for target in targets {
+            self.add_edge(source, target);
+        }
+    }
+}
+
+fn remove_from(map: &mut HashMap<Entity, Vec<Entity>>, key: Entity, value: Entity) {
+    if let Some(values) = map.get_mut(&key) {
+        values.retain(|v| *v != value);
+        if values.is_empty() {
+            map.remove(&key);
+        }
+    }
+}
+
+/// Type-erased operations for a single relation kind.
+struct RelationKindInfo {
+    index: RelationIndex,
+    remove_edge: fn(&mut World, Entity, Entity),
+    /// Registers the relation kind in another world, returning the id of `Relation<K>` there.
+    init: fn(&mut World) -> ComponentId,
+    /// Remaps the targets of a relation with an [`EntityMap`], dropping edges to unmapped targets.
+    map_targets: fn(&mut World, Entity, &EntityMap),
+}
+
+/// The reverse index of every [`Relation`] in a [`World`], keyed by the [`ComponentId`] of
+/// `Relation<K>`.
+///
+/// The index is kept in sync by the hooks of `Relation<K>`, so it stays correct when the component
+/// is inserted or removed directly rather than through the relation API.
+#[derive(Default)]
+pub struct Relations {
+    kinds: SparseSet<ComponentId, RelationKindInfo>,
+}
+
+impl Relations {
+    /// Returns the sources that have a relation with the given `component_id` to `target`.
+    pub fn sources(
+        &self,
+        component_id: ComponentId,
+        target: Entity,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.kinds
+            .get(component_id)
+            .and_then(|info| info.index.sources.get(&target))
+            .into_iter()
+            .flat_map(|sources| sources.iter().cloned())
+    }
+
+    /// Returns the [`ComponentId`]s of every registered relation kind.
+    pub fn kinds(&self) -> impl Iterator<Item = ComponentId> + '_ {
+        self.kinds.indices()
+    }
+
+    /// Returns true if `component_id` is the `Relation<K>` component of a registered relation kind.
+    pub fn contains_kind(&self, component_id: ComponentId) -> bool {
+        self.kinds.contains(component_id)
+    }
+
+    /// Registers the relation kind `K`, if it has not been registered yet.
+    pub(crate) fn init_kind<K: Component>(&mut self, component_id: ComponentId) {
+        if self.kinds.contains(component_id) {
+            return;
+        }
+        self.kinds.insert(
+            component_id,
+            RelationKindInfo {
+                index: RelationIndex::default(),
+                remove_edge: |world, source, target| {
+                    if let Some(mut source) = world.get_entity_mut(source) {
+                        source.remove_relation::<K>(target);
+                    }
+                },
+                init: |world| world.init_relation::<K>(),
+                map_targets: map_relation_targets::<K>,
+            },
+        );
+    }
+
+    /// Returns the index of every relation kind, to be restored with [`Self::restore_indices`].
+    pub(crate) fn indices_by_kind(&self) -> Vec<(ComponentId, RelationIndex)> {
+        self.kinds
+            .iter()
+            .map(|(component_id, info)| (*component_id, info.index.clone()))
+            .collect()
+    }
+
+    /// Restores the indices captured by [`Self::indices_by_kind`]. Kinds that were registered
+    /// since then are cleared.
+    pub(crate) fn restore_indices(&mut self, indices: &[(ComponentId, RelationIndex)]) {
+        for (component_id, info) in self.kinds.iter_mut() {
+            match indices.iter().find(|(id, _)| id == component_id) {
+                Some((_, index)) => info.index.clone_from(index),
+                None => info.index = RelationIndex::default(),
+            }
+        }
+    }
+
+    pub(crate) fn add_edge(&mut self, component_id: ComponentId, source: Entity, target: Entity) {
+        if let Some(info) = self.kinds.get_mut(component_id) {
+            info.index.add_edge(source, target);
+        }
+    }
+
+    pub(crate) fn remove_edge(
+        &mut self,
+        component_id: ComponentId,
+        source: Entity,
+        target: Entity,
+    ) {
+        if let Some(info) = self.kinds.get_mut(component_id) {
+            info.index.remove_edge(source, target);
+        }
+    }
+
+    pub(crate) fn set_targets(
+        &mut self,
+        component_id: ComponentId,
+        source: Entity,
+        targets: Vec<Entity>,
+    ) {
+        if let Some(info) = self.kinds.get_mut(component_id) {
+            info.index.set_targets(source, targets);
+        }
+    }
+
+    /// Returns the function registering the relation kind of `component_id` in another world, if
+    /// `component_id` is a relation kind.
+    pub(crate) fn init_fn(
+        &self,
+        component_id: ComponentId,
+    ) -> Option<fn(&mut World) -> ComponentId> {
+        self.kinds.get(component_id).map(|info| info.init)
+    }
+
+    /// Remaps the relations of the entities moved into `world`, which are the values of
+    /// `entity_map`. Edges to entities that were not moved are dropped.
+    pub(crate) fn map_moved(world: &mut World, entity_map: &EntityMap) {
+        let map_targets = world
+            .relations
+            .kinds
+            .values()
+            .map(|info| info.map_targets)
+            .collect::<Vec<_>>();
+        for map_targets in map_targets {
+            for entity in entity_map.values() {
+                map_targets(world, entity, entity_map);
+            }
+        }
+    }
+
+    /// Removes every edge that starts or ends at `entity`.
+    pub(crate) fn clear_entity(world: &mut World, entity: Entity) {
+        Self::clear_entity_except(world, entity, &HashSet::default());
+    }
+
+    /// Removes every edge that starts or ends at `entity` from the index. The `Relation`
+    /// components of the sources in `kept_sources` keep their edges to `entity`, so they can be
+    /// remapped once those sources are moved along with it.
+    pub(crate) fn clear_entity_except(
+        world: &mut World,
+        entity: Entity,
+        kept_sources: &HashSet<Entity>,
+    ) {
+        if world.relations.kinds.is_empty() {
+            return;
+        }
+        let kinds = world
+            .relations
+            .kinds
+            .indices()
+            .zip(world.relations.kinds.values())
+            .map(|(component_id, info)| (component_id, info.remove_edge))
+            .collect::<Vec<_>>();
+        for (component_id, remove_edge) in kinds {
+            let index = &mut world.relations.kinds.get_mut(component_id).unwrap().index;
+            // edges from `entity`
+            index.set_targets(entity, Vec::new());
+            // edges to `entity`
+            let sources = index.sources.remove(&entity).unwrap_or_default();
+            for source in sources {
+                if !kept_sources.contains(&source) {
+                    remove_edge(world, source, entity);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the [`Relations`] index in sync when a `Relation<K>` is inserted, including when it
+/// replaces an existing value.
+pub(crate) fn relation_on_insert<K: Component>(
+    world: &mut World,
+    source: Entity,
+    component_id: ComponentId,
+) {
+    let targets = world
+        .get::<Relation<K>>(source)
+        .map(|relation| relation.targets().collect())
+        .unwrap_or_default();
+    world.relations.set_targets(component_id, source, targets);
+}
+
+fn map_relation_targets<K: Component>(world: &mut World, source: Entity, entity_map: &EntityMap) {
+    let component_id = world.init_relation::<K>();
+    let mut source_mut = match world.get_entity_mut(source) {
+        Some(source_mut) => source_mut,
+        None => return,
+    };
+    let targets = match source_mut.get_mut::<Relation<K>>() {
+        Some(mut relation) => {
+            relation.edges = relation
+                .edges
+                .drain(..)
+                .filter_map(|(target, kind)| {
+                    entity_map.get(target).ok().map(|target| (target, kind))
+                })
+                .collect();
+            relation.targets().collect::<Vec<_>>()
+        }
+        None => return,
+    };
+    if targets.is_empty() {
+        source_mut.remove::<Relation<K>>();
+    } else {
+        world.relations.set_targets(component_id, source, targets);
+    }
+}
+
+/// Removes the edges of a `Relation<K>` from the [`Relations`] index when it is removed.
+pub(crate) fn relation_on_remove(world: &mut World, source: Entity, component_id: ComponentId) {
+    world
+        .relations
+        .set_targets(component_id, source, Vec::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        relation::{Related, Relation},
+        system::{CommandQueue, Commands, IntoSystem, Query, System},
+        world::World,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Likes(u32);
+    struct ChildOf;
+
+    #[test]
+    fn insert_and_remove_relation() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        let c = world.spawn().id();
+
+        world
+            .entity_mut(a)
+            .insert_relation(Likes(1), b)
+            .insert_relation(Likes(2), c);
+        world.entity_mut(b).insert_relation(Likes(3), c);
+        // re-inserting replaces the edge data
+        world.entity_mut(a).insert_relation(Likes(4), b);
+
+        let relation = world.get::<Relation<Likes>>(a).unwrap();
+        assert_eq!(relation.len(), 2);
+        assert_eq!(relation.get(b), Some(&Likes(4)));
+        let mut sources = world.related_to::<Likes>(c).collect::<Vec<_>>();
+        sources.sort();
+        assert_eq!(sources, vec![a, b]);
+
+        assert_eq!(
+            world.entity_mut(a).remove_relation::<Likes>(c),
+            Some(Likes(2))
+        );
+        assert_eq!(world.entity_mut(a).remove_relation::<Likes>(c), None);
+        assert_eq!(world.related_to::<Likes>(c).collect::<Vec<_>>(), vec![b]);
+
+        assert_eq!(
+            world.entity_mut(a).remove_relation::<Likes>(b),
+            Some(Likes(4))
+        );
+        assert!(
+            world.get::<Relation<Likes>>(a).is_none(),
+            "relation component is removed with the last edge"
+        );
+    }
+
+    #[test]
+    fn despawn_cleans_up_relations() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let other = world.spawn().id();
+        let source_1 = world
+            .spawn()
+            .insert_relation(ChildOf, target)
+            .insert_relation(ChildOf, other)
+            .id();
+        let source_2 = world.spawn().insert_relation(ChildOf, target).id();
+
+        assert!(world.despawn(target));
+        assert_eq!(world.related_to::<ChildOf>(target).count(), 0);
+        let relation = world.get::<Relation<ChildOf>>(source_1).unwrap();
+        assert_eq!(relation.targets().collect::<Vec<_>>(), vec![other]);
+        assert!(world.get::<Relation<ChildOf>>(source_2).is_none());
+
+        assert!(world.despawn(source_1));
+        assert_eq!(world.related_to::<ChildOf>(other).count(), 0);
+    }
+
+    #[test]
+    fn direct_insert_and_remove_keep_index() {
+        let mut world = World::new();
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        let c = world.spawn().id();
+        let source = world.spawn().insert_relation(Likes(1), a).id();
+        let other = world.spawn().insert_relation(Likes(2), b).id();
+
+        assert!(world
+            .entity_mut(source)
+            .remove::<Relation<Likes>>()
+            .is_some());
+        assert_eq!(world.related_to::<Likes>(a).count(), 0);
+
+        // replacing a relation drops the edges of the old value
+        let relation = world.get::<Relation<Likes>>(other).unwrap().clone();
+        world.entity_mut(c).insert_relation(Likes(3), a);
+        world.entity_mut(c).insert(relation);
+        assert_eq!(world.related_to::<Likes>(a).count(), 0);
+        let mut sources = world.related_to::<Likes>(b).collect::<Vec<_>>();
+        sources.sort();
+        assert_eq!(sources, vec![c, other]);
+
+        world
+            .entity_mut(other)
+            .remove_bundle::<(Relation<Likes>,)>();
+        assert_eq!(world.related_to::<Likes>(b).collect::<Vec<_>>(), vec![c]);
+
+        assert!(world.despawn(b));
+        assert!(world.get::<Relation<Likes>>(c).is_none());
+        assert!(world.get::<Relation<Likes>>(other).is_none());
+    }
+
+    #[test]
+    fn relation_registered_by_query_first() {
+        fn read_likes(_likes: Query<&Relation<Likes>>) {}
+
+        let mut world = World::new();
+        let mut system = read_likes.system();
+        system.initialize(&mut world);
+
+        let a = world.spawn().id();
+        let b = world.spawn().id();
+        let source = world.spawn().insert_relation(Likes(1), a).id();
+        let other = world.spawn().insert_relation(Likes(2), b).id();
+        assert_eq!(
+            world.related_to::<Likes>(a).collect::<Vec<_>>(),
+            vec![source]
+        );
+
+        world.entity_mut(source).remove::<Relation<Likes>>();
+        assert_eq!(world.related_to::<Likes>(a).count(), 0);
+
+        assert!(world.despawn(b));
+        assert!(world.get::<Relation<Likes>>(other).is_none());
+    }
+
+    #[test]
+    fn query_related() {
+        let mut world = World::new();
+        let target = world.spawn().id();
+        let other = world.spawn().id();
+        let a = world.spawn().insert_bundle((1u32,)).id();
+        let b = world.spawn().insert_bundle((2u32,)).id();
+        world.spawn().insert(3u32);
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(a).insert_relation(Likes(0), target);
+        commands.entity(b).insert_relation(Likes(0), other);
+        queue.apply(&mut world);
+
+        let mut values = world
+            .query_filtered::<&u32, Related<Likes>>()
+            .iter(&world)
+            .cloned()
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, vec![1, 2]);
+
+        let values = world
+            .query::<&u32>()
+            .iter_related_to::<Likes>(&world, target)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![1]);
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.entity(a).remove_relation::<Likes>(target);
+        queue.apply(&mut world);
+        assert_eq!(
+            world
+                .query::<&u32>()
+                .iter_related_to::<Likes>(&world, target)
+                .count(),
+            0
+        );
+    }
+}
@@ -11,6 +11,26 @@ use fixedbitset::FixedBitSet;
 #[cfg(test)]
 use SchedulingEvent::*;
 
+/// Resource that makes [`ParallelExecutor`] run systems in a reproducible way when inserted into
+/// the [`World`].
+///
+/// Systems that conflict on the components or resources they access, and systems that reserve
+/// entities through [`Commands`](crate::system::Commands), are run in their order within the
+/// stage instead of whichever is ready first. Since command buffers are already applied in that
+/// order, two runs from the same inputs spawn the same entities with the same ids, and create
+/// archetypes and tables, which queries iterate in creation order, in the same order as well.
+/// Combined with [`SystemRng`](crate::system::SystemRng) for randomness, this makes the resulting
+/// worlds identical, apart from the raw change ticks of systems that ran in parallel, which
+/// doesn't affect change detection.
+///
+/// Systems that don't conflict still run in parallel.
+///
+/// Reserving entities is detected through [`System::reserves_entities`](crate::system::System::reserves_entities),
+/// which defaults to `false`. Custom [`System`](crate::system::System)s wrapping other systems
+/// must forward it to the systems they wrap, like the chained and fallible systems do, or the
+/// entities they spawn may get different ids from run to run.
+pub struct DeterministicExecution;
+
 struct SystemSchedulingMetadata {
     /// Used to signal the system's task to start the system.
     start_sender: Sender<()>,
@@ -50,6 +70,8 @@ pub struct ParallelExecutor {
     active_archetype_component_access: Access<ArchetypeComponentId>,
     /// Scratch space to avoid reallocating a vector when updating dependency counters.
     dependants_scratch: Vec<usize>,
+    /// Whether dependencies were built for [`DeterministicExecution`].
+    deterministic: bool,
     #[cfg(test)]
     events_sender: Option<Sender<SchedulingEvent>>,
 }
@@ -69,6 +91,7 @@ impl Default for ParallelExecutor {
             should_run: Default::default(),
             active_archetype_component_access: Default::default(),
             dependants_scratch: Default::default(),
+            deterministic: false,
             #[cfg(test)]
             events_sender: None,
         }
@@ -84,25 +107,19 @@ impl ParallelSystemExecutor for ParallelExecutor {
 
         // Construct scheduling data for systems.
         for container in systems.iter() {
-            let dependencies_total = container.dependencies().len();
             let system = container.system();
             let (start_sender, start_receiver) = async_channel::bounded(1);
             self.system_metadata.push(SystemSchedulingMetadata {
                 start_sender,
                 start_receiver,
                 dependants: vec![],
-                dependencies_total,
+                dependencies_total: 0,
                 dependencies_now: 0,
                 is_send: system.is_send(),
                 archetype_component_access: Default::default(),
             });
         }
-        // Populate the dependants lists in the scheduling metadata.
-        for (dependant, container) in systems.iter().enumerate() {
-            for dependency in container.dependencies() {
-                self.system_metadata[*dependency].dependants.push(dependant);
-            }
-        }
+        self.rebuild_dependencies(systems);
     }
 
     fn run_systems(&mut self, systems: &mut [ParallelSystemContainer], world: &mut World) {
@@ -113,6 +130,12 @@ impl ParallelSystemExecutor for ParallelExecutor {
             self.events_sender = Some(sender);
         }
 
+        let deterministic = world.contains_resource::<DeterministicExecution>();
+        if deterministic != self.deterministic {
+            self.deterministic = deterministic;
+            self.rebuild_dependencies(systems);
+        }
+
         self.update_archetypes(systems, world);
 
         let compute_pool = world
@@ -148,6 +171,38 @@ impl ParallelSystemExecutor for ParallelExecutor {
 }
 
 impl ParallelExecutor {
+    /// Populates the dependants lists and dependency totals in the scheduling metadata. With
+    /// [`DeterministicExecution`], each system also depends on every earlier system it conflicts
+    /// with, or that reserves entities if it does too.
+    fn rebuild_dependencies(&mut self, systems: &[ParallelSystemContainer]) {
+        for system_data in self.system_metadata.iter_mut() {
+            system_data.dependants.clear();
+            system_data.dependencies_total = 0;
+        }
+        for (dependant, container) in systems.iter().enumerate() {
+            let mut dependencies = container.dependencies().to_vec();
+            if self.deterministic {
+                let system = container.system();
+                for (dependency, other) in systems[..dependant].iter().enumerate() {
+                    let other = other.system();
+                    if !system
+                        .component_access()
+                        .is_compatible(other.component_access())
+                        || (system.reserves_entities() && other.reserves_entities())
+                    {
+                        dependencies.push(dependency);
+                    }
+                }
+                dependencies.sort_unstable();
+                dependencies.dedup();
+            }
+            self.system_metadata[dependant].dependencies_total = dependencies.len();
+            for dependency in dependencies {
+                self.system_metadata[dependency].dependants.push(dependant);
+            }
+        }
+    }
+
     /// Calls system.new_archetype() for each archetype added since the last call to
     /// [update_archetypes] and updates cached archetype_component_access.
     fn update_archetypes(&mut self, systems: &mut [ParallelSystemContainer], world: &World) {
@@ -327,10 +382,14 @@ enum SchedulingEvent {
 
 #[cfg(test)]
 mod tests {
-    use super::SchedulingEvent::{self, *};
+    use super::{
+        DeterministicExecution,
+        SchedulingEvent::{self, *},
+    };
     use crate::{
+        entity::Entity,
         schedule::{SingleThreadedExecutor, Stage, SystemStage},
-        system::{IntoSystem, NonSend, Query, Res, ResMut},
+        system::{Commands, IntoSystem, NonSend, Query, Res, ResMut},
         world::World,
     };
     use async_channel::Receiver;
@@ -459,4 +518,42 @@ mod tests {
         stage.set_executor(Box::new(SingleThreadedExecutor::default()));
         stage.run(&mut world);
     }
+
+    #[test]
+    fn deterministic() {
+        let mut world = World::new();
+        world.insert_resource(DeterministicExecution);
+        world.insert_resource(0usize);
+        fn wants_ref(_: Res<usize>) {}
+        let mut stage = SystemStage::parallel();
+        for index in 0..4u32 {
+            stage.add_system(
+                (move |mut commands: Commands| {
+                    for _ in 0..3 {
+                        commands.spawn().insert(index);
+                    }
+                })
+                .system(),
+            );
+        }
+        stage.add_system(wants_ref.system());
+        stage.run(&mut world);
+        // Systems using commands run one after another, others still run alongside them.
+        assert_eq!(
+            receive_events(&world),
+            vec![
+                StartedSystems(2),
+                StartedSystems(1),
+                StartedSystems(1),
+                StartedSystems(1),
+            ]
+        );
+        let spawned = world
+            .query::<(Entity, &u32)>()
+            .iter(&world)
+            .map(|(entity, index)| (entity.id(), *index))
+            .collect::<Vec<_>>();
+        let expected = (0..12).map(|id| (id, id / 3)).collect::<Vec<_>>();
+        assert_eq!(spawned, expected);
+    }
 }
@@ -78,7 +78,8 @@ where
     graph
 }
 
-/// Generates a topological order for the given graph.
+/// Generates a topological order for the given graph. Nodes and their dependencies are visited
+/// in ascending index order, so the same graph always produces the same order.
 pub fn topological_order<Labels: Clone>(
     graph: &HashMap<usize, HashMap<usize, Labels>>,
 ) -> Result<Vec<usize>, DependencyGraphError<Labels>> {
@@ -95,7 +96,9 @@ pub fn topological_order<Labels: Clone>(
             return false;
         }
         current.push(*node);
-        for dependency in graph.get(node).unwrap().keys() {
+        let mut dependencies = graph.get(node).unwrap().keys().collect::<Vec<_>>();
+        dependencies.sort_unstable();
+        for dependency in dependencies {
             if check_if_cycles_and_visit(dependency, &graph, sorted, unvisited, current) {
                 return true;
             }
@@ -108,7 +111,9 @@ pub fn topological_order<Labels: Clone>(
     let mut current = Vec::with_capacity(graph.len());
     let mut unvisited = HashSet::with_capacity_and_hasher(graph.len(), Default::default());
     unvisited.extend(graph.keys().cloned());
-    while let Some(node) = unvisited.iter().next().cloned() {
+    let mut nodes = graph.keys().cloned().collect::<Vec<_>>();
+    nodes.sort_unstable();
+    for node in nodes {
         if check_if_cycles_and_visit(&node, graph, &mut sorted, &mut unvisited, &mut current) {
             let mut cycle = Vec::new();
             let last_window = [*current.last().unwrap(), current[0]];
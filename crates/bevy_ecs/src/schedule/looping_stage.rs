@@ -0,0 +1,152 @@
+use crate::{
+    schedule::{BoxedRunCriteria, ShouldRun, Stage},
+    system::{IntoSystem, Local, System},
+    world::World,
+};
+
+/// A [`Stage`] that runs another stage repeatedly, driven by a run criteria system.
+///
+/// Each time the looping stage is run, its criteria is evaluated before every iteration:
+/// - [`ShouldRun::Yes`] runs the inner stage one last time.
+/// - [`ShouldRun::YesAndCheckAgain`] runs the inner stage and checks the criteria again.
+/// - [`ShouldRun::No`] stops the loop.
+///
+/// Without a run criteria the inner stage is run exactly once. This can be used to run a nested
+/// [`Schedule`](crate::schedule::Schedule) several times per frame, such as fixed-step physics
+/// sub-steps, or until some condition holds.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// fn step(mut steps: ResMut<usize>) {
+///     *steps += 1;
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(0usize);
+/// let physics = SystemStage::single_threaded().with_system(step.system());
+/// let mut stage = LoopingStage::times(physics, 4);
+/// stage.run(&mut world);
+/// assert_eq!(*world.get_resource::<usize>().unwrap(), 4);
+/// ```
+pub struct LoopingStage<S: Stage> {
+    stage: S,
+    run_criteria: BoxedRunCriteria,
+}
+
+impl<S: Stage> LoopingStage<S> {
+    pub fn new(stage: S) -> Self {
+        Self {
+            stage,
+            run_criteria: Default::default(),
+        }
+    }
+
+    /// Creates a looping stage that runs `stage` `count` times each time it is run.
+    pub fn times(stage: S, count: usize) -> Self {
+        Self::new(stage).with_run_criteria(
+            (move |mut iteration: Local<usize>| {
+                if *iteration < count {
+                    *iteration += 1;
+                    ShouldRun::YesAndCheckAgain
+                } else {
+                    *iteration = 0;
+                    ShouldRun::No
+                }
+            })
+            .system(),
+        )
+    }
+
+    pub fn with_run_criteria<Sys: System<In = (), Out = ShouldRun>>(mut self, system: Sys) -> Self {
+        self.set_run_criteria(system);
+        self
+    }
+
+    pub fn set_run_criteria<Sys: System<In = (), Out = ShouldRun>>(
+        &mut self,
+        system: Sys,
+    ) -> &mut Self {
+        self.run_criteria.set(Box::new(system));
+        self
+    }
+
+    pub fn stage(&self) -> &S {
+        &self.stage
+    }
+
+    pub fn stage_mut(&mut self) -> &mut S {
+        &mut self.stage
+    }
+}
+
+impl<S: Stage> Stage for LoopingStage<S> {
+    fn run(&mut self, world: &mut World) {
+        loop {
+            match self.run_criteria.should_run(world) {
+                ShouldRun::No => return,
+                ShouldRun::Yes => {
+                    self.stage.run(world);
+                    return;
+                }
+                ShouldRun::YesAndCheckAgain => {
+                    self.stage.run(world);
+                }
+                ShouldRun::NoAndCheckAgain => {
+                    panic!("`NoAndCheckAgain` would loop infinitely in this situation.")
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        schedule::{LoopingStage, Schedule, ShouldRun, Stage, SystemStage},
+        system::{IntoSystem, Res, ResMut},
+        world::World,
+    };
+
+    fn increment(mut counter: ResMut<u32>) {
+        *counter += 1;
+    }
+
+    #[test]
+    fn loops_until_criteria_is_met() {
+        fn below_ten(counter: Res<u32>) -> ShouldRun {
+            if *counter < 10 {
+                ShouldRun::YesAndCheckAgain
+            } else {
+                ShouldRun::No
+            }
+        }
+
+        let mut world = World::new();
+        world.insert_resource(0u32);
+        let mut stage = LoopingStage::new(Schedule::default().with_stage(
+            "update",
+            SystemStage::parallel().with_system(increment.system()),
+        ))
+        .with_run_criteria(below_ten.system());
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 10);
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 10);
+    }
+
+    #[test]
+    fn runs_a_fixed_number_of_times() {
+        let mut world = World::new();
+        world.insert_resource(0u32);
+        let mut stage =
+            LoopingStage::times(SystemStage::parallel().with_system(increment.system()), 3);
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 3);
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 6);
+
+        let mut stage = LoopingStage::new(SystemStage::parallel().with_system(increment.system()));
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<u32>().unwrap(), 7);
+    }
+}
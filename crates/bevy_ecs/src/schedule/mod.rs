@@ -2,7 +2,9 @@ mod executor;
 mod executor_parallel;
 pub mod graph_utils;
 mod label;
+mod looping_stage;
 mod run_criteria;
+mod schedule_graph;
 mod stage;
 mod state;
 mod system_container;
@@ -13,7 +15,9 @@ pub use executor::*;
 pub use executor_parallel::*;
 pub use graph_utils::GraphNode;
 pub use label::*;
+pub use looping_stage::*;
 pub use run_criteria::*;
+pub use schedule_graph::*;
 pub use stage::*;
 pub use state::*;
 pub use system_container::*;
@@ -206,6 +210,8 @@ impl Schedule {
             let _stage_guard = stage_span.enter();
             let stage = self.stages.get_mut(label).unwrap();
             stage.run(world);
+            #[cfg(debug_assertions)]
+            crate::invariant::check_invariants(world, label);
         }
     }
 
@@ -215,6 +221,51 @@ impl Schedule {
             .iter()
             .map(move |label| (&**label, &*self.stages[label]))
     }
+
+    /// Describes the stages of this schedule, their systems and the dependencies between them.
+    /// Nested schedules are described recursively; other kinds of stages are listed without
+    /// systems.
+    ///
+    /// Note that this initializes every [`SystemStage`], as running the schedule would, so that
+    /// systems added since the last run are included: their [`System::initialize`] is called and
+    /// the stage's dependency graph is rebuilt, which may register components and resources in
+    /// `world`.
+    pub fn graph(&mut self, world: &mut World) -> ScheduleGraph {
+        let mut stages = Vec::with_capacity(self.stage_order.len());
+        for label in self.stage_order.iter() {
+            let stage = self.stages.get_mut(label).unwrap();
+            let label = label_name(&**label);
+            let graph = if let Some(stage) = stage.downcast_mut::<SystemStage>() {
+                stage.initialize(world);
+                stage.graph(label, world)
+            } else if let Some(schedule) = stage.downcast_mut::<Schedule>() {
+                StageGraph {
+                    label,
+                    schedule: Some(schedule.graph(world)),
+                    ..Default::default()
+                }
+            } else {
+                StageGraph {
+                    label,
+                    ..Default::default()
+                }
+            };
+            stages.push(graph);
+        }
+        ScheduleGraph { stages }
+    }
+
+    /// Renders this schedule as a graphviz DOT digraph; see [`ScheduleGraph::to_dot`]. Like
+    /// [`Schedule::graph`], this initializes every [`SystemStage`].
+    pub fn to_dot(&mut self, world: &mut World) -> String {
+        self.graph(world).to_dot()
+    }
+
+    /// Renders this schedule as JSON; see [`ScheduleGraph::to_json`]. Like [`Schedule::graph`],
+    /// this initializes every [`SystemStage`].
+    pub fn to_json(&mut self, world: &mut World) -> String {
+        self.graph(world).to_json()
+    }
 }
 
 impl Stage for Schedule {
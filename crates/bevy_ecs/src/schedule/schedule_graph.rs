@@ -0,0 +1,384 @@
+use crate::schedule::DynHash;
+use std::{
+    borrow::Cow,
+    fmt::{Debug, Write},
+};
+
+/// A snapshot of a [`Schedule`](crate::schedule::Schedule)'s stages, systems and their
+/// dependencies, which can be exported to [DOT](https://graphviz.org/doc/info/lang.html) or JSON
+/// for visualization.
+///
+/// Obtained from [`Schedule::graph`](crate::schedule::Schedule::graph).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScheduleGraph {
+    /// The stages of the schedule, in execution order.
+    pub stages: Vec<StageGraph>,
+}
+
+/// The systems and run criteria of a single stage.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StageGraph {
+    pub label: String,
+    /// Systems in execution order: exclusive systems at the start, parallel systems, exclusive
+    /// systems before commands, and exclusive systems at the end.
+    pub systems: Vec<SystemNode>,
+    pub run_criteria: Vec<RunCriteriaNode>,
+    /// The graph of a nested [`Schedule`](crate::schedule::Schedule), if this stage is one.
+    pub schedule: Option<ScheduleGraph>,
+}
+
+/// Where in the stage a system is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemKind {
+    ExclusiveAtStart,
+    Parallel,
+    ExclusiveBeforeCommands,
+    ExclusiveAtEnd,
+}
+
+impl Default for SystemKind {
+    fn default() -> Self {
+        SystemKind::Parallel
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SystemNode {
+    pub name: String,
+    pub kind: SystemKind,
+    pub labels: Vec<String>,
+    /// Index of the system set this system was added with, unique within its stage.
+    pub system_set: Option<usize>,
+    /// Index into [`StageGraph::run_criteria`].
+    pub run_criteria: Option<usize>,
+    /// Indices into [`StageGraph::systems`] of the systems that must run before this one.
+    pub dependencies: Vec<usize>,
+    /// Whether the system has access to the whole [`World`](crate::world::World).
+    pub reads_all: bool,
+    /// Names of the components and resources read, but not written, by the system.
+    pub reads: Vec<String>,
+    /// Names of the components and resources written by the system.
+    pub writes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunCriteriaNode {
+    pub name: String,
+    pub label: Option<String>,
+    /// Index into [`StageGraph::run_criteria`] of the criteria this one is piped from.
+    pub piped_from: Option<usize>,
+}
+
+impl ScheduleGraph {
+    /// Renders the schedule as a graphviz DOT digraph. Each stage is a cluster, system sets are
+    /// nested clusters, solid edges are ordering dependencies and dashed edges lead from run
+    /// criteria to the systems they guard.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph schedule {\n");
+        dot.push_str("    compound=true;\n");
+        dot.push_str("    node [shape=box];\n");
+        self.write_dot(&mut dot, "s", 1);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String, prefix: &str, depth: usize) {
+        let indent = "    ".repeat(depth);
+        let mut previous_stage: Option<String> = None;
+        for (stage_index, stage) in self.stages.iter().enumerate() {
+            let stage_id = format!("{}{}", prefix, stage_index);
+            writeln!(dot, "{}subgraph cluster_{} {{", indent, stage_id).unwrap();
+            writeln!(dot, "{}    label={};", indent, dot_string(&stage.label)).unwrap();
+            writeln!(
+                dot,
+                "{}    {} [label={}, shape=plaintext];",
+                indent,
+                stage_id,
+                dot_string(&stage.label)
+            )
+            .unwrap();
+
+            for (index, criteria) in stage.run_criteria.iter().enumerate() {
+                let label = match &criteria.label {
+                    Some(label) => format!("{}\n{}", criteria.name, label),
+                    None => criteria.name.clone(),
+                };
+                writeln!(
+                    dot,
+                    "{}    {}_rc{} [label={}, shape=diamond];",
+                    indent,
+                    stage_id,
+                    index,
+                    dot_string(&label)
+                )
+                .unwrap();
+            }
+
+            let mut sets: Vec<usize> = stage
+                .systems
+                .iter()
+                .filter_map(|system| system.system_set)
+                .collect();
+            sets.sort_unstable();
+            sets.dedup();
+            for (index, system) in stage.systems.iter().enumerate() {
+                if system.system_set.is_none() {
+                    write_dot_system(dot, &indent, &stage_id, index, system);
+                }
+            }
+            for set in sets {
+                writeln!(
+                    dot,
+                    "{}    subgraph cluster_{}_set{} {{",
+                    indent, stage_id, set
+                )
+                .unwrap();
+                writeln!(dot, "{}        label=\"set {}\";", indent, set).unwrap();
+                writeln!(dot, "{}        style=dashed;", indent).unwrap();
+                for (index, system) in stage.systems.iter().enumerate() {
+                    if system.system_set == Some(set) {
+                        write_dot_system(dot, &format!("{}    ", indent), &stage_id, index, system);
+                    }
+                }
+                writeln!(dot, "{}    }}", indent).unwrap();
+            }
+
+            if let Some(schedule) = &stage.schedule {
+                schedule.write_dot(dot, &format!("{}_s", stage_id), depth + 1);
+            }
+            writeln!(dot, "{}}}", indent).unwrap();
+
+            for (index, criteria) in stage.run_criteria.iter().enumerate() {
+                if let Some(input) = criteria.piped_from {
+                    writeln!(
+                        dot,
+                        "{}{}_rc{} -> {}_rc{} [style=dashed];",
+                        indent, stage_id, input, stage_id, index
+                    )
+                    .unwrap();
+                }
+            }
+            for (index, system) in stage.systems.iter().enumerate() {
+                for dependency in &system.dependencies {
+                    writeln!(
+                        dot,
+                        "{}{}_{} -> {}_{};",
+                        indent, stage_id, dependency, stage_id, index
+                    )
+                    .unwrap();
+                }
+                if let Some(criteria) = system.run_criteria {
+                    writeln!(
+                        dot,
+                        "{}{}_rc{} -> {}_{} [style=dashed];",
+                        indent, stage_id, criteria, stage_id, index
+                    )
+                    .unwrap();
+                }
+            }
+            if let Some(previous) = previous_stage.replace(stage_id.clone()) {
+                writeln!(
+                    dot,
+                    "{}{} -> {} [style=bold, ltail=cluster_{}, lhead=cluster_{}];",
+                    indent, previous, stage_id, previous, stage_id
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Renders the schedule as a JSON document of the form
+    /// `{"stages": [{"label", "systems", "run_criteria", "schedule"}]}`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str("{\"stages\":[");
+        for (stage_index, stage) in self.stages.iter().enumerate() {
+            if stage_index > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"label\":{},\"systems\":[",
+                json_string(&stage.label)
+            )
+            .unwrap();
+            for (index, system) in stage.systems.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write!(
+                    json,
+                    "{{\"name\":{},\"kind\":\"{:?}\",\"labels\":{},\"system_set\":{},\
+                     \"run_criteria\":{},\"dependencies\":{:?},\"reads_all\":{},\
+                     \"reads\":{},\"writes\":{}}}",
+                    json_string(&system.name),
+                    system.kind,
+                    json_strings(&system.labels),
+                    json_option(system.system_set),
+                    json_option(system.run_criteria),
+                    system.dependencies,
+                    system.reads_all,
+                    json_strings(&system.reads),
+                    json_strings(&system.writes),
+                )
+                .unwrap();
+            }
+            json.push_str("],\"run_criteria\":[");
+            for (index, criteria) in stage.run_criteria.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write!(
+                    json,
+                    "{{\"name\":{},\"label\":{},\"piped_from\":{}}}",
+                    json_string(&criteria.name),
+                    criteria
+                        .label
+                        .as_deref()
+                        .map_or_else(|| "null".to_string(), json_string),
+                    json_option(criteria.piped_from),
+                )
+                .unwrap();
+            }
+            json.push_str("],\"schedule\":");
+            match &stage.schedule {
+                Some(schedule) => schedule.write_json(json),
+                None => json.push_str("null"),
+            }
+            json.push('}');
+        }
+        json.push_str("]}");
+    }
+}
+
+fn write_dot_system(
+    dot: &mut String,
+    indent: &str,
+    stage_id: &str,
+    index: usize,
+    system: &SystemNode,
+) {
+    let mut label = system.name.clone();
+    for system_label in &system.labels {
+        write!(label, "\nlabel: {}", system_label).unwrap();
+    }
+    if system.reads_all {
+        label.push_str("\nreads: World");
+    }
+    for read in &system.reads {
+        write!(label, "\nreads: {}", read).unwrap();
+    }
+    for write in &system.writes {
+        write!(label, "\nwrites: {}", write).unwrap();
+    }
+    let style = if system.kind == SystemKind::Parallel {
+        ""
+    } else {
+        ", style=bold"
+    };
+    writeln!(
+        dot,
+        "{}    {}_{} [label={}{}];",
+        indent,
+        stage_id,
+        index,
+        dot_string(&label),
+        style
+    )
+    .unwrap();
+}
+
+fn dot_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\l"),
+            c => escaped.push(c),
+        }
+    }
+    if value.contains('\n') {
+        escaped.push_str("\\l");
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_strings(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| json_string(value)).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn json_option(value: Option<usize>) -> String {
+    value.map_or_else(|| "null".to_string(), |value| value.to_string())
+}
+
+/// Returns the name of a label: string labels without the quotes added by their `Debug` output,
+/// other labels as formatted by `Debug`.
+pub(crate) fn label_name<L: DynHash + Debug + ?Sized>(label: &L) -> String {
+    let any = label.as_dyn_eq().as_any();
+    if let Some(name) = any.downcast_ref::<&'static str>() {
+        name.to_string()
+    } else if let Some(name) = any.downcast_ref::<Cow<'static, str>>() {
+        name.to_string()
+    } else {
+        format!("{:?}", label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(dot_string("a\"b"), "\"a\\\"b\"");
+        let graph = ScheduleGraph {
+            stages: vec![StageGraph {
+                label: "a\"b\\c\n".to_string(),
+                ..Default::default()
+            }],
+        };
+        assert!(graph.to_json().contains("\"label\":\"a\\\"b\\\\c\\n\""));
+    }
+
+    #[test]
+    fn label_names() {
+        #[derive(Debug, Hash, PartialEq, Eq)]
+        struct Label;
+
+        assert_eq!(label_name(&"update"), "update");
+        assert_eq!(
+            label_name(&Cow::<'static, str>::Borrowed("update")),
+            "update"
+        );
+        assert_eq!(label_name(&Label), "Label");
+    }
+}
@@ -2,13 +2,14 @@ use crate::{
     component::ComponentId,
     schedule::{
         graph_utils::{self, DependencyGraphError},
-        BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel, DuplicateLabelStrategy,
-        ExclusiveSystemContainer, GraphNode, InsertionPoint, ParallelExecutor,
-        ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaContainer,
-        RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel, RunCriteriaInner, ShouldRun,
-        SingleThreadedExecutor, SystemContainer, SystemDescriptor, SystemSet,
+        label_name, BoxedRunCriteria, BoxedRunCriteriaLabel, BoxedSystemLabel,
+        DuplicateLabelStrategy, ExclusiveSystemContainer, GraphNode, InsertionPoint,
+        ParallelExecutor, ParallelSystemContainer, ParallelSystemExecutor, RunCriteriaContainer,
+        RunCriteriaDescriptor, RunCriteriaDescriptorOrLabel, RunCriteriaInner, RunCriteriaNode,
+        ShouldRun, SingleThreadedExecutor, StageGraph, SystemContainer, SystemDescriptor,
+        SystemKind, SystemNode, SystemSet,
     },
-    system::System,
+    system::{System, SystemErrorHandler},
     world::{World, WorldId},
 };
 use bevy_utils::{tracing::info, HashMap, HashSet};
@@ -81,6 +82,11 @@ pub struct SystemStage {
     uninitialized_parallel: Vec<usize>,
     /// Saves the value of the World change_tick during the last tick check
     last_tick_check: u32,
+    /// Number of system sets added to this stage.
+    system_set_count: usize,
+    /// Handles errors returned by this stage's systems instead of the world's
+    /// [`SystemErrorHandler`] resource.
+    error_handler: Option<SystemErrorHandler>,
 }
 
 impl SystemStage {
@@ -102,6 +108,8 @@ impl SystemStage {
             uninitialized_before_commands: vec![],
             uninitialized_at_end: vec![],
             last_tick_check: Default::default(),
+            system_set_count: 0,
+            error_handler: None,
         }
     }
 
@@ -137,7 +145,7 @@ impl SystemStage {
     }
 
     pub fn add_system(&mut self, system: impl Into<SystemDescriptor>) -> &mut Self {
-        self.add_system_inner(system, None);
+        self.add_system_inner(system, None, None);
         self
     }
 
@@ -145,6 +153,7 @@ impl SystemStage {
         &mut self,
         system: impl Into<SystemDescriptor>,
         default_run_criteria: Option<usize>,
+        system_set: Option<usize>,
     ) {
         self.systems_modified = true;
         match system.into() {
@@ -152,6 +161,7 @@ impl SystemStage {
                 let insertion_point = descriptor.insertion_point;
                 let criteria = descriptor.run_criteria.take();
                 let mut container = ExclusiveSystemContainer::from_descriptor(descriptor);
+                container.system_set = system_set;
                 match criteria {
                     Some(RunCriteriaDescriptorOrLabel::Label(label)) => {
                         container.run_criteria_label = Some(label);
@@ -186,6 +196,7 @@ impl SystemStage {
             SystemDescriptor::Parallel(mut descriptor) => {
                 let criteria = descriptor.run_criteria.take();
                 let mut container = ParallelSystemContainer::from_descriptor(descriptor);
+                container.system_set = system_set;
                 match criteria {
                     Some(RunCriteriaDescriptorOrLabel::Label(label)) => {
                         container.run_criteria_label = Some(label);
@@ -234,6 +245,71 @@ impl SystemStage {
         &self.exclusive_before_commands
     }
 
+    /// Describes this stage's systems, run criteria and dependencies; see [`ScheduleGraph`].
+    /// `label` is the label the stage was added with.
+    ///
+    /// Note that systems won't be fully-formed until the stage has been initialized, see
+    /// [`SystemStage::initialize`].
+    pub fn graph(&self, label: impl Into<String>, world: &World) -> StageGraph {
+        let component_name = |id: ComponentId| {
+            world
+                .components()
+                .get_info(id)
+                .map_or_else(|| format!("{:?}", id), |info| info.name().to_string())
+        };
+        let mut systems = Vec::new();
+        add_exclusive_system_nodes(
+            &mut systems,
+            &self.exclusive_at_start,
+            SystemKind::ExclusiveAtStart,
+        );
+        let offset = systems.len();
+        systems.extend(self.parallel.iter().map(|container| {
+            let access = container.system().component_access();
+            SystemNode {
+                name: container.name().into_owned(),
+                kind: SystemKind::Parallel,
+                labels: format_labels(container.labels()),
+                system_set: container.system_set,
+                run_criteria: container.run_criteria(),
+                dependencies: offset_dependencies(container.dependencies(), offset),
+                reads_all: access.reads_all(),
+                reads: access.reads().map(component_name).collect(),
+                writes: access.writes().map(component_name).collect(),
+            }
+        }));
+        add_exclusive_system_nodes(
+            &mut systems,
+            &self.exclusive_before_commands,
+            SystemKind::ExclusiveBeforeCommands,
+        );
+        add_exclusive_system_nodes(
+            &mut systems,
+            &self.exclusive_at_end,
+            SystemKind::ExclusiveAtEnd,
+        );
+
+        let run_criteria = self
+            .run_criteria
+            .iter()
+            .map(|criteria| RunCriteriaNode {
+                name: criteria.name().into_owned(),
+                label: criteria.label.as_ref().map(|label| label_name(&**label)),
+                piped_from: match criteria.inner {
+                    RunCriteriaInner::Single(_) => None,
+                    RunCriteriaInner::Piped { input, .. } => Some(input),
+                },
+            })
+            .collect();
+
+        StageGraph {
+            label: label.into(),
+            systems,
+            run_criteria,
+            schedule: None,
+        }
+    }
+
     pub fn with_system_set(mut self, system_set: SystemSet) -> Self {
         self.add_system_set(system_set);
         self
@@ -285,8 +361,10 @@ impl SystemStage {
                 }
             }
         });
+        let system_set = self.system_set_count;
+        self.system_set_count += 1;
         for system in systems.drain(..) {
-            self.add_system_inner(system, set_run_criteria_index);
+            self.add_system_inner(system, set_run_criteria_index, Some(system_set));
         }
         self
     }
@@ -304,6 +382,21 @@ impl SystemStage {
         self
     }
 
+    pub fn with_error_handler(mut self, handler: SystemErrorHandler) -> Self {
+        self.set_error_handler(handler);
+        self
+    }
+
+    /// Sets the handler of errors returned by this stage's systems, overriding the world's
+    /// [`SystemErrorHandler`] resource.
+    pub fn set_error_handler(&mut self, handler: SystemErrorHandler) -> &mut Self {
+        self.error_handler = Some(handler);
+        for container in self.parallel.iter_mut() {
+            container.system_mut().set_error_handler(handler);
+        }
+        self
+    }
+
     pub fn with_system_run_criteria(mut self, run_criteria: RunCriteriaDescriptor) -> Self {
         self.add_system_run_criteria(run_criteria);
         self
@@ -393,10 +486,40 @@ impl SystemStage {
             if let Some(index) = container.run_criteria() {
                 container.set_run_criteria(new_indices[index]);
             }
+            if let Some(handler) = self.error_handler {
+                container.system_mut().set_error_handler(handler);
+            }
             container.system_mut().initialize(world);
         }
     }
 
+    /// Initializes newly added systems and sorts all systems in topological order, without running
+    /// them. This happens automatically when the stage is run.
+    pub fn initialize(&mut self, world: &mut World) {
+        if let Some(world_id) = self.world_id {
+            assert!(
+                world.id() == world_id,
+                "Cannot run SystemStage on two different Worlds"
+            );
+        } else {
+            self.world_id = Some(world.id());
+        }
+
+        if self.systems_modified {
+            self.initialize_systems(world);
+            self.rebuild_orders_and_dependencies();
+            self.systems_modified = false;
+            self.executor.rebuild_cached_data(&self.parallel);
+            self.executor_modified = false;
+            if world.contains_resource::<ReportExecutionOrderAmbiguities>() {
+                self.report_ambiguities(world);
+            }
+        } else if self.executor_modified {
+            self.executor.rebuild_cached_data(&self.parallel);
+            self.executor_modified = false;
+        }
+    }
+
     /// Rearranges all systems in topological orders. Systems must be initialized.
     fn rebuild_orders_and_dependencies(&mut self) {
         // This assertion is there to document that a maximum of `u32::MAX / 8` systems should be
@@ -660,6 +783,33 @@ fn process_systems(
     Ok(())
 }
 
+fn add_exclusive_system_nodes(
+    systems: &mut Vec<SystemNode>,
+    containers: &[ExclusiveSystemContainer],
+    kind: SystemKind,
+) {
+    let offset = systems.len();
+    systems.extend(containers.iter().map(|container| SystemNode {
+        name: container.name().into_owned(),
+        kind,
+        labels: format_labels(container.labels()),
+        system_set: container.system_set,
+        run_criteria: container.run_criteria(),
+        dependencies: offset_dependencies(container.dependencies(), offset),
+        reads_all: true,
+        reads: Vec::new(),
+        writes: Vec::new(),
+    }));
+}
+
+fn format_labels(labels: &[BoxedSystemLabel]) -> Vec<String> {
+    labels.iter().map(|label| label_name(&**label)).collect()
+}
+
+fn offset_dependencies(dependencies: &[usize], offset: usize) -> Vec<usize> {
+    dependencies.iter().map(|index| index + offset).collect()
+}
+
 /// Returns vector containing all pairs of indices of systems with ambiguous execution order,
 /// along with specific components that have triggered the warning.
 /// Systems must be topologically sorted beforehand.
@@ -738,28 +888,7 @@ fn find_ambiguities(systems: &[impl SystemContainer]) -> Vec<(usize, usize, Vec<
 
 impl Stage for SystemStage {
     fn run(&mut self, world: &mut World) {
-        if let Some(world_id) = self.world_id {
-            assert!(
-                world.id() == world_id,
-                "Cannot run SystemStage on two different Worlds"
-            );
-        } else {
-            self.world_id = Some(world.id());
-        }
-
-        if self.systems_modified {
-            self.initialize_systems(world);
-            self.rebuild_orders_and_dependencies();
-            self.systems_modified = false;
-            self.executor.rebuild_cached_data(&self.parallel);
-            self.executor_modified = false;
-            if world.contains_resource::<ReportExecutionOrderAmbiguities>() {
-                self.report_ambiguities(world);
-            }
-        } else if self.executor_modified {
-            self.executor.rebuild_cached_data(&self.parallel);
-            self.executor_modified = false;
-        }
+        self.initialize(world);
 
         let mut run_stage_loop = true;
         while run_stage_loop {
@@ -894,10 +1023,10 @@ mod tests {
         query::{ChangeTrackers, Changed},
         schedule::{
             BoxedSystemLabel, ExclusiveSystemDescriptorCoercion, ParallelSystemDescriptorCoercion,
-            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaPiping, ShouldRun,
-            SingleThreadedExecutor, Stage, SystemSet, SystemStage,
+            RunCriteria, RunCriteriaDescriptorCoercion, RunCriteriaPiping, Schedule, ShouldRun,
+            SingleThreadedExecutor, Stage, SystemKind, SystemSet, SystemStage,
         },
-        system::{In, IntoExclusiveSystem, IntoSystem, Local, Query, ResMut},
+        system::{In, IntoExclusiveSystem, IntoSystem, Local, Query, Res, ResMut},
         world::World,
     };
 
@@ -2096,4 +2225,64 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn schedule_graph() {
+        fn read_vec(_: Res<Vec<usize>>, _: Query<&Entity>) {}
+        fn write_vec(_: ResMut<Vec<usize>>) {}
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<usize>::new());
+        let mut schedule = Schedule::default();
+        schedule.add_stage(
+            "update",
+            SystemStage::parallel()
+                .with_system(make_exclusive(0).exclusive_system().at_start())
+                .with_system(read_vec.system().label("read").after("write"))
+                .with_system_set(
+                    SystemSet::new()
+                        .with_run_criteria(every_other_time.system())
+                        .with_system(write_vec.system().label("write")),
+                ),
+        );
+        schedule.add_stage("last", SystemStage::parallel());
+
+        let graph = schedule.graph(&mut world);
+        assert_eq!(graph.stages.len(), 2);
+        assert_eq!(graph.stages[0].label, "update");
+        assert!(graph.stages[1].systems.is_empty());
+
+        let stage = &graph.stages[0];
+        assert_eq!(stage.systems.len(), 3);
+        assert_eq!(stage.systems[0].kind, SystemKind::ExclusiveAtStart);
+        assert!(stage.systems[0].reads_all);
+        let (write, read) = (&stage.systems[1], &stage.systems[2]);
+        assert!(write.name.ends_with("write_vec"));
+        assert_eq!(write.system_set, Some(0));
+        assert_eq!(write.run_criteria, Some(0));
+        assert_eq!(write.writes, vec![std::any::type_name::<Vec<usize>>()]);
+        assert!(read.name.ends_with("read_vec"));
+        assert_eq!(read.labels, vec!["read"]);
+        assert_eq!(read.system_set, None);
+        assert_eq!(read.dependencies, vec![1]);
+        assert_eq!(read.reads.len(), 2);
+        assert!(read
+            .reads
+            .contains(&std::any::type_name::<Vec<usize>>().to_string()));
+        assert!(read.writes.is_empty());
+        assert!(stage.run_criteria[0].name.ends_with("every_other_time"));
+
+        let dot = schedule.to_dot(&mut world);
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains("subgraph cluster_s0_set0"));
+        assert!(dot.contains("s0_1 -> s0_2;"));
+        assert!(dot.contains("s0_rc0 -> s0_1 [style=dashed];"));
+        assert!(dot.contains("s0 -> s1"));
+
+        let json = schedule.to_json(&mut world);
+        assert!(json.starts_with("{\"stages\":[{\"label\":\"update\""));
+        assert!(json.contains("\"kind\":\"Parallel\""));
+        assert!(json.contains("\"dependencies\":[1]"));
+        assert!(json.contains("\"system_set\":0"));
+    }
 }
@@ -1,490 +1,348 @@
 use crate::{
     component::Component,
     schedule::{
-        RunCriteriaDescriptor, RunCriteriaDescriptorCoercion, RunCriteriaLabel, ShouldRun,
-        SystemSet,
+        ExclusiveSystemDescriptor, ExclusiveSystemDescriptorCoercion, RunCriteriaDescriptor,
+        RunCriteriaDescriptorCoercion, RunCriteriaLabel, ShouldRun, Stage, SystemLabel,
+        SystemStage,
     },
-    system::{In, IntoChainSystem, IntoSystem, Local, Res, ResMut},
+    system::{IntoExclusiveSystem, IntoSystem, Res},
+    world::{Mut, World},
 };
+use bevy_utils::HashMap;
 use std::{any::TypeId, fmt::Debug, hash::Hash};
-use thiserror::Error;
 
-/// ### Stack based state machine
+/// Types that can be used as a state machine: [`State<T>`] holds the current value and
+/// [`NextState<T>`] queues transitions.
 ///
-/// This state machine has four operations: Push, Pop, Set and Replace.
-/// * Push pushes a new state to the state stack, pausing the previous state
-/// * Pop removes the current state, and unpauses the last paused state
-/// * Set replaces the active state with a new one
-/// * Replace unwinds the state stack, and replaces the entire stack with a single new state
-#[derive(Debug)]
-pub struct State<T: Component + Clone + Eq> {
-    transition: Option<StateTransition<T>>,
-    stack: Vec<T>,
-    scheduled: Option<ScheduledOperation<T>>,
-    end_next_loop: bool,
-}
+/// Implemented for every type that is `Clone + Eq + Hash + Debug + Send + Sync + 'static`.
+pub trait States: Component + Debug + Clone + Eq + Hash {}
 
-#[derive(Debug)]
-enum StateTransition<T: Component + Clone + Eq> {
-    PreStartup,
-    Startup,
-    // The parameter order is always (leaving, entering)
-    ExitingToResume(T, T),
-    ExitingFull(T, T),
-    Entering(T, T),
-    Resuming(T, T),
-    Pausing(T, T),
-}
+impl<T: Component + Debug + Clone + Eq + Hash> States for T {}
 
-#[derive(Debug)]
-enum ScheduledOperation<T: Component + Clone + Eq> {
-    Set(T),
-    Replace(T),
-    Pop,
-    Push(T),
+/// A state that is derived from another state and can't be set directly.
+///
+/// It is recomputed whenever its source state changes, with the transition schedules of both
+/// states being run as for a regular state. Returning `None` from [`ComputedStates::compute`]
+/// removes the state: its [`State`] resource no longer exists and [`in_state`] is never true.
+///
+/// ```
+/// # use bevy_ecs::schedule::ComputedStates;
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// enum AppState {
+///     Menu,
+///     InGame { paused: bool },
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// struct InGame;
+///
+/// impl ComputedStates for InGame {
+///     type SourceStates = AppState;
+///
+///     fn compute(source: Option<&AppState>) -> Option<Self> {
+///         match source {
+///             Some(AppState::InGame { .. }) => Some(InGame),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait ComputedStates: States {
+    type SourceStates: States;
+
+    fn compute(source: Option<&Self::SourceStates>) -> Option<Self>;
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-enum StateCallback {
-    Update,
-    InactiveUpdate,
-    InStackUpdate,
-    Enter,
-    Exit,
-    Pause,
-    Resume,
-}
+/// A state that only exists while its source state allows it, and can otherwise be set through
+/// [`NextState`] like a regular state.
+///
+/// Whenever it starts existing it is entered with its [`Default`] value, and whenever it stops
+/// existing it is exited and its queued transition is discarded.
+pub trait SubStates: States + Default {
+    type SourceStates: States;
 
-impl StateCallback {
-    fn into_label<T>(self, state: T) -> StateRunCriteriaLabel<T>
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        StateRunCriteriaLabel(state, self)
-    }
+    fn should_exist(source: Option<&Self::SourceStates>) -> bool;
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-struct StateRunCriteriaLabel<T>(T, StateCallback);
-impl<T> RunCriteriaLabel for StateRunCriteriaLabel<T>
-where
-    T: Component + Debug + Clone + Eq + Hash,
-{
-    fn dyn_clone(&self) -> Box<dyn RunCriteriaLabel> {
-        Box::new(self.clone())
-    }
-}
+/// The current value of the state machine `T`.
+///
+/// Changes are queued with [`NextState<T>`] and applied by the state's driver system, see
+/// [`state_driver`]. Use [`in_state`] to only run systems while in a given state.
+#[derive(Debug)]
+pub struct State<T: States>(T);
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-struct DriverLabel(TypeId);
-impl RunCriteriaLabel for DriverLabel {
-    fn dyn_clone(&self) -> Box<dyn RunCriteriaLabel> {
-        Box::new(self.clone())
+impl<T: States> State<T> {
+    pub fn new(initial: T) -> Self {
+        Self(initial)
     }
-}
 
-impl DriverLabel {
-    fn of<T: 'static>() -> Self {
-        Self(TypeId::of::<T>())
+    pub fn current(&self) -> &T {
+        &self.0
     }
 }
 
-impl<T> State<T>
-where
-    T: Component + Debug + Clone + Eq + Hash,
-{
-    pub fn on_update(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, pred: Local<Option<T>>| {
-            state.stack.last().unwrap() == pred.as_ref().unwrap() && state.transition.is_none()
-        })
-        .system()
-        .config(|(_, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::Update.into_label(s))
-    }
-
-    pub fn on_inactive_update(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, mut is_inactive: Local<bool>, pred: Local<Option<T>>| match &state
-            .transition
-        {
-            Some(StateTransition::Pausing(ref relevant, _))
-            | Some(StateTransition::Resuming(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_inactive = !*is_inactive;
-                }
-                false
-            }
-            Some(_) => false,
-            None => *is_inactive,
-        })
-        .system()
-        .config(|(_, _, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::InactiveUpdate.into_label(s))
-    }
-
-    pub fn on_in_stack_update(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, mut is_in_stack: Local<bool>, pred: Local<Option<T>>| match &state
-            .transition
-        {
-            Some(StateTransition::Entering(ref relevant, _))
-            | Some(StateTransition::ExitingToResume(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(StateTransition::ExitingFull(_, ref relevant)) => {
-                if relevant == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(StateTransition::Startup) => {
-                if state.stack.last().unwrap() == pred.as_ref().unwrap() {
-                    *is_in_stack = !*is_in_stack;
-                }
-                false
-            }
-            Some(_) => false,
-            None => *is_in_stack,
-        })
-        .system()
-        .config(|(_, _, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::InStackUpdate.into_label(s))
-    }
-
-    pub fn on_enter(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, pred: Local<Option<T>>| {
-            state
-                .transition
-                .as_ref()
-                .map_or(false, |transition| match transition {
-                    StateTransition::Entering(_, entering) => entering == pred.as_ref().unwrap(),
-                    StateTransition::Startup => {
-                        state.stack.last().unwrap() == pred.as_ref().unwrap()
-                    }
-                    _ => false,
-                })
-        })
-        .system()
-        .config(|(_, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::Enter.into_label(s))
-    }
+/// The next value of the state machine `T`, applied by the next run of its driver system.
+///
+/// Setting a state more than once before the transition happens keeps only the last value, and
+/// setting the current state does nothing.
+#[derive(Debug)]
+pub struct NextState<T: States>(Option<T>);
 
-    pub fn on_exit(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, pred: Local<Option<T>>| {
-            state
-                .transition
-                .as_ref()
-                .map_or(false, |transition| match transition {
-                    StateTransition::ExitingToResume(exiting, _)
-                    | StateTransition::ExitingFull(exiting, _) => exiting == pred.as_ref().unwrap(),
-                    _ => false,
-                })
-        })
-        .system()
-        .config(|(_, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::Exit.into_label(s))
+impl<T: States> Default for NextState<T> {
+    fn default() -> Self {
+        Self(None)
     }
+}
 
-    pub fn on_pause(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, pred: Local<Option<T>>| {
-            state
-                .transition
-                .as_ref()
-                .map_or(false, |transition| match transition {
-                    StateTransition::Pausing(pausing, _) => pausing == pred.as_ref().unwrap(),
-                    _ => false,
-                })
-        })
-        .system()
-        .config(|(_, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::Pause.into_label(s))
+impl<T: States> NextState<T> {
+    /// Queues a transition to `state`.
+    pub fn set(&mut self, state: T) {
+        self.0 = Some(state);
     }
 
-    pub fn on_resume(s: T) -> RunCriteriaDescriptor {
-        (|state: Res<State<T>>, pred: Local<Option<T>>| {
-            state
-                .transition
-                .as_ref()
-                .map_or(false, |transition| match transition {
-                    StateTransition::Resuming(_, resuming) => resuming == pred.as_ref().unwrap(),
-                    _ => false,
-                })
-        })
-        .system()
-        .config(|(_, pred)| *pred = Some(Some(s.clone())))
-        .chain(should_run_adapter::<T>.system())
-        .after(DriverLabel::of::<T>())
-        .label_discard_if_duplicate(StateCallback::Resume.into_label(s))
+    /// The state that will be entered next, if any.
+    pub fn get(&self) -> Option<&T> {
+        self.0.as_ref()
     }
 
-    pub fn on_update_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_update(s))
+    /// Cancels the queued transition.
+    pub fn clear(&mut self) {
+        self.0 = None;
     }
+}
 
-    pub fn on_inactive_update_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_inactive_update(s))
-    }
+/// The stages that are run when the state machine `T` changes value. Stored as a resource.
+///
+/// On a transition from `A` to `B`, the exit stage of `A` runs first, while [`State<T>`] still
+/// holds `A`. [`State<T>`] is then updated, and the transition stage of `(A, B)` and the enter
+/// stage of `B` run in that order. Systems in these stages may queue another transition, which is
+/// applied right after, in the same frame.
+///
+/// The stages are single threaded, as they only run on transitions.
+pub struct StateTransitionSchedules<T: States> {
+    enter: HashMap<T, SystemStage>,
+    exit: HashMap<T, SystemStage>,
+    transition: HashMap<(T, T), SystemStage>,
+    entered_initial: bool,
+}
 
-    pub fn on_enter_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_enter(s))
+impl<T: States> Default for StateTransitionSchedules<T> {
+    fn default() -> Self {
+        Self {
+            enter: Default::default(),
+            exit: Default::default(),
+            transition: Default::default(),
+            entered_initial: false,
+        }
     }
+}
 
-    pub fn on_exit_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_exit(s))
+impl<T: States> StateTransitionSchedules<T> {
+    /// The stage run when entering `state`, including the initial state.
+    pub fn on_enter(&mut self, state: T) -> &mut SystemStage {
+        self.enter
+            .entry(state)
+            .or_insert_with(SystemStage::single_threaded)
     }
 
-    pub fn on_pause_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_pause(s))
+    /// The stage run when exiting `state`.
+    pub fn on_exit(&mut self, state: T) -> &mut SystemStage {
+        self.exit
+            .entry(state)
+            .or_insert_with(SystemStage::single_threaded)
     }
 
-    pub fn on_resume_set(s: T) -> SystemSet {
-        SystemSet::new().with_run_criteria(Self::on_resume(s))
+    /// The stage run when changing from `from` to `to`, between the exit and enter stages.
+    pub fn on_transition(&mut self, from: T, to: T) -> &mut SystemStage {
+        self.transition
+            .entry((from, to))
+            .or_insert_with(SystemStage::single_threaded)
     }
+}
 
-    /// Creates a driver set for the State.
-    ///
-    /// Important note: this set must be inserted **before** all other state-dependant sets to work
-    /// properly!
-    pub fn get_driver() -> SystemSet {
-        SystemSet::default()
-            .with_run_criteria(state_cleaner::<T>.system().label(DriverLabel::of::<T>()))
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+struct StateDriverLabel(TypeId);
+impl SystemLabel for StateDriverLabel {
+    fn dyn_clone(&self) -> Box<dyn SystemLabel> {
+        Box::new(self.clone())
     }
+}
 
-    pub fn new(initial: T) -> Self {
-        Self {
-            stack: vec![initial],
-            transition: Some(StateTransition::PreStartup),
-            scheduled: None,
-            end_next_loop: false,
-        }
+impl StateDriverLabel {
+    fn of<T: 'static>() -> Self {
+        Self(TypeId::of::<T>())
     }
+}
 
-    /// Schedule a state change that replaces the active state with the given state.
-    /// This will fail if there is a scheduled operation, or if the given `state` matches the
-    /// current state
-    pub fn set(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
-        }
-
-        if self.scheduled.is_some() {
-            return Err(StateError::StateAlreadyQueued);
-        }
-
-        self.scheduled = Some(ScheduledOperation::Set(state));
-        Ok(())
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+struct InStateLabel<T>(T);
+impl<T: States> RunCriteriaLabel for InStateLabel<T> {
+    fn dyn_clone(&self) -> Box<dyn RunCriteriaLabel> {
+        Box::new(self.clone())
     }
+}
 
-    /// Same as [Self::set], but if there is already a next state, it will be overwritten
-    /// instead of failing
-    pub fn overwrite_set(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
-        }
-
-        self.scheduled = Some(ScheduledOperation::Set(state));
-        Ok(())
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+struct StateExistsLabel(TypeId);
+impl RunCriteriaLabel for StateExistsLabel {
+    fn dyn_clone(&self) -> Box<dyn RunCriteriaLabel> {
+        Box::new(self.clone())
     }
+}
 
-    /// Schedule a state change that replaces the full stack with the given state.
-    /// This will fail if there is a scheduled operation, or if the given `state` matches the
-    /// current state
-    pub fn replace(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
-        }
-
-        if self.scheduled.is_some() {
-            return Err(StateError::StateAlreadyQueued);
-        }
-
-        self.scheduled = Some(ScheduledOperation::Replace(state));
-        Ok(())
-    }
+/// Run criteria that is satisfied while the state machine `T` is in `state`. Can be used in any
+/// stage.
+pub fn in_state<T: States>(state: T) -> RunCriteriaDescriptor {
+    let expected = state.clone();
+    (move |current: Option<Res<State<T>>>| match current {
+        Some(current) if current.0 == expected => ShouldRun::Yes,
+        _ => ShouldRun::No,
+    })
+    .system()
+    .label_discard_if_duplicate(InStateLabel(state))
+}
 
-    /// Same as [Self::replace], but if there is already a next state, it will be overwritten
-    /// instead of failing
-    pub fn overwrite_replace(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
+/// Run criteria that is satisfied while the state machine `T` exists. Only computed and sub-states
+/// can stop existing.
+pub fn state_exists<T: States>() -> RunCriteriaDescriptor {
+    (|current: Option<Res<State<T>>>| {
+        if current.is_some() {
+            ShouldRun::Yes
+        } else {
+            ShouldRun::No
         }
+    })
+    .system()
+    .label_discard_if_duplicate(StateExistsLabel(TypeId::of::<T>()))
+}
 
-        self.scheduled = Some(ScheduledOperation::Replace(state));
-        Ok(())
-    }
-
-    /// Same as [Self::set], but does a push operation instead of a next operation
-    pub fn push(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
-        }
+/// The exclusive system applying [`NextState<T>`], which enters the initial state the first time
+/// it runs. Requires the [`State<T>`] resource.
+pub fn state_driver<T: States>() -> ExclusiveSystemDescriptor {
+    apply_state_transition::<T>
+        .exclusive_system()
+        .label(StateDriverLabel::of::<T>())
+}
 
-        if self.scheduled.is_some() {
-            return Err(StateError::StateAlreadyQueued);
-        }
+/// The exclusive system updating the computed state `S`. Must be in the same stage as, or a later
+/// stage than, the driver of its source state.
+pub fn computed_state_driver<S: ComputedStates>() -> ExclusiveSystemDescriptor {
+    apply_computed_state::<S>
+        .exclusive_system()
+        .label(StateDriverLabel::of::<S>())
+        .after(StateDriverLabel::of::<S::SourceStates>())
+}
 
-        self.scheduled = Some(ScheduledOperation::Push(state));
-        Ok(())
-    }
+/// The exclusive system updating the sub-state `S` and applying [`NextState<S>`]. Must be in the
+/// same stage as, or a later stage than, the driver of its source state.
+pub fn sub_state_driver<S: SubStates>() -> ExclusiveSystemDescriptor {
+    apply_sub_state::<S>
+        .exclusive_system()
+        .label(StateDriverLabel::of::<S>())
+        .after(StateDriverLabel::of::<S::SourceStates>())
+}
 
-    /// Same as [Self::push], but if there is already a next state, it will be overwritten
-    /// instead of failing
-    pub fn overwrite_push(&mut self, state: T) -> Result<(), StateError> {
-        if self.stack.last().unwrap() == &state {
-            return Err(StateError::AlreadyInState);
-        }
+fn apply_state_transition<T: States>(world: &mut World) {
+    let entered_initial = std::mem::replace(
+        &mut world
+            .get_resource_or_insert_with(StateTransitionSchedules::<T>::default)
+            .entered_initial,
+        true,
+    );
+    if !entered_initial {
+        let initial = world
+            .get_resource::<State<T>>()
+            .expect("State resource must exist for its driver to run")
+            .0
+            .clone();
+        run_transition(world, None, Some(initial));
+    }
+    apply_next_state::<T>(world);
+}
 
-        self.scheduled = Some(ScheduledOperation::Push(state));
-        Ok(())
+fn apply_computed_state<S: ComputedStates>(world: &mut World) {
+    let computed = S::compute(
+        world
+            .get_resource::<State<S::SourceStates>>()
+            .map(State::current),
+    );
+    let current = world
+        .get_resource::<State<S>>()
+        .map(|state| state.0.clone());
+    if computed != current {
+        run_transition(world, current, computed);
     }
+}
 
-    /// Same as [Self::set], but does a pop operation instead of a set operation
-    pub fn pop(&mut self) -> Result<(), StateError> {
-        if self.scheduled.is_some() {
-            return Err(StateError::StateAlreadyQueued);
-        }
-
-        if self.stack.len() == 1 {
-            return Err(StateError::StackEmpty);
+fn apply_sub_state<S: SubStates>(world: &mut World) {
+    let should_exist = S::should_exist(
+        world
+            .get_resource::<State<S::SourceStates>>()
+            .map(State::current),
+    );
+    let current = world
+        .get_resource::<State<S>>()
+        .map(|state| state.0.clone());
+    match (should_exist, current) {
+        (true, None) => {
+            world
+                .get_resource_or_insert_with(NextState::<S>::default)
+                .clear();
+            run_transition(world, None, Some(S::default()));
         }
-
-        self.scheduled = Some(ScheduledOperation::Pop);
-        Ok(())
-    }
-
-    /// Same as [Self::pop], but if there is already a next state, it will be overwritten
-    /// instead of failing
-    pub fn overwrite_pop(&mut self) -> Result<(), StateError> {
-        if self.stack.len() == 1 {
-            return Err(StateError::StackEmpty);
+        (false, Some(current)) => {
+            world
+                .get_resource_or_insert_with(NextState::<S>::default)
+                .clear();
+            run_transition(world, Some(current), None);
         }
-        self.scheduled = Some(ScheduledOperation::Pop);
-        Ok(())
+        _ => {}
     }
-
-    pub fn current(&self) -> &T {
-        self.stack.last().unwrap()
-    }
-
-    pub fn inactives(&self) -> &[T] {
-        self.stack.split_last().map(|(_, rest)| rest).unwrap()
-    }
-}
-
-#[derive(Debug, Error)]
-pub enum StateError {
-    #[error("Attempted to change the state to the current state.")]
-    AlreadyInState,
-    #[error("Attempted to queue a state change, but there was already a state queued.")]
-    StateAlreadyQueued,
-    #[error("Attempted to queue a pop, but there is nothing to pop.")]
-    StackEmpty,
+    apply_next_state::<S>(world);
 }
 
-fn should_run_adapter<T: Component + Clone + Eq>(
-    In(cmp_result): In<bool>,
-    state: Res<State<T>>,
-) -> ShouldRun {
-    if state.end_next_loop {
-        return ShouldRun::No;
-    }
-    if cmp_result {
-        ShouldRun::YesAndCheckAgain
-    } else {
-        ShouldRun::NoAndCheckAgain
+fn apply_next_state<T: States>(world: &mut World) {
+    while let Some(next) = world
+        .get_resource_mut::<NextState<T>>()
+        .and_then(|mut next| next.0.take())
+    {
+        match world.get_resource::<State<T>>() {
+            Some(current) if current.0 != next => {
+                let current = current.0.clone();
+                run_transition(world, Some(current), Some(next));
+            }
+            _ => {}
+        }
     }
 }
 
-fn state_cleaner<T: Component + Clone + Eq>(
-    mut state: ResMut<State<T>>,
-    mut prep_exit: Local<bool>,
-) -> ShouldRun {
-    if *prep_exit {
-        *prep_exit = false;
-        if state.scheduled.is_none() {
-            state.end_next_loop = true;
-            return ShouldRun::YesAndCheckAgain;
-        }
-    } else if state.end_next_loop {
-        state.end_next_loop = false;
-        return ShouldRun::No;
-    }
-    match state.scheduled.take() {
-        Some(ScheduledOperation::Set(next)) => {
-            state.transition = Some(StateTransition::ExitingFull(
-                state.stack.last().unwrap().clone(),
-                next,
-            ));
+fn run_transition<T: States>(world: &mut World, exited: Option<T>, entered: Option<T>) {
+    world.get_resource_or_insert_with(StateTransitionSchedules::<T>::default);
+    world.resource_scope(|world, mut schedules: Mut<StateTransitionSchedules<T>>| {
+        if let Some(stage) = exited
+            .as_ref()
+            .and_then(|exited| schedules.exit.get_mut(exited))
+        {
+            stage.run(world);
         }
-        Some(ScheduledOperation::Replace(next)) => {
-            if state.stack.len() <= 1 {
-                state.transition = Some(StateTransition::ExitingFull(
-                    state.stack.last().unwrap().clone(),
-                    next,
-                ));
-            } else {
-                state.scheduled = Some(ScheduledOperation::Replace(next));
-                match state.transition.take() {
-                    Some(StateTransition::ExitingToResume(p, n)) => {
-                        state.stack.pop();
-                        state.transition = Some(StateTransition::Resuming(p, n));
-                    }
-                    _ => {
-                        state.transition = Some(StateTransition::ExitingToResume(
-                            state.stack[state.stack.len() - 1].clone(),
-                            state.stack[state.stack.len() - 2].clone(),
-                        ));
-                    }
-                }
+        match &entered {
+            Some(entered) => match world.get_resource_mut::<State<T>>() {
+                Some(mut state) => state.0 = entered.clone(),
+                None => world.insert_resource(State(entered.clone())),
+            },
+            None => {
+                world.remove_resource::<State<T>>();
             }
         }
-        Some(ScheduledOperation::Push(next)) => {
-            let last_type_id = state.stack.last().unwrap().clone();
-            state.transition = Some(StateTransition::Pausing(last_type_id, next));
+        if let (Some(exited), Some(entered)) = (exited, entered.clone()) {
+            if let Some(stage) = schedules.transition.get_mut(&(exited, entered)) {
+                stage.run(world);
+            }
         }
-        Some(ScheduledOperation::Pop) => {
-            state.transition = Some(StateTransition::ExitingToResume(
-                state.stack[state.stack.len() - 1].clone(),
-                state.stack[state.stack.len() - 2].clone(),
-            ));
+        if let Some(stage) = entered.and_then(|entered| schedules.enter.get_mut(&entered)) {
+            stage.run(world);
         }
-        None => match state.transition.take() {
-            Some(StateTransition::ExitingFull(p, n)) => {
-                state.transition = Some(StateTransition::Entering(p, n.clone()));
-                *state.stack.last_mut().unwrap() = n;
-            }
-            Some(StateTransition::Pausing(p, n)) => {
-                state.transition = Some(StateTransition::Entering(p, n.clone()));
-                state.stack.push(n);
-            }
-            Some(StateTransition::ExitingToResume(p, n)) => {
-                state.stack.pop();
-                state.transition = Some(StateTransition::Resuming(p, n));
-            }
-            Some(StateTransition::PreStartup) => {
-                state.transition = Some(StateTransition::Startup);
-            }
-            _ => {}
-        },
-    };
-    if state.transition.is_none() {
-        *prep_exit = true;
-    }
-
-    ShouldRun::YesAndCheckAgain
+    });
 }
 
 #[cfg(test)]
@@ -497,190 +355,237 @@ mod test {
         S1,
         S2,
         S3,
-        S4,
-        S5,
-        S6,
-        Final,
     }
 
-    #[test]
-    fn state_test() {
-        let mut world = World::default();
+    // See `make_parallel!` in the stage tests: closures returned from a function can't be systems.
+    macro_rules! log {
+        ($message:expr) => {{
+            fn log(mut log: ResMut<Vec<&'static str>>) {
+                log.push($message)
+            }
+            log
+        }};
+    }
 
+    fn setup(initial: MyState) -> World {
+        let mut world = World::default();
         world.insert_resource(Vec::<&'static str>::new());
-        world.insert_resource(State::new(MyState::S1));
+        world.insert_resource(State::new(initial));
+        world.insert_resource(NextState::<MyState>::default());
+        world.insert_resource(StateTransitionSchedules::<MyState>::default());
+        world
+    }
 
-        let mut stage = SystemStage::parallel();
+    fn schedules(world: &mut World) -> Mut<'_, StateTransitionSchedules<MyState>> {
+        world
+            .get_resource_mut::<StateTransitionSchedules<MyState>>()
+            .unwrap()
+    }
 
-        stage.add_system_set(State::<MyState>::get_driver());
-        stage
-            .add_system_set(
-                State::on_enter_set(MyState::S1)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("startup")).system()),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S1).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S1");
-                        s.overwrite_replace(MyState::S2).unwrap();
-                    })
-                    .system(),
-                ),
-            )
-            .add_system_set(
-                State::on_enter_set(MyState::S2)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("enter S2")).system()),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S2).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S2");
-                        s.overwrite_replace(MyState::S3).unwrap();
-                    })
-                    .system(),
-                ),
-            )
-            .add_system_set(
-                State::on_exit_set(MyState::S2)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("exit S2")).system()),
-            )
-            .add_system_set(
-                State::on_enter_set(MyState::S3)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("enter S3")).system()),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S3).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S3");
-                        s.overwrite_push(MyState::S4).unwrap();
-                    })
-                    .system(),
-                ),
-            )
-            .add_system_set(
-                State::on_pause_set(MyState::S3)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("pause S3")).system()),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S4).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S4");
-                        s.overwrite_push(MyState::S5).unwrap();
-                    })
-                    .system(),
-                ),
-            )
-            .add_system_set(
-                State::on_inactive_update_set(MyState::S4).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>| r.push("inactive S4"))
-                        .system()
-                        .label("inactive s4"),
-                ),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S5).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S5");
-                        s.overwrite_push(MyState::S6).unwrap();
-                    })
-                    .system()
-                    .after("inactive s4"),
-                ),
-            )
-            .add_system_set(
-                State::on_inactive_update_set(MyState::S5).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>| r.push("inactive S5"))
-                        .system()
-                        .label("inactive s5")
-                        .after("inactive s4"),
-                ),
-            )
-            .add_system_set(
-                State::on_update_set(MyState::S6).with_system(
-                    (|mut r: ResMut<Vec<&'static str>>, mut s: ResMut<State<MyState>>| {
-                        r.push("update S6");
-                        s.overwrite_push(MyState::Final).unwrap();
-                    })
+    fn take_log(world: &mut World) -> Vec<&'static str> {
+        std::mem::take(&mut *world.get_resource_mut::<Vec<&'static str>>().unwrap())
+    }
+
+    #[test]
+    fn transitions() {
+        let mut world = setup(MyState::S1);
+        let mut schedules = schedules(&mut world);
+        schedules
+            .on_enter(MyState::S1)
+            .add_system(log!("enter S1").system());
+        schedules
+            .on_exit(MyState::S1)
+            .add_system(log!("exit S1").system());
+        schedules
+            .on_transition(MyState::S1, MyState::S2)
+            .add_system(log!("S1 -> S2").system());
+        schedules.on_enter(MyState::S2).add_system(
+            (|mut log: ResMut<Vec<&'static str>>, mut next: ResMut<NextState<MyState>>| {
+                log.push("enter S2");
+                next.set(MyState::S3);
+            })
+            .system(),
+        );
+        schedules.on_exit(MyState::S2).add_system(
+            (|mut log: ResMut<Vec<&'static str>>, state: Res<State<MyState>>| {
+                assert_eq!(*state.current(), MyState::S2);
+                log.push("exit S2");
+            })
+            .system(),
+        );
+        schedules
+            .on_enter(MyState::S3)
+            .add_system(log!("enter S3").system());
+
+        let mut transitions = SystemStage::single_threaded().with_system(state_driver::<MyState>());
+        let mut update = SystemStage::parallel()
+            .with_system(
+                log!("update S1")
                     .system()
-                    .after("inactive s5"),
-                ),
-            )
-            .add_system_set(
-                State::on_resume_set(MyState::S4)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("resume S4")).system()),
+                    .with_run_criteria(in_state(MyState::S1)),
             )
-            .add_system_set(
-                State::on_exit_set(MyState::S5)
-                    .with_system((|mut r: ResMut<Vec<&'static str>>| r.push("exit S4")).system()),
+            .with_system_set(
+                SystemSet::on_update(MyState::S3).with_system(log!("update S3").system()),
             );
 
-        const EXPECTED: &[&str] = &[
-            //
-            "startup",
-            "update S1",
-            //
-            "enter S2",
-            "update S2",
-            //
-            "exit S2",
-            "enter S3",
-            "update S3",
-            //
-            "pause S3",
-            "update S4",
-            //
-            "inactive S4",
-            "update S5",
-            //
-            "inactive S4",
-            "inactive S5",
-            "update S6",
-            //
-            "inactive S4",
-            "inactive S5",
-        ];
-
-        stage.run(&mut world);
-        let mut collected = world.get_resource_mut::<Vec<&'static str>>().unwrap();
-        let mut count = 0;
-        for (found, expected) in collected.drain(..).zip(EXPECTED) {
-            assert_eq!(found, *expected);
-            count += 1;
-        }
-        // If not equal, some elements weren't executed
-        assert_eq!(EXPECTED.len(), count);
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(take_log(&mut world), vec!["enter S1", "update S1"]);
+
+        // Nothing queued: no transition.
+        transitions.run(&mut world);
+        assert!(take_log(&mut world).is_empty());
+
+        // Queuing twice keeps the last value, and transitions queued while entering a state are
+        // applied in the same frame.
+        let mut next = world.get_resource_mut::<NextState<MyState>>().unwrap();
+        next.set(MyState::S3);
+        next.set(MyState::S2);
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(
+            take_log(&mut world),
+            vec![
+                "exit S1",
+                "S1 -> S2",
+                "enter S2",
+                "exit S2",
+                "enter S3",
+                "update S3"
+            ]
+        );
         assert_eq!(
-            world.get_resource::<State<MyState>>().unwrap().current(),
-            &MyState::Final
+            *world.get_resource::<State<MyState>>().unwrap().current(),
+            MyState::S3
         );
+
+        // Setting the current state does nothing.
+        world
+            .get_resource_mut::<NextState<MyState>>()
+            .unwrap()
+            .set(MyState::S3);
+        transitions.run(&mut world);
+        assert!(take_log(&mut world).is_empty());
     }
 
-    #[test]
-    fn issue_1753() {
-        #[derive(Clone, PartialEq, Eq, Debug, Hash)]
-        enum AppState {
-            Main,
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    struct InS2OrS3;
+
+    impl ComputedStates for InS2OrS3 {
+        type SourceStates = MyState;
+
+        fn compute(source: Option<&MyState>) -> Option<Self> {
+            match source {
+                Some(MyState::S2) | Some(MyState::S3) => Some(InS2OrS3),
+                _ => None,
+            }
         }
+    }
+
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    enum Paused {
+        No,
+        Yes,
+    }
 
-        fn should_run_once(mut flag: ResMut<bool>, test_name: Res<&'static str>) {
-            assert!(!*flag, "{:?}", *test_name);
-            *flag = true;
+    impl Default for Paused {
+        fn default() -> Self {
+            Paused::No
         }
+    }
+
+    impl SubStates for Paused {
+        type SourceStates = MyState;
+
+        fn should_exist(source: Option<&MyState>) -> bool {
+            source == Some(&MyState::S2)
+        }
+    }
+
+    #[test]
+    fn computed_and_sub_states() {
+        let mut world = setup(MyState::S1);
+        let mut computed = StateTransitionSchedules::<InS2OrS3>::default();
+        computed
+            .on_enter(InS2OrS3)
+            .add_system(log!("enter computed").system());
+        computed
+            .on_exit(InS2OrS3)
+            .add_system(log!("exit computed").system());
+        world.insert_resource(computed);
+        let mut paused = StateTransitionSchedules::<Paused>::default();
+        paused
+            .on_enter(Paused::No)
+            .add_system(log!("enter running").system());
+        paused
+            .on_exit(Paused::No)
+            .add_system(log!("exit running").system());
+        paused
+            .on_enter(Paused::Yes)
+            .add_system(log!("enter paused").system());
+        paused
+            .on_exit(Paused::Yes)
+            .add_system(log!("exit paused").system());
+        world.insert_resource(paused);
+
+        // Registered in reverse order: the drivers are sorted after their source's.
+        let mut transitions = SystemStage::single_threaded()
+            .with_system(sub_state_driver::<Paused>())
+            .with_system(computed_state_driver::<InS2OrS3>())
+            .with_system(state_driver::<MyState>());
+        let mut update = SystemStage::single_threaded()
+            .with_system(
+                log!("update computed")
+                    .system()
+                    .with_run_criteria(state_exists::<InS2OrS3>()),
+            )
+            .with_system(
+                log!("update paused")
+                    .system()
+                    .with_run_criteria(in_state(Paused::Yes)),
+            );
+
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert!(take_log(&mut world).is_empty());
+        assert!(world.get_resource::<State<Paused>>().is_none());
+
+        // Ignored while the sub-state doesn't exist.
+        world.insert_resource(NextState(Some(Paused::Yes)));
+        world.insert_resource(NextState(Some(MyState::S2)));
+        transitions.run(&mut world);
+        update.run(&mut world);
+        // The drivers of the computed and sub-state don't depend on each other.
+        let mut log = take_log(&mut world);
+        log[..2].sort_unstable();
+        assert_eq!(
+            log,
+            vec!["enter computed", "enter running", "update computed"]
+        );
+
+        world.insert_resource(NextState(Some(Paused::Yes)));
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(
+            take_log(&mut world),
+            vec![
+                "exit running",
+                "enter paused",
+                "update computed",
+                "update paused"
+            ]
+        );
 
-        let mut world = World::new();
-        world.insert_resource(State::new(AppState::Main));
-        world.insert_resource(false);
-        world.insert_resource("control");
-        let mut stage = SystemStage::parallel().with_system(should_run_once.system());
-        stage.run(&mut world);
-        assert!(*world.get_resource::<bool>().unwrap(), "after control");
-
-        world.insert_resource(false);
-        world.insert_resource("test");
-        let mut stage = SystemStage::parallel()
-            .with_system_set(State::<AppState>::get_driver())
-            .with_system(should_run_once.system());
-        stage.run(&mut world);
-        assert!(*world.get_resource::<bool>().unwrap(), "after test");
+        world.insert_resource(NextState(Some(MyState::S3)));
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(take_log(&mut world), vec!["exit paused", "update computed"]);
+        assert!(world.get_resource::<State<Paused>>().is_none());
+
+        world.insert_resource(NextState(Some(MyState::S1)));
+        transitions.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(take_log(&mut world), vec!["exit computed"]);
+        assert!(world.get_resource::<State<InS2OrS3>>().is_none());
     }
 }
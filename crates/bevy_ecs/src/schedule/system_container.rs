@@ -28,6 +28,8 @@ pub(super) struct ExclusiveSystemContainer {
     system: Box<dyn ExclusiveSystem>,
     pub(super) run_criteria_index: Option<usize>,
     pub(super) run_criteria_label: Option<BoxedRunCriteriaLabel>,
+    /// Index of the [`SystemSet`](crate::schedule::SystemSet) this system was added with.
+    pub(super) system_set: Option<usize>,
     dependencies: Vec<usize>,
     labels: Vec<BoxedSystemLabel>,
     before: Vec<BoxedSystemLabel>,
@@ -41,6 +43,7 @@ impl ExclusiveSystemContainer {
             system: descriptor.system,
             run_criteria_index: None,
             run_criteria_label: None,
+            system_set: None,
             dependencies: Vec::new(),
             labels: descriptor.labels,
             before: descriptor.before,
@@ -107,6 +110,8 @@ pub struct ParallelSystemContainer {
     system: NonNull<dyn System<In = (), Out = ()>>,
     pub(crate) run_criteria_index: Option<usize>,
     pub(crate) run_criteria_label: Option<BoxedRunCriteriaLabel>,
+    /// Index of the [`SystemSet`](crate::schedule::SystemSet) this system was added with.
+    pub(crate) system_set: Option<usize>,
     pub(crate) should_run: bool,
     dependencies: Vec<usize>,
     labels: Vec<BoxedSystemLabel>,
@@ -125,6 +130,7 @@ impl ParallelSystemContainer {
             should_run: false,
             run_criteria_index: None,
             run_criteria_label: None,
+            system_set: None,
             dependencies: Vec::new(),
             labels: descriptor.labels,
             before: descriptor.before,
@@ -3,7 +3,10 @@ use crate::{
         AmbiguitySetLabel, BoxedAmbiguitySetLabel, BoxedSystemLabel, IntoRunCriteria,
         RunCriteriaDescriptorOrLabel, SystemLabel,
     },
-    system::{BoxedSystem, ExclusiveSystem, ExclusiveSystemCoerced, ExclusiveSystemFn, System},
+    system::{
+        BoxedSystem, ExclusiveSystem, ExclusiveSystemCoerced, ExclusiveSystemFn, System,
+        SystemOutput,
+    },
 };
 
 /// Encapsulates a system and information on when it run in a `SystemStage`.
@@ -49,10 +52,11 @@ impl From<ParallelSystemDescriptor> for SystemDescriptor {
 
 impl<S> From<S> for SystemDescriptor
 where
-    S: System<In = (), Out = ()>,
+    S: System<In = ()>,
+    S::Out: SystemOutput,
 {
     fn from(system: S) -> Self {
-        new_parallel_descriptor(Box::new(system)).into()
+        new_parallel_descriptor(S::Out::into_boxed_system(system)).into()
     }
 }
 
@@ -155,29 +159,30 @@ impl ParallelSystemDescriptorCoercion for ParallelSystemDescriptor {
 
 impl<S> ParallelSystemDescriptorCoercion for S
 where
-    S: System<In = (), Out = ()>,
+    S: System<In = ()>,
+    S::Out: SystemOutput,
 {
     fn with_run_criteria<Marker>(
         self,
         run_criteria: impl IntoRunCriteria<Marker>,
     ) -> ParallelSystemDescriptor {
-        new_parallel_descriptor(Box::new(self)).with_run_criteria(run_criteria)
+        new_parallel_descriptor(S::Out::into_boxed_system(self)).with_run_criteria(run_criteria)
     }
 
     fn label(self, label: impl SystemLabel) -> ParallelSystemDescriptor {
-        new_parallel_descriptor(Box::new(self)).label(label)
+        new_parallel_descriptor(S::Out::into_boxed_system(self)).label(label)
     }
 
     fn before(self, label: impl SystemLabel) -> ParallelSystemDescriptor {
-        new_parallel_descriptor(Box::new(self)).before(label)
+        new_parallel_descriptor(S::Out::into_boxed_system(self)).before(label)
     }
 
     fn after(self, label: impl SystemLabel) -> ParallelSystemDescriptor {
-        new_parallel_descriptor(Box::new(self)).after(label)
+        new_parallel_descriptor(S::Out::into_boxed_system(self)).after(label)
     }
 
     fn in_ambiguity_set(self, set: impl AmbiguitySetLabel) -> ParallelSystemDescriptor {
-        new_parallel_descriptor(Box::new(self)).in_ambiguity_set(set)
+        new_parallel_descriptor(S::Out::into_boxed_system(self)).in_ambiguity_set(set)
     }
 }
 
@@ -1,11 +1,7 @@
-use crate::{
-    component::Component,
-    schedule::{
-        AmbiguitySetLabel, BoxedAmbiguitySetLabel, BoxedSystemLabel, IntoRunCriteria,
-        RunCriteriaDescriptorOrLabel, State, SystemDescriptor, SystemLabel,
-    },
+use crate::schedule::{
+    in_state, AmbiguitySetLabel, BoxedAmbiguitySetLabel, BoxedSystemLabel, IntoRunCriteria,
+    RunCriteriaDescriptorOrLabel, States, SystemDescriptor, SystemLabel,
 };
-use std::{fmt::Debug, hash::Hash};
 
 /// A builder for describing several systems at the same time.
 pub struct SystemSet {
@@ -35,53 +31,9 @@ impl SystemSet {
         Default::default()
     }
 
-    pub fn on_update<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_update(s))
-    }
-
-    pub fn on_inactive_update<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_inactive_update(s))
-    }
-
-    pub fn on_in_stack_update<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_in_stack_update(s))
-    }
-
-    pub fn on_enter<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_enter(s))
-    }
-
-    pub fn on_exit<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_exit(s))
-    }
-
-    pub fn on_pause<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_pause(s))
-    }
-
-    pub fn on_resume<T>(s: T) -> SystemSet
-    where
-        T: Component + Debug + Clone + Eq + Hash,
-    {
-        Self::new().with_run_criteria(State::<T>::on_resume(s))
+    /// A set whose systems only run while the state machine `T` is in `state`, see [`in_state`].
+    pub fn on_update<T: States>(state: T) -> SystemSet {
+        Self::new().with_run_criteria(in_state(state))
     }
 
     pub fn in_ambiguity_set(mut self, set: impl AmbiguitySetLabel) -> Self {
@@ -173,6 +173,41 @@ impl BlobVec {
         *self.data.get()
     }
 
+    /// Creates a new vec holding clones of the items of this one.
+    ///
+    /// # Safety
+    /// `clone` must write a valid clone of the item behind its first pointer into the memory
+    /// behind its second pointer, for the type of the items stored in this vec.
+    pub unsafe fn clone_with(&self, clone: unsafe fn(*const u8, *mut u8)) -> BlobVec {
+        let mut blob_vec = BlobVec::new(self.item_layout, self.drop, self.len);
+        blob_vec.clone_from_with(self, clone);
+        blob_vec
+    }
+
+    /// Replaces the items of this vec with clones of the items of `other`, reusing the existing
+    /// allocation when possible.
+    ///
+    /// # Safety
+    /// `other` must store items of the same type as this vec, and `clone` must write a valid clone
+    /// of the item behind its first pointer into the memory behind its second pointer.
+    pub unsafe fn clone_from_with(
+        &mut self,
+        other: &BlobVec,
+        clone: unsafe fn(*const u8, *mut u8),
+    ) {
+        debug_assert_eq!(self.item_layout, other.item_layout);
+        self.clear();
+        self.reserve(other.len);
+        for index in 0..other.len {
+            clone(
+                other.get_unchecked(index),
+                self.get_ptr().as_ptr().add(index * self.item_layout.size()),
+            );
+            // set after each clone, so that a panicking clone only drops the initialized items
+            self.len = index + 1;
+        }
+    }
+
     pub fn clear(&mut self) {
         let len = self.len;
         // We set len to 0 _before_ dropping elements for unwind safety. This ensures we don't
@@ -1,11 +1,11 @@
 use crate::{
-    component::{ComponentId, ComponentInfo, ComponentTicks},
+    component::{CloneFn, ComponentId, ComponentInfo, ComponentTicks},
     entity::Entity,
     storage::BlobVec,
 };
 use std::{cell::UnsafeCell, marker::PhantomData};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SparseArray<I, V = I> {
     values: Vec<Option<V>>,
     marker: PhantomData<I>,
@@ -112,6 +112,11 @@ impl ComponentSparseSet {
         self.dense.len() == 0
     }
 
+    #[inline]
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
     /// Inserts the `entity` key and component `value` pair into this sparse set.
     /// The caller is responsible for ensuring the value is not dropped. This collection will drop
     /// the value when needed.
@@ -219,6 +224,34 @@ impl ComponentSparseSet {
             component_ticks.check_ticks(change_tick);
         }
     }
+
+    /// # Safety
+    /// `clone` must clone values of this set's component. See [`BlobVec::clone_with`].
+    pub(crate) unsafe fn clone_with(&self, clone: CloneFn) -> Self {
+        Self {
+            dense: self.dense.clone_with(clone),
+            ticks: UnsafeCell::new((*self.ticks.get()).clone()),
+            entities: self.entities.clone(),
+            sparse: self.sparse.clone(),
+        }
+    }
+
+    /// # Safety
+    /// `other` must store the same component as this set, and `clone` must clone its values. See
+    /// [`BlobVec::clone_from_with`].
+    pub(crate) unsafe fn clone_from_with(&mut self, other: &ComponentSparseSet, clone: CloneFn) {
+        self.dense.clone_from_with(&other.dense, clone);
+        self.ticks.get_mut().clone_from(&*other.ticks.get());
+        self.entities.clone_from(&other.entities);
+        self.sparse.clone_from(&other.sparse);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.dense.clear();
+        self.ticks.get_mut().clear();
+        self.entities.clear();
+        self.sparse = Default::default();
+    }
 }
 
 #[derive(Debug)]
@@ -358,6 +391,14 @@ impl<I: SparseSetIndex, V> SparseSet<I, V> {
         self.dense.iter()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (&I, &V)> {
+        self.indices.iter().zip(self.dense.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&I, &mut V)> {
+        self.indices.iter().zip(self.dense.iter_mut())
+    }
+
     pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
         self.dense.iter_mut()
     }
@@ -443,6 +484,16 @@ impl SparseSets {
         self.sets.get_mut(component_id)
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (ComponentId, &ComponentSparseSet)> {
+        self.sets.iter().map(|(id, set)| (*id, set))
+    }
+
+    pub(crate) fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (ComponentId, &mut ComponentSparseSet)> {
+        self.sets.iter_mut().map(|(id, set)| (*id, set))
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for set in self.sets.values_mut() {
             set.check_change_ticks(change_tick);
@@ -1,6 +1,6 @@
 use crate::{
     archetype::ArchetypeId,
-    component::{ComponentId, ComponentInfo, ComponentTicks, Components},
+    component::{CloneFn, ComponentId, ComponentInfo, ComponentTicks, Components},
     entity::Entity,
     storage::{BlobVec, SparseSet},
 };
@@ -142,6 +142,30 @@ impl Column {
         self.get_ticks_mut_ptr().add(row)
     }
 
+    /// # Safety
+    /// `clone` must clone values of this column's component. See [`BlobVec::clone_with`].
+    pub(crate) unsafe fn clone_with(&self, clone: CloneFn) -> Column {
+        Column {
+            component_id: self.component_id,
+            data: self.data.clone_with(clone),
+            ticks: UnsafeCell::new((*self.ticks.get()).clone()),
+        }
+    }
+
+    /// # Safety
+    /// `other` must store the same component as this column, and `clone` must clone its values.
+    /// See [`BlobVec::clone_from_with`].
+    pub(crate) unsafe fn clone_from_with(&mut self, other: &Column, clone: CloneFn) {
+        debug_assert_eq!(self.component_id, other.component_id);
+        self.data.clone_from_with(&other.data, clone);
+        self.ticks.get_mut().clone_from(&*other.ticks.get());
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.data.clear();
+        self.ticks.get_mut().clear();
+    }
+
     #[inline]
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         let ticks = unsafe { (*self.ticks.get()).iter_mut() };
@@ -214,6 +238,33 @@ impl Table {
         }
     }
 
+    /// Removes the entity at the given row and returns the entity swapped in to replace it (if an
+    /// entity was swapped in). The values of the columns in `forget` are "forgotten". It is the
+    /// caller's responsibility to drop them
+    ///
+    /// # Safety
+    /// `row` must be in-bounds
+    pub(crate) unsafe fn swap_remove_and_forget_columns_unchecked(
+        &mut self,
+        row: usize,
+        forget: &[ComponentId],
+    ) -> Option<Entity> {
+        for column in self.columns.values_mut() {
+            if forget.contains(&column.component_id) {
+                column.swap_remove_and_forget_unchecked(row);
+            } else {
+                column.swap_remove_unchecked(row);
+            }
+        }
+        let is_last = row == self.entities.len() - 1;
+        self.entities.swap_remove(row);
+        if is_last {
+            None
+        } else {
+            Some(self.entities[row])
+        }
+    }
+
     /// Moves the `row` column values to `new_table`, for the columns shared between both tables.
     /// Returns the index of the new row in `new_table` and the entity in this table swapped in
     /// to replace it (if an entity was swapped in). missing columns will be "forgotten". It is
@@ -377,6 +428,33 @@ impl Table {
         }
     }
 
+    /// Removes and drops every entity of this table.
+    pub(crate) fn clear(&mut self) {
+        self.entities.clear();
+        for column in self.columns.values_mut() {
+            column.clear();
+        }
+    }
+
+    /// Replaces the rows of this table with `entities` and clones of the values in `columns`,
+    /// which must hold one column per column of this table, in the same order.
+    ///
+    /// # Safety
+    /// `clone_fns` must return a function cloning the values of the given component.
+    pub(crate) unsafe fn clone_from_with(
+        &mut self,
+        entities: &[Entity],
+        columns: &[Column],
+        clone_fns: impl Fn(ComponentId) -> CloneFn,
+    ) {
+        self.clear();
+        self.reserve(entities.len());
+        self.entities.extend_from_slice(entities);
+        for (column, other) in self.columns.values_mut().zip(columns) {
+            column.clone_from_with(other, clone_fns(column.component_id));
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Column> {
         self.columns.values()
     }
@@ -459,6 +537,10 @@ impl Tables {
         self.tables.iter()
     }
 
+    pub(crate) fn iter_mut(&mut self) -> std::slice::IterMut<'_, Table> {
+        self.tables.iter_mut()
+    }
+
     pub(crate) fn check_change_ticks(&mut self, change_tick: u32) {
         for table in self.tables.iter_mut() {
             table.check_change_ticks(change_tick);
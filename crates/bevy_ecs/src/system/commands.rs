@@ -1,17 +1,82 @@
 use crate::{
     bundle::Bundle,
     component::Component,
-    entity::{Entities, Entity},
-    world::World,
+    entity::{Disabled, Entities, Entity},
+    observer::{self, Traversal},
+    system::{report_error, BoxedSystem, ErrorHandler, HandledError, System, SystemId},
+    world::{EntityMut, World},
 };
-use bevy_utils::tracing::debug;
+use bevy_utils::tracing::{debug, warn};
 use std::marker::PhantomData;
+use thiserror::Error;
 
 /// A [`World`] mutation.
 pub trait Command: Send + Sync + 'static {
     fn write(self: Box<Self>, world: &mut World);
 }
 
+/// An error that occurs when a [`Command`] is applied to a [`World`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    #[error("{command} failed because entity {entity:?} does not exist")]
+    NoSuchEntity {
+        command: &'static str,
+        entity: Entity,
+    },
+    /// A [`Despawn`] command found its entity already despawned, which commonly happens when
+    /// several systems despawn the same entity.
+    #[error("Despawn failed because entity {entity:?} was already despawned")]
+    AlreadyDespawned { entity: Entity },
+}
+
+/// A resource that decides what happens when a command fails, e.g. because its entity was
+/// despawned by another system. If the resource does not exist, errors are logged:
+/// [`CommandError::AlreadyDespawned`] with [`debug!`](bevy_utils::tracing::debug), other failures
+/// with [`warn!`](bevy_utils::tracing::warn).
+///
+/// Commands prefixed with `try_` ignore failures instead.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::{prelude::*, event::Events, system::{CommandError, CommandErrorHandler}};
+/// fn insert_into_despawned(mut commands: Commands) {
+///     let entity = commands.spawn().id();
+///     commands.entity(entity).despawn();
+///     commands.entity(entity).insert(1u32);
+/// }
+///
+/// let mut world = World::default();
+/// world.insert_resource(Events::<CommandError>::default());
+/// world.insert_resource(CommandErrorHandler::Event);
+///
+/// let mut stage = SystemStage::parallel();
+/// stage.add_system(insert_into_despawned.system());
+/// stage.run(&mut world);
+///
+/// let events = world.get_resource::<Events<CommandError>>().unwrap();
+/// assert_eq!(events.get_reader().iter(events).count(), 1);
+/// ```
+pub type CommandErrorHandler = ErrorHandler<CommandError>;
+
+impl HandledError for CommandError {
+    fn log(&self) {
+        match self {
+            CommandError::AlreadyDespawned { .. } => debug!("{}", self),
+            CommandError::NoSuchEntity { .. } => warn!("{}", self),
+        }
+    }
+}
+
+fn no_such_entity<C: Command>(world: &mut World, entity: Entity) {
+    report_error(
+        world,
+        CommandError::NoSuchEntity {
+            command: std::any::type_name::<C>(),
+            entity,
+        },
+    );
+}
+
 /// A queue of [`Command`]s.
 #[derive(Default)]
 pub struct CommandQueue {
@@ -178,6 +243,52 @@ impl<'a> Commands<'a> {
         });
     }
 
+    /// Spawns an observer of every event of type `E`. See [`World::observe`].
+    pub fn observe<E: Component>(
+        &mut self,
+        system: impl System<In = (), Out = ()>,
+    ) -> EntityCommands<'a, '_> {
+        let mut entity_commands = self.spawn();
+        let observer = entity_commands.id();
+        entity_commands.commands().add(InsertObserver::<E> {
+            observer,
+            target: None,
+            system: Box::new(system),
+            phantom: PhantomData,
+        });
+        entity_commands
+    }
+
+    /// See [`World::trigger`].
+    pub fn trigger<E: Component>(&mut self, event: E) {
+        self.queue.push(TriggerEvent {
+            event,
+            entity: None,
+        });
+    }
+
+    /// See [`World::trigger_entity`].
+    pub fn trigger_entity<E: Component>(&mut self, event: E, entity: Entity) {
+        self.queue.push(TriggerEvent {
+            event,
+            entity: Some(entity),
+        });
+    }
+
+    /// See [`World::trigger_propagated`].
+    pub fn trigger_propagated<T: Traversal, E: Component>(&mut self, event: E, entity: Entity) {
+        self.queue.push(TriggerPropagated::<T, E> {
+            event,
+            entity,
+            phantom: PhantomData,
+        });
+    }
+
+    /// See [`World::run_system`].
+    pub fn run_system(&mut self, id: SystemId) {
+        self.queue.push(RunSystem { id });
+    }
+
     /// Adds a command directly to the command list.
     pub fn add<C: Command>(&mut self, command: C) {
         self.queue.push(command);
@@ -198,6 +309,9 @@ impl<'a, 'b> EntityCommands<'a, 'b> {
     }
 
     /// Adds a [`Bundle`] of components to the current entity.
+    ///
+    /// If the entity does not exist when the command is applied, the error is passed to the
+    /// [`CommandErrorHandler`].
     pub fn insert_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
         self.commands.add(InsertBundle {
             entity: self.entity,
@@ -206,8 +320,19 @@ impl<'a, 'b> EntityCommands<'a, 'b> {
         self
     }
 
+    /// Like [`Self::insert_bundle`], but does nothing if the entity does not exist.
+    pub fn try_insert_bundle(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.commands.add(TryInsertBundle {
+            entity: self.entity,
+            bundle,
+        });
+        self
+    }
+
     /// Adds a single [`Component`] to the current entity.
     ///
+    /// If the entity does not exist when the command is applied, the error is passed to the
+    /// [`CommandErrorHandler`].
     ///
     /// # Warning
     ///
@@ -246,6 +371,25 @@ impl<'a, 'b> EntityCommands<'a, 'b> {
         self
     }
 
+    /// Like [`Self::insert`], but does nothing if the entity does not exist.
+    pub fn try_insert(&mut self, component: impl Component) -> &mut Self {
+        self.commands.add(TryInsert {
+            entity: self.entity,
+            component,
+        });
+        self
+    }
+
+    /// Like [`Self::insert`], but keeps the current value if the entity already has a component
+    /// of the same type.
+    pub fn insert_if_new(&mut self, component: impl Component) -> &mut Self {
+        self.commands.add(InsertIfNew {
+            entity: self.entity,
+            component,
+        });
+        self
+    }
+
     /// See [`EntityMut::remove_bundle`](crate::world::EntityMut::remove_bundle).
     pub fn remove_bundle<T>(&mut self) -> &mut Self
     where
@@ -270,13 +414,100 @@ impl<'a, 'b> EntityCommands<'a, 'b> {
         self
     }
 
+    /// See [`EntityMut::insert_relation`](crate::world::EntityMut::insert_relation).
+    pub fn insert_relation<K: Component>(&mut self, kind: K, target: Entity) -> &mut Self {
+        self.commands.add(InsertRelation {
+            entity: self.entity,
+            kind,
+            target,
+        });
+        self
+    }
+
+    /// See [`EntityMut::remove_relation`](crate::world::EntityMut::remove_relation).
+    pub fn remove_relation<K: Component>(&mut self, target: Entity) -> &mut Self {
+        self.commands.add(RemoveRelation::<K> {
+            entity: self.entity,
+            target,
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// See [`EntityMut::observe`](crate::world::EntityMut::observe).
+    pub fn observe<E: Component>(&mut self, system: impl System<In = (), Out = ()>) -> &mut Self {
+        let observer = self.commands.spawn().id();
+        self.commands.add(InsertObserver::<E> {
+            observer,
+            target: Some(self.entity),
+            system: Box::new(system),
+            phantom: PhantomData,
+        });
+        self
+    }
+
+    /// Disables the current entity by inserting [`Disabled`], so that queries skip it.
+    pub fn disable(&mut self) -> &mut Self {
+        self.insert(Disabled)
+    }
+
+    /// Enables the current entity again by removing [`Disabled`].
+    pub fn enable(&mut self) -> &mut Self {
+        self.remove::<Disabled>()
+    }
+
     /// Despawns only the specified entity, not including its children.
+    ///
+    /// If the entity does not exist when the command is applied, the error is passed to the
+    /// [`CommandErrorHandler`].
     pub fn despawn(&mut self) {
         self.commands.add(Despawn {
             entity: self.entity,
         })
     }
 
+    /// Like [`Self::despawn`], but does nothing if the entity does not exist.
+    pub fn try_despawn(&mut self) {
+        self.commands.add(TryDespawn {
+            entity: self.entity,
+        })
+    }
+
+    /// Runs `func` with full [`EntityMut`] access to the current entity when the command is
+    /// applied. If the entity does not exist by then, the error is passed to the
+    /// [`CommandErrorHandler`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// struct Health(u32);
+    ///
+    /// fn example_system(mut commands: Commands, query: Query<Entity, With<Health>>) {
+    ///     for entity in query.iter() {
+    ///         commands.entity(entity).add(|mut entity| {
+    ///             if entity.get::<Health>().unwrap().0 == 0 {
+    ///                 entity.despawn();
+    ///             } else {
+    ///                 entity.insert(true);
+    ///             }
+    ///         });
+    ///     }
+    /// }
+    /// # example_system.system();
+    /// ```
+    pub fn add<F>(&mut self, func: F) -> &mut Self
+    where
+        F: FnOnce(EntityMut) + Send + Sync + 'static,
+    {
+        self.commands.add(EntityCommand {
+            entity: self.entity,
+            func,
+        });
+        self
+    }
+
     /// Returns the underlying `[Commands]`.
     pub fn commands(&mut self) -> &mut Commands<'a> {
         self.commands
@@ -323,11 +554,27 @@ pub struct Despawn {
 impl Command for Despawn {
     fn write(self: Box<Self>, world: &mut World) {
         if !world.despawn(self.entity) {
-            debug!("Failed to despawn non-existent entity {:?}", self.entity);
+            report_error(
+                world,
+                CommandError::AlreadyDespawned {
+                    entity: self.entity,
+                },
+            );
         }
     }
 }
 
+#[derive(Debug)]
+pub struct TryDespawn {
+    pub entity: Entity,
+}
+
+impl Command for TryDespawn {
+    fn write(self: Box<Self>, world: &mut World) {
+        world.despawn(self.entity);
+    }
+}
+
 pub struct InsertBundle<T> {
     pub entity: Entity,
     pub bundle: T,
@@ -338,7 +585,28 @@ where
     T: Bundle + 'static,
 {
     fn write(self: Box<Self>, world: &mut World) {
-        world.entity_mut(self.entity).insert_bundle(self.bundle);
+        match world.get_entity_mut(self.entity) {
+            Some(mut entity_mut) => {
+                entity_mut.insert_bundle(self.bundle);
+            }
+            None => no_such_entity::<Self>(world, self.entity),
+        }
+    }
+}
+
+pub struct TryInsertBundle<T> {
+    pub entity: Entity,
+    pub bundle: T,
+}
+
+impl<T> Command for TryInsertBundle<T>
+where
+    T: Bundle + 'static,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.insert_bundle(self.bundle);
+        }
     }
 }
 
@@ -353,7 +621,68 @@ where
     T: Component,
 {
     fn write(self: Box<Self>, world: &mut World) {
-        world.entity_mut(self.entity).insert(self.component);
+        match world.get_entity_mut(self.entity) {
+            Some(mut entity_mut) => {
+                entity_mut.insert(self.component);
+            }
+            None => no_such_entity::<Self>(world, self.entity),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TryInsert<T> {
+    pub entity: Entity,
+    pub component: T,
+}
+
+impl<T> Command for TryInsert<T>
+where
+    T: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.insert(self.component);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InsertIfNew<T> {
+    pub entity: Entity,
+    pub component: T,
+}
+
+impl<T> Command for InsertIfNew<T>
+where
+    T: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        match world.get_entity_mut(self.entity) {
+            Some(mut entity_mut) => {
+                if !entity_mut.contains::<T>() {
+                    entity_mut.insert(self.component);
+                }
+            }
+            None => no_such_entity::<Self>(world, self.entity),
+        }
+    }
+}
+
+pub struct EntityCommand<F> {
+    pub entity: Entity,
+    pub func: F,
+}
+
+impl<F> Command for EntityCommand<F>
+where
+    F: FnOnce(EntityMut) + Send + Sync + 'static,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        match world.get_entity_mut(self.entity) {
+            Some(entity_mut) => (self.func)(entity_mut),
+            None => no_such_entity::<Self>(world, self.entity),
+        }
     }
 }
 
@@ -393,6 +722,106 @@ where
     }
 }
 
+#[derive(Debug)]
+pub struct InsertRelation<K> {
+    pub entity: Entity,
+    pub kind: K,
+    pub target: Entity,
+}
+
+impl<K> Command for InsertRelation<K>
+where
+    K: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        match world.get_entity_mut(self.entity) {
+            Some(mut entity_mut) => {
+                entity_mut.insert_relation(self.kind, self.target);
+            }
+            None => no_such_entity::<Self>(world, self.entity),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoveRelation<K> {
+    pub entity: Entity,
+    pub target: Entity,
+    pub phantom: PhantomData<K>,
+}
+
+impl<K> Command for RemoveRelation<K>
+where
+    K: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Some(mut entity_mut) = world.get_entity_mut(self.entity) {
+            entity_mut.remove_relation::<K>(self.target);
+        }
+    }
+}
+
+pub struct InsertObserver<E> {
+    pub observer: Entity,
+    pub target: Option<Entity>,
+    pub system: BoxedSystem,
+    pub phantom: PhantomData<E>,
+}
+
+impl<E> Command for InsertObserver<E>
+where
+    E: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        observer::insert_observer::<E>(world, self.observer, self.target, self.system);
+    }
+}
+
+#[derive(Debug)]
+pub struct TriggerEvent<E> {
+    pub event: E,
+    pub entity: Option<Entity>,
+}
+
+impl<E> Command for TriggerEvent<E>
+where
+    E: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        observer::trigger(world, self.event, self.entity, None);
+    }
+}
+
+#[derive(Debug)]
+pub struct TriggerPropagated<T, E> {
+    pub event: E,
+    pub entity: Entity,
+    pub phantom: PhantomData<T>,
+}
+
+impl<T, E> Command for TriggerPropagated<T, E>
+where
+    T: Traversal,
+    E: Component,
+{
+    fn write(self: Box<Self>, world: &mut World) {
+        observer::trigger_propagated::<T, E>(world, self.event, self.entity);
+    }
+}
+
+#[derive(Debug)]
+pub struct RunSystem {
+    pub id: SystemId,
+}
+
+impl Command for RunSystem {
+    fn write(self: Box<Self>, world: &mut World) {
+        if let Err(error) = world.run_system(self.id) {
+            warn!("Failed to run system: {}", error);
+        }
+    }
+}
+
 pub struct InsertResource<T: Component> {
     pub resource: T,
 }
@@ -417,7 +846,8 @@ impl<T: Component> Command for RemoveResource<T> {
 #[allow(clippy::float_cmp, clippy::approx_constant)]
 mod tests {
     use crate::{
-        system::{CommandQueue, Commands},
+        event::Events,
+        system::{CommandError, CommandErrorHandler, CommandQueue, Commands},
         world::World,
     };
 
@@ -510,4 +940,86 @@ mod tests {
         assert!(!world.contains_resource::<i32>());
         assert!(world.contains_resource::<f64>());
     }
+
+    #[test]
+    fn conditional_inserts() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().insert(1u32).id();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands
+                .entity(entity)
+                .insert_if_new(2u32)
+                .insert_if_new(3u64)
+                .add(|mut entity| {
+                    let value = *entity.get::<u32>().unwrap();
+                    entity.insert(value as u16);
+                });
+        }
+        queue.apply(&mut world);
+        let entity_ref = world.entity(entity);
+        assert_eq!(entity_ref.get::<u32>(), Some(&1));
+        assert_eq!(entity_ref.get::<u64>(), Some(&3));
+        assert_eq!(entity_ref.get::<u16>(), Some(&1));
+    }
+
+    #[test]
+    fn command_errors() {
+        let mut world = World::default();
+        world.insert_resource(Events::<CommandError>::default());
+        world.insert_resource(CommandErrorHandler::Event);
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        world.despawn(entity);
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands
+                .entity(entity)
+                .try_insert(1u32)
+                .try_insert_bundle((1u64,))
+                .try_despawn();
+            commands.entity(entity).insert(1u32).add(|_| unreachable!());
+            commands.entity(entity).despawn();
+        }
+        queue.apply(&mut world);
+        let events = world.get_resource::<Events<CommandError>>().unwrap();
+        let errors = events
+            .get_reader()
+            .iter(events)
+            .map(|error| match error {
+                CommandError::NoSuchEntity {
+                    command,
+                    entity: error_entity,
+                } => {
+                    assert_eq!(*error_entity, entity);
+                    *command
+                }
+                CommandError::AlreadyDespawned {
+                    entity: error_entity,
+                } => {
+                    assert_eq!(*error_entity, entity);
+                    "despawn"
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("Insert<u32>"));
+        assert!(errors[1].contains("EntityCommand"));
+        assert_eq!(errors[2], "despawn");
+    }
+
+    #[test]
+    #[should_panic]
+    fn panicking_command_error_handler() {
+        let mut world = World::default();
+        world.insert_resource(CommandErrorHandler::Panic);
+        let mut queue = CommandQueue::default();
+        let entity = world.spawn().id();
+        world.despawn(entity);
+        Commands::new(&mut queue, &world)
+            .entity(entity)
+            .insert(1u32);
+        queue.apply(&mut world);
+    }
 }
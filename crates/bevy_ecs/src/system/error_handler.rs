@@ -0,0 +1,75 @@
+use crate::{event::Events, world::World};
+use std::fmt::{self, Debug, Display};
+
+/// An error that can be passed to an [`ErrorHandler`], such as a
+/// [`SystemError`](crate::system::SystemError) or a [`CommandError`](crate::system::CommandError).
+pub trait HandledError: Display + Send + Sync + 'static {
+    /// Logs the error; used by [`ErrorHandler::Log`] and whenever the error can't be sent as an
+    /// event.
+    fn log(&self);
+}
+
+/// A resource that decides what happens when a system or a command reports an error of type `E`.
+/// If the resource does not exist, errors are logged.
+///
+/// See [`SystemErrorHandler`](crate::system::SystemErrorHandler) and
+/// [`CommandErrorHandler`](crate::system::CommandErrorHandler).
+pub enum ErrorHandler<E> {
+    /// Logs the error through `bevy_log`.
+    Log,
+    /// Panics with the error.
+    Panic,
+    /// Sends the error to the [`Events<E>`](Events) resource, or logs it if that resource does
+    /// not exist.
+    Event,
+    /// Calls the given function with the world and the error.
+    Custom(fn(&mut World, E)),
+}
+
+impl<E> Clone for ErrorHandler<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for ErrorHandler<E> {}
+
+impl<E> Default for ErrorHandler<E> {
+    fn default() -> Self {
+        ErrorHandler::Log
+    }
+}
+
+impl<E> Debug for ErrorHandler<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorHandler::Log => f.write_str("Log"),
+            ErrorHandler::Panic => f.write_str("Panic"),
+            ErrorHandler::Event => f.write_str("Event"),
+            ErrorHandler::Custom(handler) => f.debug_tuple("Custom").field(handler).finish(),
+        }
+    }
+}
+
+impl<E: HandledError> ErrorHandler<E> {
+    pub fn handle(&self, world: &mut World, error: E) {
+        match self {
+            ErrorHandler::Log => error.log(),
+            ErrorHandler::Panic => panic!("{}", error),
+            ErrorHandler::Event => match world.get_resource_mut::<Events<E>>() {
+                Some(mut events) => events.send(error),
+                None => error.log(),
+            },
+            ErrorHandler::Custom(handler) => handler(world, error),
+        }
+    }
+}
+
+/// Passes `error` to the world's [`ErrorHandler<E>`] resource, or logs it if there is none.
+pub(crate) fn report_error<E: HandledError>(world: &mut World, error: E) {
+    let handler = world
+        .get_resource::<ErrorHandler<E>>()
+        .copied()
+        .unwrap_or_default();
+    handler.handle(world, error);
+}
@@ -0,0 +1,323 @@
+use crate::{
+    archetype::{Archetype, ArchetypeComponentId},
+    component::ComponentId,
+    query::Access,
+    system::{report_error, BoxedSystem, ErrorHandler, HandledError, System, SystemId},
+    world::World,
+};
+use bevy_utils::tracing::error;
+use std::{
+    borrow::Cow,
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// The output of a system that can be added to a [`SystemStage`](crate::schedule::SystemStage).
+///
+/// Implemented for `()` and for `Result<(), E>`. Systems that return a `Result` are wrapped in a
+/// [`FallibleSystem`], which passes errors to the [`SystemErrorHandler`] resource.
+pub trait SystemOutput: Sized {
+    fn into_boxed_system<S: System<In = (), Out = Self>>(system: S) -> BoxedSystem;
+}
+
+impl SystemOutput for () {
+    fn into_boxed_system<S: System<In = (), Out = Self>>(system: S) -> BoxedSystem {
+        Box::new(system)
+    }
+}
+
+impl<E> SystemOutput for Result<(), E>
+where
+    E: Into<Box<dyn Error + Send + Sync>> + 'static,
+{
+    fn into_boxed_system<S: System<In = (), Out = Self>>(system: S) -> BoxedSystem {
+        Box::new(FallibleSystem::new(system))
+    }
+}
+
+/// An error returned by a system, along with the name of that system.
+#[derive(Debug)]
+pub struct SystemError {
+    pub system_name: Cow<'static, str>,
+    pub error: Box<dyn Error + Send + Sync>,
+}
+
+impl Display for SystemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "System {} returned an error: {}",
+            self.system_name, self.error
+        )
+    }
+}
+
+impl HandledError for SystemError {
+    fn log(&self) {
+        error!("{}", self);
+    }
+}
+
+/// A resource that decides what happens when a system returns an error. If the resource does not
+/// exist, errors are logged with [`error!`](bevy_utils::tracing::error).
+///
+/// Errors are handled when the stage applies the failing system's buffers, along with its
+/// [`Commands`](crate::system::Commands), so handlers have mutable access to the world.
+///
+/// A [`SystemStage`](crate::schedule::SystemStage) can override this resource for its own systems
+/// with [`SystemStage::set_error_handler`](crate::schedule::SystemStage::set_error_handler).
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::{prelude::*, system::{SystemError, SystemErrorHandler}};
+/// struct Score(String);
+///
+/// fn parse_score(score: Res<Score>) -> Result<(), std::num::ParseIntError> {
+///     let _score: u32 = score.0.parse()?;
+///     Ok(())
+/// }
+///
+/// fn on_error(_world: &mut World, error: SystemError) {
+///     eprintln!("{} failed: {}", error.system_name, error.error);
+/// }
+///
+/// let mut world = World::default();
+/// world.insert_resource(Score("not a number".to_string()));
+/// world.insert_resource(SystemErrorHandler::Custom(on_error));
+///
+/// let mut stage = SystemStage::parallel();
+/// stage.add_system(parse_score.system());
+/// stage.run(&mut world);
+/// ```
+pub type SystemErrorHandler = ErrorHandler<SystemError>;
+
+/// A [`System`] that wraps a system returning `Result<(), E>` and passes its errors to the
+/// [`SystemErrorHandler`] resource when its buffers are applied.
+///
+/// Systems returning a `Result` are wrapped automatically when they are added to a
+/// [`SystemStage`](crate::schedule::SystemStage).
+pub struct FallibleSystem<S> {
+    system: S,
+    errors: Vec<SystemError>,
+    /// Overrides the [`SystemErrorHandler`] resource, see [`System::set_error_handler`].
+    handler: Option<SystemErrorHandler>,
+}
+
+impl<S> FallibleSystem<S> {
+    pub fn new(system: S) -> Self {
+        Self {
+            system,
+            errors: Vec::new(),
+            handler: None,
+        }
+    }
+}
+
+impl<S, E> System for FallibleSystem<S>
+where
+    S: System<In = (), Out = Result<(), E>>,
+    E: Into<Box<dyn Error + Send + Sync>> + 'static,
+{
+    type In = ();
+    type Out = ();
+
+    fn name(&self) -> Cow<'static, str> {
+        self.system.name()
+    }
+
+    fn id(&self) -> SystemId {
+        self.system.id()
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype) {
+        self.system.new_archetype(archetype);
+    }
+
+    fn component_access(&self) -> &Access<ComponentId> {
+        self.system.component_access()
+    }
+
+    fn archetype_component_access(&self) -> &Access<ArchetypeComponentId> {
+        self.system.archetype_component_access()
+    }
+
+    fn is_send(&self) -> bool {
+        self.system.is_send()
+    }
+
+    fn reserves_entities(&self) -> bool {
+        self.system.reserves_entities()
+    }
+
+    unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) -> Self::Out {
+        if let Err(error) = self.system.run_unsafe(input, world) {
+            self.errors.push(SystemError {
+                system_name: self.system.name(),
+                error: error.into(),
+            });
+        }
+    }
+
+    fn apply_buffers(&mut self, world: &mut World) {
+        self.system.apply_buffers(world);
+        for error in self.errors.drain(..) {
+            match self.handler {
+                Some(handler) => handler.handle(world, error),
+                None => report_error(world, error),
+            }
+        }
+    }
+
+    fn set_error_handler(&mut self, handler: SystemErrorHandler) {
+        self.handler = Some(handler);
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        self.system.initialize(world);
+    }
+
+    fn check_change_tick(&mut self, change_tick: u32) {
+        self.system.check_change_tick(change_tick);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::Events,
+        prelude::*,
+        system::{SystemError, SystemErrorHandler},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct Fail(bool);
+
+    fn fallible(fail: Res<Fail>, mut runs: ResMut<usize>) -> Result<(), String> {
+        *runs += 1;
+        if fail.0 {
+            Err("failed".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn count_error(_world: &mut World, error: SystemError) {
+        assert!(error.system_name.contains("fallible"));
+        assert_eq!(error.error.to_string(), "failed");
+        ERRORS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn errors_are_passed_to_handler() {
+        let mut world = World::default();
+        world.insert_resource(Fail(false));
+        world.insert_resource(0usize);
+        world.insert_resource(SystemErrorHandler::Custom(count_error));
+
+        let mut stage = SystemStage::parallel();
+        stage.add_system(fallible.system().label("fallible"));
+        stage.run(&mut world);
+        assert_eq!(ERRORS.load(Ordering::SeqCst), 0);
+
+        world.insert_resource(Fail(true));
+        stage.run(&mut world);
+        stage.run(&mut world);
+        assert_eq!(ERRORS.load(Ordering::SeqCst), 2);
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "returned an error: failed")]
+    fn panic_handler() {
+        let mut world = World::default();
+        world.insert_resource(Fail(true));
+        world.insert_resource(0usize);
+        world.insert_resource(SystemErrorHandler::Panic);
+
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(fallible.system());
+        stage.run(&mut world);
+    }
+
+    #[test]
+    fn event_handler() {
+        let mut world = World::default();
+        world.insert_resource(Fail(true));
+        world.insert_resource(0usize);
+        world.insert_resource(Events::<SystemError>::default());
+        world.insert_resource(SystemErrorHandler::Event);
+
+        let mut stage = SystemStage::parallel();
+        stage.add_system(fallible.system());
+        stage.run(&mut world);
+        let events = world.get_resource::<Events<SystemError>>().unwrap();
+        let errors: Vec<_> = events.get_reader().iter(events).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            format!("System {} returned an error: failed", errors[0].system_name)
+        );
+    }
+
+    #[test]
+    fn missing_handler_falls_back_to_log() {
+        assert!(matches!(
+            SystemErrorHandler::default(),
+            SystemErrorHandler::Log
+        ));
+
+        let mut world = World::default();
+        world.insert_resource(Fail(true));
+        world.insert_resource(0usize);
+
+        let mut stage = SystemStage::parallel();
+        stage.add_system(fallible.system());
+        // logging the error does not panic or stop the stage
+        stage.run(&mut world);
+        stage.run(&mut world);
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 2);
+        assert!(world.get_resource::<SystemErrorHandler>().is_none());
+    }
+
+    #[test]
+    fn stage_handler_overrides_resource() {
+        static STAGE_ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+        fn count_stage_error(_world: &mut World, _error: SystemError) {
+            STAGE_ERRORS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Fail(true));
+        world.insert_resource(0usize);
+        world.insert_resource(SystemErrorHandler::Panic);
+
+        let mut stage = SystemStage::single_threaded()
+            .with_error_handler(SystemErrorHandler::Custom(count_stage_error));
+        stage.add_system(fallible.system());
+        world.clear_trackers();
+        stage.run(&mut world);
+        assert_eq!(STAGE_ERRORS.load(Ordering::SeqCst), 1);
+        // the resource is left untouched
+        assert!(matches!(
+            world.get_resource::<SystemErrorHandler>(),
+            Some(SystemErrorHandler::Panic)
+        ));
+        assert!(!world.is_resource_changed::<SystemErrorHandler>());
+
+        // handlers set by systems of the stage are kept
+        fn set_log_handler(world: &mut World) {
+            world.insert_resource(SystemErrorHandler::Log);
+        }
+        stage.add_system(set_log_handler.exclusive_system());
+        stage.run(&mut world);
+        assert_eq!(STAGE_ERRORS.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            world.get_resource::<SystemErrorHandler>(),
+            Some(SystemErrorHandler::Log)
+        ));
+    }
+}
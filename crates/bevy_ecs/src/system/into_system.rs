@@ -19,6 +19,7 @@ pub struct SystemState {
     // NOTE: this must be kept private. making a SystemState non-send is irreversible to prevent
     // SystemParams from overriding each other
     is_send: bool,
+    pub(crate) reserves_entities: bool,
     pub(crate) last_change_tick: u32,
 }
 
@@ -29,6 +30,7 @@ impl SystemState {
             archetype_component_access: Access::default(),
             component_access_set: FilteredAccessSet::default(),
             is_send: true,
+            reserves_entities: false,
             id: SystemId::new(),
             last_change_tick: 0,
         }
@@ -208,6 +210,11 @@ where
         self.system_state.is_send
     }
 
+    #[inline]
+    fn reserves_entities(&self) -> bool {
+        self.system_state.reserves_entities
+    }
+
     #[inline]
     unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) -> Self::Out {
         let change_tick = world.increment_change_tick();
@@ -1,19 +1,27 @@
 mod commands;
+mod error_handler;
 mod exclusive_system;
+mod fallible_system;
 mod into_system;
 mod query;
 #[allow(clippy::module_inception)]
 mod system;
 mod system_chaining;
 mod system_param;
+mod system_registry;
+mod system_rng;
 
 pub use commands::*;
+pub use error_handler::*;
 pub use exclusive_system::*;
+pub use fallible_system::*;
 pub use into_system::*;
 pub use query::*;
 pub use system::*;
 pub use system_chaining::*;
 pub use system_param::*;
+pub use system_registry::*;
+pub use system_rng::*;
 
 #[cfg(test)]
 mod tests {
@@ -24,11 +32,11 @@ mod tests {
         bundle::Bundles,
         component::Components,
         entity::{Entities, Entity},
-        query::{Added, Changed, Or, With, Without},
+        query::{Added, Changed, DynamicQuery, Or, With, Without},
         schedule::{Schedule, Stage, SystemStage},
         system::{
-            IntoExclusiveSystem, IntoSystem, Local, Query, QuerySet, RemovedComponents, Res,
-            ResMut, System,
+            DynamicQueryParam, IntoExclusiveSystem, IntoSystem, Local, Query, QuerySet,
+            RemovedComponents, Res, ResMut, System,
         },
         world::{FromWorld, World},
     };
@@ -154,6 +162,40 @@ mod tests {
         assert!(*world.get_resource::<bool>().unwrap(), "system ran");
     }
 
+    #[test]
+    fn query_since_tick() {
+        struct Snapshot(u32);
+
+        fn changed_since_snapshot(
+            mut query: Query<Entity, Changed<A>>,
+            snapshot: Res<Snapshot>,
+            mut changed: ResMut<Vec<Entity>>,
+        ) {
+            *changed = query.since_tick(snapshot.0).iter().collect();
+        }
+
+        let mut world = World::default();
+        world.insert_resource(Snapshot(0));
+        world.insert_resource(Vec::<Entity>::new());
+        let e1 = world.spawn().insert(A).id();
+        let e2 = world.spawn().insert(A).id();
+
+        let mut system = changed_since_snapshot.system();
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        assert_eq!(*world.get_resource::<Vec<Entity>>().unwrap(), vec![e1, e2]);
+
+        // both changes are still newer than the snapshot, even though the system already saw them
+        world.get_mut::<A>(e2).unwrap().set_changed();
+        system.run((), &mut world);
+        assert_eq!(*world.get_resource::<Vec<Entity>>().unwrap(), vec![e1, e2]);
+
+        world.get_resource_mut::<Snapshot>().unwrap().0 = world.read_change_tick();
+        world.increment_change_tick();
+        system.run((), &mut world);
+        assert_eq!(*world.get_resource::<Vec<Entity>>().unwrap(), vec![]);
+    }
+
     #[test]
     fn changed_resource_system() {
         struct Added(usize);
@@ -259,6 +301,39 @@ mod tests {
         run_system(&mut world, sys.system());
     }
 
+    #[test]
+    fn dynamic_query_system() {
+        fn sys(query: DynamicQueryParam, _b: Query<&mut B>, mut count: ResMut<usize>) {
+            *count = query.iter().count();
+        }
+
+        let mut world = World::default();
+        world.insert_resource(0usize);
+        world.spawn().insert_bundle((A, B));
+        world.spawn().insert(A);
+        let a_id = world.components.get_or_insert_id::<A>();
+        run_system(
+            &mut world,
+            sys.system()
+                .config(|config| config.0 = Some(DynamicQuery::new().read(a_id))),
+        );
+        assert_eq!(*world.get_resource::<usize>().unwrap(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_dynamic_query_system() {
+        fn sys(_query: DynamicQueryParam, _a: Query<&A>) {}
+
+        let mut world = World::default();
+        let a_id = world.components.get_or_insert_id::<A>();
+        run_system(
+            &mut world,
+            sys.system()
+                .config(|config| config.0 = Some(DynamicQuery::new().write(a_id))),
+        );
+    }
+
     #[derive(Default)]
     struct BufferRes {
         _buffer: Vec<u8>,
@@ -2,7 +2,9 @@ use crate::{
     component::Component,
     entity::Entity,
     query::{
-        Fetch, FilterFetch, QueryEntityError, QueryIter, QueryState, ReadOnlyFetch, WorldQuery,
+        verify_entities_unique, BatchSize, DynamicItem, DynamicQueryIter, DynamicQueryState, Fetch,
+        FilterFetch, QueryCombinationIter, QueryEntityError, QueryIter, QueryParCombinationIter,
+        QueryParIter, QueryState, ReadOnlyFetch, WorldQuery,
     },
     world::{Mut, World},
 };
@@ -141,6 +143,43 @@ where
         }
     }
 
+    /// Returns a query whose `Added` and `Changed` filters, as well as [`Mut::is_added`] and
+    /// [`Mut::is_changed`], compare against `tick` instead of the last run of this system.
+    ///
+    /// `tick` is usually the `change_tick` of a
+    /// [`SystemChangeTick`](crate::system::SystemChangeTick) stored at an earlier point, such as
+    /// when the last network snapshot was sent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_ecs::system::{SystemChangeTick, IntoSystem};
+    /// struct Position(f32);
+    /// struct LastSnapshot(u32);
+    ///
+    /// fn send_snapshot(
+    ///     mut query: Query<&Position, Changed<Position>>,
+    ///     mut last_snapshot: ResMut<LastSnapshot>,
+    ///     ticks: SystemChangeTick,
+    /// ) {
+    ///     for _position in query.since_tick(last_snapshot.0).iter() {
+    ///         // send the position
+    ///     }
+    ///     last_snapshot.0 = ticks.change_tick;
+    /// }
+    /// # send_snapshot.system();
+    /// ```
+    #[inline]
+    pub fn since_tick(&mut self, tick: u32) -> Query<'_, Q, F> {
+        Query {
+            world: self.world,
+            state: self.state,
+            last_change_tick: tick,
+            change_tick: self.change_tick,
+        }
+    }
+
     /// Returns an [`Iterator`] over the query results.
     ///
     /// This can only be called for read-only queries, see [`Self::iter_mut`] for write-queries.
@@ -182,6 +221,77 @@ where
             .iter_unchecked_manual(self.world, self.last_change_tick, self.change_tick)
     }
 
+    /// Returns an [`Iterator`] over every unordered combination of `K` distinct query results.
+    ///
+    /// This can only be called for read-only queries, see [`Self::iter_combinations_mut`] for
+    /// write-queries.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// struct Position(f32);
+    ///
+    /// fn closest_pair(query: Query<(Entity, &Position)>) {
+    ///     let closest = query
+    ///         .iter_combinations()
+    ///         .min_by(|[(_, a1), (_, a2)], [(_, b1), (_, b2)]| {
+    ///             let distance_a = (a1.0 - a2.0).abs();
+    ///             let distance_b = (b1.0 - b2.0).abs();
+    ///             distance_a.partial_cmp(&distance_b).unwrap()
+    ///         });
+    ///     if let Some([(a, _), (b, _)]) = closest {
+    ///         println!("{:?} and {:?} are closest", a, b);
+    ///     }
+    /// }
+    /// # closest_pair.system();
+    /// ```
+    #[inline]
+    pub fn iter_combinations<const K: usize>(&self) -> QueryCombinationIter<'_, '_, Q, F, K>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.iter_combinations_unchecked_manual(
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Returns an iterator over every unordered combination of `K` distinct query results. Use
+    /// [`QueryCombinationIter::fetch_next`] to get the results.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// struct Velocity(f32);
+    ///
+    /// fn exchange_momentum(mut query: Query<&mut Velocity>) {
+    ///     let mut combinations = query.iter_combinations_mut();
+    ///     while let Some([mut a, mut b]) = combinations.fetch_next() {
+    ///         let average = (a.0 + b.0) / 2.0;
+    ///         a.0 = average;
+    ///         b.0 = average;
+    ///     }
+    /// }
+    /// # exchange_momentum.system();
+    /// ```
+    #[inline]
+    pub fn iter_combinations_mut<const K: usize>(
+        &mut self,
+    ) -> QueryCombinationIter<'_, '_, Q, F, K> {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.iter_combinations_unchecked_manual(
+                self.world,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Runs `f` on each query result. This is faster than the equivalent iter() method, but cannot
     /// be chained like a normal [`Iterator`].
     ///
@@ -219,6 +329,77 @@ where
         };
     }
 
+    /// Returns an [`Iterator`] over the query results of every entity that has a
+    /// [`Relation`](crate::relation::Relation) of kind `K` to `target`.
+    ///
+    /// This can only be called for read-only queries, see [`Self::for_each_related_to_mut`] for
+    /// write-queries.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// struct ChildOf;
+    /// struct Health(u32);
+    /// struct Selected;
+    ///
+    /// fn selected_children_system(
+    ///     selected: Query<Entity, With<Selected>>,
+    ///     children: Query<&Health>,
+    /// ) {
+    ///     for parent in selected.iter() {
+    ///         for health in children.iter_related_to::<ChildOf>(parent) {
+    ///             println!("child health: {}", health.0);
+    ///         }
+    ///     }
+    /// }
+    /// # selected_children_system.system();
+    /// ```
+    #[inline]
+    pub fn iter_related_to<K: Component>(
+        &self,
+        target: Entity,
+    ) -> impl Iterator<Item = <Q::Fetch as Fetch<'_>>::Item> + '_
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        let world = self.world;
+        world.related_to::<K>(target).filter_map(move |entity| {
+            // SAFE: system runs without conflicts with other systems.
+            // same-system queries have runtime borrow checks when they conflict
+            unsafe {
+                self.state
+                    .get_unchecked_manual(world, entity, self.last_change_tick, self.change_tick)
+                    .ok()
+            }
+        })
+    }
+
+    /// Runs `f` on the query result of every entity that has a
+    /// [`Relation`](crate::relation::Relation) of kind `K` to `target`.
+    #[inline]
+    pub fn for_each_related_to_mut<K: Component>(
+        &mut self,
+        target: Entity,
+        mut f: impl FnMut(<Q::Fetch as Fetch<'w>>::Item),
+    ) {
+        let world = self.world;
+        for entity in world.related_to::<K>(target) {
+            // SAFE: system runs without conflicts with other systems. same-system queries have
+            // runtime borrow checks when they conflict. Sources in the relation index are unique,
+            // so no item is handed out twice.
+            let item = unsafe {
+                self.state.get_unchecked_manual(
+                    world,
+                    entity,
+                    self.last_change_tick,
+                    self.change_tick,
+                )
+            };
+            if let Ok(item) = item {
+                f(item);
+            }
+        }
+    }
+
     /// Runs `f` on each query result in parallel using the given task pool.
     ///
     /// This can only be called for read-only queries, see [`Self::par_for_each_mut`] for
@@ -268,6 +449,79 @@ where
         };
     }
 
+    /// Returns a parallel iterator over the query results, which runs on `task_pool`. Unlike
+    /// [`Self::par_for_each`], the batch size is picked automatically unless it is set with
+    /// [`QueryParIter::batch_size`], and results can be returned with [`QueryParIter::map`],
+    /// [`QueryParIter::fold`] and [`QueryParIter::reduce`].
+    ///
+    /// This can only be called for read-only queries, see [`Self::par_iter_mut`] for
+    /// write-queries.
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_tasks::TaskPool;
+    /// struct Mass(f32);
+    ///
+    /// fn total_mass(query: Query<&Mass>, task_pool: Res<TaskPool>) {
+    ///     let total = query
+    ///         .par_iter(&task_pool)
+    ///         .reduce(|mass| mass.0, |a, b| a + b)
+    ///         .unwrap_or(0.0);
+    ///     println!("total mass: {}", total);
+    /// }
+    /// # total_mass.system();
+    /// ```
+    #[inline]
+    pub fn par_iter<'s>(&'s self, task_pool: &'s TaskPool) -> QueryParIter<'w, 's, Q, F>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        QueryParIter {
+            world: self.world,
+            state: self.state,
+            task_pool,
+            batch_size: BatchSize::Auto,
+            last_change_tick: self.last_change_tick,
+            change_tick: self.change_tick,
+        }
+    }
+
+    /// Returns a parallel iterator over the query results, which runs on `task_pool`.
+    #[inline]
+    pub fn par_iter_mut<'s>(&'s mut self, task_pool: &'s TaskPool) -> QueryParIter<'s, 's, Q, F> {
+        QueryParIter {
+            world: self.world,
+            state: self.state,
+            task_pool,
+            batch_size: BatchSize::Auto,
+            last_change_tick: self.last_change_tick,
+            change_tick: self.change_tick,
+        }
+    }
+
+    /// Returns a parallel iterator over every unordered combination of `K` distinct query
+    /// results, which runs on `task_pool`.
+    ///
+    /// This can only be called for read-only queries.
+    #[inline]
+    pub fn par_iter_combinations<'s, const K: usize>(
+        &'s self,
+        task_pool: &'s TaskPool,
+    ) -> QueryParCombinationIter<'w, 's, Q, F, K>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        QueryParCombinationIter {
+            world: self.world,
+            state: self.state,
+            task_pool,
+            batch_size: BatchSize::Auto,
+            last_change_tick: self.last_change_tick,
+            change_tick: self.change_tick,
+        }
+    }
+
     /// Gets the query result for the given [`Entity`].
     ///
     /// This can only be called for read-only queries, see [`Self::get_mut`] for write-queries.
@@ -306,6 +560,64 @@ where
         }
     }
 
+    /// Gets the query results for the given array of [`Entity`]s.
+    ///
+    /// This can only be called for read-only queries, see [`Self::get_many_mut`] for write-queries.
+    #[inline]
+    pub fn get_many<const N: usize>(
+        &self,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch>::Item; N], QueryEntityError>
+    where
+        Q::Fetch: ReadOnlyFetch,
+    {
+        // SAFE: system runs without conflicts with other systems.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.get_many_unchecked_manual(
+                self.world,
+                entities,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Gets the query results for the given array of [`Entity`]s. Returns
+    /// [`QueryEntityError::AliasedMutability`] if an entity appears more than once.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// struct Slot(Option<u32>);
+    /// struct Swap(Entity, Entity);
+    ///
+    /// fn swap_slots(swaps: Res<Vec<Swap>>, mut slots: Query<&mut Slot>) {
+    ///     for swap in swaps.iter() {
+    ///         if let Ok([mut a, mut b]) = slots.get_many_mut([swap.0, swap.1]) {
+    ///             std::mem::swap(&mut a.0, &mut b.0);
+    ///         }
+    ///     }
+    /// }
+    /// # swap_slots.system();
+    /// ```
+    #[inline]
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [Entity; N],
+    ) -> Result<[<Q::Fetch as Fetch>::Item; N], QueryEntityError> {
+        verify_entities_unique(&entities)?;
+        // SAFE: system runs without conflicts with other systems, and the entities are distinct.
+        // same-system queries have runtime borrow checks when they conflict
+        unsafe {
+            self.state.get_many_unchecked_manual(
+                self.world,
+                entities,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
     /// Gets the query result for the given [`Entity`].
     ///
     /// # Safety
@@ -488,3 +800,115 @@ pub enum QuerySingleError {
     #[error("Multiple entities fit the query {0}!")]
     MultipleEntities(&'static str),
 }
+
+/// A system parameter that queries components by [`ComponentId`](crate::component::ComponentId)
+/// according to a [`DynamicQuery`](crate::query::DynamicQuery) set in the system's config.
+///
+/// Its access is checked against the other parameters of the system and scheduled alongside
+/// typed [`Query`]s like any other query.
+///
+/// # Panics
+///
+/// Panics when the system is initialized if no [`DynamicQuery`](crate::query::DynamicQuery) was
+/// set in the config.
+///
+/// # Example
+/// ```
+/// # use bevy_ecs::{
+/// #     component::{ComponentDescriptor, StorageType},
+/// #     prelude::*,
+/// #     query::DynamicQuery,
+/// #     system::DynamicQueryParam,
+/// # };
+/// # struct Health(f32);
+/// fn count(query: DynamicQueryParam) {
+///     println!("{} entities matched", query.iter().count());
+/// }
+///
+/// let mut world = World::new();
+/// let health_id = world
+///     .register_component(ComponentDescriptor::new::<Health>(StorageType::Table))
+///     .unwrap();
+/// let system = count
+///     .system()
+///     .config(|config| config.0 = Some(DynamicQuery::new().read(health_id)));
+/// ```
+pub struct DynamicQueryParam<'w> {
+    pub(crate) world: &'w World,
+    pub(crate) state: &'w DynamicQueryState,
+    pub(crate) last_change_tick: u32,
+    pub(crate) change_tick: u32,
+}
+
+impl<'w> DynamicQueryParam<'w> {
+    /// Returns an iterator over the matched entities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query fetches a component mutably. Use [`DynamicQueryParam::iter_mut`]
+    /// instead.
+    #[inline]
+    pub fn iter(&self) -> DynamicQueryIter<'_, '_> {
+        assert!(
+            self.state.is_read_only(),
+            "DynamicQuery fetches components mutably, use iter_mut instead."
+        );
+        // SAFE: system runs without conflicts with other systems, and the query is read only
+        unsafe {
+            self.state
+                .iter_unchecked_manual(self.world, self.last_change_tick, self.change_tick)
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> DynamicQueryIter<'_, '_> {
+        // SAFE: system runs without conflicts with other systems. same-system queries have runtime
+        // borrow checks when they conflict
+        unsafe {
+            self.state
+                .iter_unchecked_manual(self.world, self.last_change_tick, self.change_tick)
+        }
+    }
+
+    /// Returns the components of `entity`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query fetches a component mutably. Use [`DynamicQueryParam::get_mut`]
+    /// instead.
+    #[inline]
+    pub fn get(&self, entity: Entity) -> Result<DynamicItem<'_, '_>, QueryEntityError> {
+        assert!(
+            self.state.is_read_only(),
+            "DynamicQuery fetches components mutably, use get_mut instead."
+        );
+        // SAFE: system runs without conflicts with other systems, and the query is read only
+        unsafe {
+            self.state.get_unchecked_manual(
+                self.world,
+                entity,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, entity: Entity) -> Result<DynamicItem<'_, '_>, QueryEntityError> {
+        // SAFE: system runs without conflicts with other systems. same-system queries have runtime
+        // borrow checks when they conflict
+        unsafe {
+            self.state.get_unchecked_manual(
+                self.world,
+                entity,
+                self.last_change_tick,
+                self.change_tick,
+            )
+        }
+    }
+
+    #[inline]
+    pub fn state(&self) -> &DynamicQueryState {
+        self.state
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
     archetype::{Archetype, ArchetypeComponentId},
     component::ComponentId,
     query::Access,
+    system::SystemErrorHandler,
     world::World,
 };
 use std::borrow::Cow;
@@ -48,6 +49,12 @@ pub trait System: Send + Sync + 'static {
     fn archetype_component_access(&self) -> &Access<ArchetypeComponentId>;
     /// Returns true if the system is [`Send`].
     fn is_send(&self) -> bool;
+    /// Returns true if the system reserves entities while running, e.g. through
+    /// [`Commands`](crate::system::Commands). The ids handed out then depend on the order such
+    /// systems run in. Defaults to `false`; systems wrapping other systems should forward it.
+    fn reserves_entities(&self) -> bool {
+        false
+    }
     /// Runs the system with the given input in the world. Unlike [`System::run`], this function
     /// takes a shared reference to [`World`] and may therefore break Rust's aliasing rules, making
     /// it unsafe to call.
@@ -66,6 +73,11 @@ pub trait System: Send + Sync + 'static {
         unsafe { self.run_unsafe(input, world) }
     }
     fn apply_buffers(&mut self, world: &mut World);
+    /// Sets the handler of the errors returned by this system, overriding the world's
+    /// [`SystemErrorHandler`] resource. Called by
+    /// [`SystemStage::set_error_handler`](crate::schedule::SystemStage::set_error_handler). Does
+    /// nothing by default, as only [`FallibleSystem`](crate::system::FallibleSystem)s return errors.
+    fn set_error_handler(&mut self, _handler: SystemErrorHandler) {}
     /// Initialize the system.
     fn initialize(&mut self, _world: &mut World);
     fn check_change_tick(&mut self, change_tick: u32);
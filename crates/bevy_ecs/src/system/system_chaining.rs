@@ -87,6 +87,10 @@ impl<SystemA: System, SystemB: System<In = SystemA::Out>> System for ChainSystem
         self.system_a.is_send() && self.system_b.is_send()
     }
 
+    fn reserves_entities(&self) -> bool {
+        self.system_a.reserves_entities() || self.system_b.reserves_entities()
+    }
+
     unsafe fn run_unsafe(&mut self, input: Self::In, world: &World) -> Self::Out {
         let out = self.system_a.run_unsafe(input, world);
         self.system_b.run_unsafe(out, world)
@@ -3,8 +3,11 @@ use crate::{
     bundle::Bundles,
     component::{Component, ComponentId, ComponentTicks, Components},
     entity::{Entities, Entity},
-    query::{FilterFetch, FilteredAccess, FilteredAccessSet, QueryState, WorldQuery},
-    system::{CommandQueue, Commands, Query, SystemState},
+    query::{
+        DynamicQuery, DynamicQueryState, FilterFetch, FilteredAccess, FilteredAccessSet,
+        QueryState, WorldQuery,
+    },
+    system::{CommandQueue, Commands, DynamicQueryParam, Query, SystemState},
     world::{FromWorld, World},
 };
 pub use bevy_ecs_macros::SystemParam;
@@ -135,6 +138,71 @@ where
     }
 }
 
+impl<'a> SystemParam for DynamicQueryParam<'a> {
+    type Fetch = DynamicQueryState;
+}
+
+// SAFE: Relevant query ComponentId and ArchetypeComponentId access is applied to SystemState. If
+// this DynamicQueryState conflicts with any prior access, a panic will occur.
+unsafe impl SystemParamState for DynamicQueryState {
+    type Config = Option<DynamicQuery>;
+
+    fn init(world: &mut World, system_state: &mut SystemState, config: Self::Config) -> Self {
+        let query = config.unwrap_or_else(|| {
+            panic!(
+                "DynamicQueryParam in system {} requires a DynamicQuery to be set in the system's config.",
+                system_state.name
+            )
+        });
+        let state = query.build(world);
+        assert_component_access_compatibility(
+            &system_state.name,
+            std::any::type_name::<DynamicQuery>(),
+            "()",
+            &system_state.component_access_set,
+            &state.component_access,
+            world,
+        );
+        system_state
+            .component_access_set
+            .add(state.component_access.clone());
+        system_state
+            .archetype_component_access
+            .extend(&state.archetype_component_access);
+        state
+    }
+
+    fn new_archetype(&mut self, archetype: &Archetype, system_state: &mut SystemState) {
+        self.new_archetype(archetype);
+        system_state
+            .archetype_component_access
+            .extend(&self.archetype_component_access);
+    }
+
+    fn default_config() -> Option<DynamicQuery> {
+        None
+    }
+}
+
+impl<'a> SystemParamFetch<'a> for DynamicQueryState {
+    type Item = DynamicQueryParam<'a>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'a mut Self,
+        system_state: &'a SystemState,
+        world: &'a World,
+        change_tick: u32,
+    ) -> Self::Item {
+        DynamicQueryParam {
+            world,
+            state,
+            last_change_tick: system_state.last_change_tick,
+            change_tick,
+        }
+    }
+}
+
 fn assert_component_access_compatibility(
     system_name: &str,
     query_type: &'static str,
@@ -350,6 +418,16 @@ impl<'w, T: Component> ResMut<'w, T> {
         self.ticks
             .is_changed(self.last_change_tick, self.change_tick)
     }
+
+    /// Returns the resource without marking it as changed.
+    pub(crate) fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
+
+    /// Marks the resource as changed.
+    pub(crate) fn set_changed(&mut self) {
+        self.ticks.set_changed(self.change_tick);
+    }
 }
 
 impl<'w, T: Component> Deref for ResMut<'w, T> {
@@ -486,7 +564,8 @@ impl<'a> SystemParam for Commands<'a> {
 unsafe impl SystemParamState for CommandQueue {
     type Config = ();
 
-    fn init(_world: &mut World, _system_state: &mut SystemState, _config: Self::Config) -> Self {
+    fn init(_world: &mut World, system_state: &mut SystemState, _config: Self::Config) -> Self {
+        system_state.reserves_entities = true;
         Default::default()
     }
 
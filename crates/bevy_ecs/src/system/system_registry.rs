@@ -0,0 +1,139 @@
+use crate::{
+    system::{BoxedSystem, SystemId},
+    world::World,
+};
+use bevy_utils::HashMap;
+use thiserror::Error;
+
+/// A [`System`](crate::system::System) that is stored outside of a schedule and run on demand.
+///
+/// Keeps track of the archetypes the system has seen, so that its [`Query`](crate::system::Query)
+/// parameters stay up to date between runs.
+pub(crate) struct RegisteredSystem {
+    system: BoxedSystem,
+    archetype_generation: usize,
+}
+
+impl RegisteredSystem {
+    pub(crate) fn new(world: &mut World, mut system: BoxedSystem) -> Self {
+        system.initialize(world);
+        Self {
+            system,
+            archetype_generation: 0,
+        }
+    }
+
+    /// Runs the system and applies its buffers (such as [`Commands`](crate::system::Commands)).
+    pub(crate) fn run(&mut self, world: &mut World) {
+        let archetypes = world.archetypes();
+        for archetype in archetypes.iter().skip(self.archetype_generation) {
+            self.system.new_archetype(archetype);
+        }
+        self.archetype_generation = archetypes.len();
+        self.system.run((), world);
+        self.system.apply_buffers(world);
+    }
+
+    pub(crate) fn into_inner(self) -> BoxedSystem {
+        self.system
+    }
+}
+
+/// The systems registered with [`World::register_system`]. A system is `None` while it runs.
+#[derive(Default)]
+pub(crate) struct SystemRegistry {
+    pub(crate) systems: HashMap<SystemId, Option<RegisteredSystem>>,
+}
+
+/// An error that occurs when running a registered system with [`World::run_system`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RunSystemError {
+    #[error("System {0:?} is not registered")]
+    SystemIdNotRegistered(SystemId),
+    #[error("System {0:?} tried to run itself recursively")]
+    Recursive(SystemId),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::*,
+        system::{Command, CommandQueue, RunSystemError, SystemId},
+    };
+
+    #[derive(Default)]
+    struct Counter(usize);
+
+    #[test]
+    fn run_registered_system() {
+        fn count(mut counter: ResMut<Counter>, mut local: Local<usize>, query: Query<&u32>) {
+            *local += 1;
+            counter.0 = *local * 100 + query.iter().sum::<u32>() as usize;
+        }
+
+        let mut world = World::new();
+        world.insert_resource(Counter::default());
+        let id = world.register_system(count.system());
+
+        world.run_system(id).unwrap();
+        assert_eq!(world.get_resource::<Counter>().unwrap().0, 100);
+
+        // new archetypes are picked up and `Local`s are kept between runs
+        world.spawn().insert(1u32);
+        world.spawn().insert_bundle((2u32, true));
+        world.run_system(id).unwrap();
+        assert_eq!(world.get_resource::<Counter>().unwrap().0, 203);
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands.run_system(id);
+        queue.apply(&mut world);
+        assert_eq!(world.get_resource::<Counter>().unwrap().0, 303);
+
+        assert!(world.unregister_system(id).is_some());
+        assert_eq!(
+            world.run_system(id),
+            Err(RunSystemError::SystemIdNotRegistered(id))
+        );
+    }
+
+    #[test]
+    fn commands_of_registered_system_are_applied() {
+        fn spawn(mut commands: Commands) {
+            commands.spawn().insert(1u32);
+        }
+
+        let mut world = World::new();
+        let id = world.register_system(spawn.system());
+        world.run_system(id).unwrap();
+        world.run_system(id).unwrap();
+        assert_eq!(world.query::<&u32>().iter(&world).count(), 2);
+    }
+
+    #[test]
+    fn recursive_run_is_an_error() {
+        struct Id(SystemId);
+        struct RunResult(Result<(), RunSystemError>);
+        struct RunAndStoreResult(SystemId);
+
+        impl Command for RunAndStoreResult {
+            fn write(self: Box<Self>, world: &mut World) {
+                let result = world.run_system(self.0);
+                world.insert_resource(RunResult(result));
+            }
+        }
+
+        fn recurse(mut commands: Commands, id: Res<Id>) {
+            commands.add(RunAndStoreResult(id.0));
+        }
+
+        let mut world = World::new();
+        let id = world.register_system(recurse.system());
+        world.insert_resource(Id(id));
+        world.run_system(id).unwrap();
+        assert_eq!(
+            world.get_resource::<RunResult>().unwrap().0,
+            Err(RunSystemError::Recursive(id))
+        );
+    }
+}
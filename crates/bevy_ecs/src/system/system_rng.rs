@@ -0,0 +1,140 @@
+use crate::{
+    system::{SystemParam, SystemParamFetch, SystemParamState, SystemState},
+    world::World,
+};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The seed of every [`SystemRng`], read when the system using it is initialized. Defaults to 0
+/// if the resource is missing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemRngSeed(pub u64);
+
+/// A [`SystemParam`] giving a system its own random number generator.
+///
+/// The generator is seeded from the [`SystemRngSeed`] resource and the system's name, so two runs
+/// with the same seed produce the same numbers regardless of the order systems run in. Systems
+/// sharing a name, such as two instances of the same function, draw from identical sequences.
+/// The numbers come from [`ChaCha8Rng`], so they are also the same across platforms and versions
+/// of `rand`.
+///
+/// `SystemRng` implements [`RngCore`], so the methods of [`rand::Rng`] are available on it.
+///
+/// ```
+/// # use bevy_ecs::{prelude::*, system::{SystemRng, SystemRngSeed}};
+/// use rand::Rng;
+///
+/// struct Roll(u32);
+///
+/// fn roll(mut rng: SystemRng, mut last: ResMut<Roll>) {
+///     last.0 = rng.gen_range(1..=6);
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(SystemRngSeed(42));
+/// world.insert_resource(Roll(0));
+/// let mut stage = SystemStage::single_threaded().with_system(roll.system());
+/// stage.run(&mut world);
+/// assert!((1..=6).contains(&world.get_resource::<Roll>().unwrap().0));
+/// ```
+pub struct SystemRng<'a>(&'a mut ChaCha8Rng);
+
+impl<'a> RngCore for SystemRng<'a> {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+/// The [`SystemParamState`] of [`SystemRng`].
+pub struct SystemRngState(ChaCha8Rng);
+
+impl<'a> SystemParam for SystemRng<'a> {
+    type Fetch = SystemRngState;
+}
+
+// SAFE: only local state is accessed
+unsafe impl SystemParamState for SystemRngState {
+    type Config = ();
+
+    fn init(world: &mut World, system_state: &mut SystemState, _config: Self::Config) -> Self {
+        let seed = world
+            .get_resource::<SystemRngSeed>()
+            .map_or(0, |seed| seed.0);
+        Self(ChaCha8Rng::seed_from_u64(fxhash::hash64(&(
+            seed,
+            &*system_state.name,
+        ))))
+    }
+
+    fn default_config() {}
+}
+
+impl<'a> SystemParamFetch<'a> for SystemRngState {
+    type Item = SystemRng<'a>;
+
+    #[inline]
+    unsafe fn get_param(
+        state: &'a mut Self,
+        _system_state: &'a SystemState,
+        _world: &'a World,
+        _change_tick: u32,
+    ) -> Self::Item {
+        SystemRng(&mut state.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SystemRng, SystemRngSeed};
+    use crate::{
+        schedule::{Stage, SystemStage},
+        system::{IntoSystem, ResMut},
+        world::World,
+    };
+    use rand::RngCore;
+
+    fn draw(seed: u64) -> Vec<u64> {
+        fn first(mut rng: SystemRng, mut values: ResMut<Vec<u64>>) {
+            values.push(rng.next_u64());
+        }
+        fn second(mut rng: SystemRng, mut values: ResMut<Vec<u64>>) {
+            values.push(rng.next_u64());
+        }
+
+        let mut world = World::new();
+        world.insert_resource(SystemRngSeed(seed));
+        world.insert_resource(Vec::<u64>::new());
+        let mut stage = SystemStage::single_threaded()
+            .with_system(first.system())
+            .with_system(second.system());
+        stage.run(&mut world);
+        stage.run(&mut world);
+        world.remove_resource::<Vec<u64>>().unwrap()
+    }
+
+    #[test]
+    fn reproducible_per_system_streams() {
+        let values = draw(7);
+        assert_eq!(values, draw(7));
+        assert_ne!(values, draw(8));
+        // Each system has its own stream, and each draw advances it.
+        assert_ne!(values[0], values[1]);
+        assert_ne!(values[0], values[2]);
+    }
+}
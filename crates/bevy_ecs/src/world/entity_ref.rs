@@ -1,12 +1,17 @@
 use crate::{
     archetype::{Archetype, ArchetypeId, Archetypes, ComponentStatus},
-    bundle::{Bundle, BundleInfo},
-    component::{Component, ComponentId, ComponentTicks, Components, StorageType},
-    entity::{Entities, Entity, EntityLocation},
+    bundle::{Bundle, BundleId, BundleInfo},
+    component::{Component, ComponentHook, ComponentId, ComponentTicks, Components, StorageType},
+    entity::{Entities, Entity, EntityLocation, EntityMap},
+    observer,
+    query::{Ptr, PtrMut},
+    relation::{Relation, Relations},
     storage::{SparseSet, Storages},
+    system::System,
     world::{Mut, World},
 };
-use std::any::TypeId;
+use bevy_utils::HashSet;
+use std::{any::TypeId, ptr::NonNull};
 
 pub struct EntityRef<'w> {
     world: &'w World,
@@ -85,6 +90,25 @@ impl<'w> EntityRef<'w> {
                 change_tick,
             })
     }
+
+    /// Returns a pointer to the component with the given id, which may not have a Rust type.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<Ptr<'w>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| Ptr {
+                    ptr: NonNull::new_unchecked(value),
+                    ticks: &*ticks,
+                    last_change_tick: self.world.last_change_tick(),
+                    change_tick: self.world.read_change_tick(),
+                },
+            )
+        }
+    }
 }
 
 pub struct EntityMut<'w> {
@@ -182,6 +206,47 @@ impl<'w> EntityMut<'w> {
             })
     }
 
+    /// Returns a pointer to the component with the given id, which may not have a Rust type.
+    #[inline]
+    pub fn get_by_id(&self, component_id: ComponentId) -> Option<Ptr<'_>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid. lifetimes enforce
+        // valid usage of the world
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| Ptr {
+                    ptr: NonNull::new_unchecked(value),
+                    ticks: &*ticks,
+                    last_change_tick: self.world.last_change_tick(),
+                    change_tick: self.world.read_change_tick(),
+                },
+            )
+        }
+    }
+
+    /// Returns a mutable pointer to the component with the given id, which may not have a Rust
+    /// type.
+    #[inline]
+    pub fn get_mut_by_id(&mut self, component_id: ComponentId) -> Option<PtrMut<'_>> {
+        if !self.contains_id(component_id) {
+            return None;
+        }
+        // SAFE: the entity has the component, so `component_id` is valid. world access is unique
+        // through `&mut self`
+        unsafe {
+            get_component_and_ticks(self.world, component_id, self.entity, self.location).map(
+                |(value, ticks)| PtrMut {
+                    ptr: NonNull::new_unchecked(value),
+                    ticks: &mut *ticks,
+                    last_change_tick: self.world.last_change_tick(),
+                    change_tick: self.world.read_change_tick(),
+                },
+            )
+        }
+    }
+
     // TODO: move relevant methods to World (add/remove bundle)
     pub fn insert_bundle<T: Bundle>(&mut self, bundle: T) -> &mut Self {
         let entity = self.entity;
@@ -194,74 +259,6 @@ impl<'w> EntityMut<'w> {
         let bundle_info = self.world.bundles.init_info::<T>(components);
         let current_location = self.location;
 
-        // Use a non-generic function to cut down on monomorphization
-        unsafe fn get_insert_bundle_info<'a>(
-            entities: &mut Entities,
-            archetypes: &'a mut Archetypes,
-            components: &mut Components,
-            storages: &mut Storages,
-            bundle_info: &BundleInfo,
-            current_location: EntityLocation,
-            entity: Entity,
-        ) -> (&'a Archetype, &'a Vec<ComponentStatus>, EntityLocation) {
-            // SAFE: component ids in `bundle_info` and self.location are valid
-            let new_archetype_id = add_bundle_to_archetype(
-                archetypes,
-                storages,
-                components,
-                current_location.archetype_id,
-                bundle_info,
-            );
-            if new_archetype_id == current_location.archetype_id {
-                let archetype = &archetypes[current_location.archetype_id];
-                let edge = archetype.edges().get_add_bundle(bundle_info.id).unwrap();
-                (archetype, &edge.bundle_status, current_location)
-            } else {
-                let (old_table_row, old_table_id) = {
-                    let old_archetype = &mut archetypes[current_location.archetype_id];
-                    let result = old_archetype.swap_remove(current_location.index);
-                    if let Some(swapped_entity) = result.swapped_entity {
-                        entities.meta[swapped_entity.id as usize].location = current_location;
-                    }
-                    (result.table_row, old_archetype.table_id())
-                };
-
-                let new_table_id = archetypes[new_archetype_id].table_id();
-
-                let new_location = if old_table_id == new_table_id {
-                    archetypes[new_archetype_id].allocate(entity, old_table_row)
-                } else {
-                    let (old_table, new_table) =
-                        storages.tables.get_2_mut(old_table_id, new_table_id);
-                    // PERF: store "non bundle" components in edge, then just move those to avoid
-                    // redundant copies
-                    let move_result =
-                        old_table.move_to_superset_unchecked(old_table_row, new_table);
-
-                    let new_location =
-                        archetypes[new_archetype_id].allocate(entity, move_result.new_row);
-                    // if an entity was moved into this entity's table spot, update its table row
-                    if let Some(swapped_entity) = move_result.swapped_entity {
-                        let swapped_location = entities.get(swapped_entity).unwrap();
-                        archetypes[swapped_location.archetype_id]
-                            .set_entity_table_row(swapped_location.index, old_table_row);
-                    }
-                    new_location
-                };
-
-                entities.meta[entity.id as usize].location = new_location;
-                let (old_archetype, new_archetype) =
-                    archetypes.get_2_mut(current_location.archetype_id, new_archetype_id);
-                let edge = old_archetype
-                    .edges()
-                    .get_add_bundle(bundle_info.id)
-                    .unwrap();
-                (&*new_archetype, &edge.bundle_status, new_location)
-
-                // Sparse set components are intentionally ignored here. They don't need to move
-            }
-        }
-
         let (archetype, bundle_status, new_location) = unsafe {
             get_insert_bundle_info(
                 entities,
@@ -289,10 +286,30 @@ impl<'w> EntityMut<'w> {
                 change_tick,
             )
         };
+        let hooks = get_insert_hooks(components, bundle_info, bundle_status);
+        self.run_hooks(hooks);
         self
     }
 
     pub fn remove_bundle<T: Bundle>(&mut self) -> Option<T> {
+        let bundle_info = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components);
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        if bundle_info
+            .component_ids
+            .iter()
+            .all(|component_id| archetype.contains(*component_id))
+        {
+            let hooks = get_remove_hooks(
+                &self.world.components,
+                archetype,
+                bundle_info.component_ids.iter().cloned(),
+            );
+            self.run_hooks(hooks);
+        }
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
@@ -378,13 +395,30 @@ impl<'w> EntityMut<'w> {
 
     /// Remove any components in the bundle that the entity has.
     pub fn remove_bundle_intersection<T: Bundle>(&mut self) {
+        let bundle_id = self
+            .world
+            .bundles
+            .init_info::<T>(&mut self.world.components)
+            .id;
+        self.remove_bundle_intersection_by_id(bundle_id);
+    }
+
+    fn remove_bundle_intersection_by_id(&mut self, bundle_id: BundleId) {
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
+        let hooks = get_remove_hooks(
+            &self.world.components,
+            &self.world.archetypes[self.location.archetype_id],
+            bundle_info.component_ids.iter().cloned(),
+        );
+        self.run_hooks(hooks);
+
         let archetypes = &mut self.world.archetypes;
         let storages = &mut self.world.storages;
         let components = &mut self.world.components;
         let entities = &mut self.world.entities;
         let removed_components = &mut self.world.removed_components;
 
-        let bundle_info = self.world.bundles.init_info::<T>(components);
+        let bundle_info = self.world.bundles.get(bundle_id).unwrap();
         let old_location = self.location;
         let new_archetype_id = unsafe {
             remove_bundle_from_archetype(
@@ -465,9 +499,156 @@ impl<'w> EntityMut<'w> {
         self.remove_bundle::<(T,)>().map(|v| v.0)
     }
 
-    pub fn despawn(self) {
+    /// Inserts the component with the given id, taking ownership of the value behind `value`.
+    /// This also works for components that have no Rust type, like those registered with
+    /// [ComponentDescriptor::new_with_layout](crate::component::ComponentDescriptor::new_with_layout).
+    ///
+    /// # Panics
+    /// Panics if `component_id` is not registered in the [World].
+    ///
+    /// # Safety
+    /// `value` must point to a valid value of the component, matching its layout. The value is
+    /// moved into the world, so the caller must not use or drop it afterwards.
+    pub unsafe fn insert_by_id(&mut self, component_id: ComponentId, value: *mut u8) -> &mut Self {
+        self.insert_by_ids(&[component_id], &[value])
+    }
+
+    /// Inserts the components with the given ids at once, taking ownership of the values behind
+    /// `values`. See [EntityMut::insert_by_id].
+    ///
+    /// # Panics
+    /// Panics if a component is not registered in the [World], if a component id is given more
+    /// than once or if `component_ids` and `values` have different lengths.
+    ///
+    /// # Safety
+    /// Each value must point to a valid value of the component at the same index, matching its
+    /// layout. The values are moved into the world, so the caller must not use or drop them
+    /// afterwards.
+    pub unsafe fn insert_by_ids(
+        &mut self,
+        component_ids: &[ComponentId],
+        values: &[*mut u8],
+    ) -> &mut Self {
+        assert_eq!(
+            component_ids.len(),
+            values.len(),
+            "Each inserted component needs exactly one value"
+        );
+        let entity = self.entity;
+        let change_tick = self.world.change_tick();
+        let entities = &mut self.world.entities;
+        let archetypes = &mut self.world.archetypes;
+        let components = &mut self.world.components;
+        let storages = &mut self.world.storages;
+
+        let bundle_info = self
+            .world
+            .bundles
+            .init_dynamic_info(components, component_ids);
+        let (archetype, bundle_status, new_location) = get_insert_bundle_info(
+            entities,
+            archetypes,
+            components,
+            storages,
+            bundle_info,
+            self.location,
+            entity,
+        );
+        self.location = new_location;
+
+        let table = &storages.tables[archetype.table_id()];
+        let table_row = archetype.entity_table_row(new_location.index);
+        for (bundle_component, value) in values.iter().enumerate() {
+            bundle_info.write_component(
+                &mut storages.sparse_sets,
+                entity,
+                table,
+                table_row,
+                bundle_component,
+                bundle_status,
+                *value,
+                change_tick,
+            );
+        }
+        let hooks = get_insert_hooks(components, bundle_info, bundle_status);
+        self.run_hooks(hooks);
+        self
+    }
+
+    /// Removes and drops the component with the given id. Returns `false` if the entity did not
+    /// have the component.
+    pub fn remove_by_id(&mut self, component_id: ComponentId) -> bool {
+        if !self.contains_id(component_id) {
+            return false;
+        }
+        let bundle_id = self
+            .world
+            .bundles
+            .init_dynamic_info(&self.world.components, &[component_id])
+            .id;
+        self.remove_bundle_intersection_by_id(bundle_id);
+        true
+    }
+
+    /// Adds a [`Relation`] edge of kind `K` from this entity to `target`. If the edge already
+    /// exists, its data is replaced with `kind`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `target` does not exist.
+    pub fn insert_relation<K: Component>(&mut self, kind: K, target: Entity) -> &mut Self {
+        assert!(
+            self.world.entities.contains(target),
+            "Could not insert a {} relation to {:?} because the target does not exist.",
+            std::any::type_name::<K>(),
+            target
+        );
+        let component_id = self.world.init_relation::<K>();
+        if let Some(mut relation) = self.get_mut::<Relation<K>>() {
+            relation.insert(target, kind);
+        } else {
+            self.insert(Relation::new(target, kind));
+        }
+        self.world
+            .relations
+            .add_edge(component_id, self.entity, target);
+        self
+    }
+
+    /// Removes the [`Relation`] edge of kind `K` from this entity to `target` and returns its data,
+    /// if it exists. The `Relation<K>` component is removed along with the last edge.
+    pub fn remove_relation<K: Component>(&mut self, target: Entity) -> Option<K> {
+        let mut relation = self.get_mut::<Relation<K>>()?;
+        let kind = relation.remove(target)?;
+        if relation.is_empty() {
+            self.remove::<Relation<K>>();
+        }
+        let component_id = self.world.init_relation::<K>();
+        self.world
+            .relations
+            .remove_edge(component_id, self.entity, target);
+        Some(kind)
+    }
+
+    /// Registers `system` as an observer of the events of type `E` that target this entity. The
+    /// observer is despawned along with this entity.
+    ///
+    /// See [`World::observe`] and [`World::trigger_entity`].
+    pub fn observe<E: Component>(&mut self, system: impl System<In = (), Out = ()>) -> &mut Self {
+        let observer = self.world.spawn().id();
+        observer::insert_observer::<E>(self.world, observer, Some(self.entity), Box::new(system));
+        self.update_location();
+        self
+    }
+
+    pub fn despawn(mut self) {
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        let hooks = get_remove_hooks(&self.world.components, archetype, archetype.components());
+        self.run_hooks(hooks);
+
         let world = self.world;
         world.flush();
+        Relations::clear_entity(world, self.entity);
         let location = world
             .entities
             .free(self.entity)
@@ -505,6 +686,119 @@ impl<'w> EntityMut<'w> {
         }
     }
 
+    /// Moves this entity and its components to `destination`, where they are spawned as a new
+    /// entity, and returns that entity. Component values are moved as raw data, so this also works
+    /// for components that are not reflected or have no Rust type. Components that are not
+    /// registered in `destination` yet are registered with the same storage type and hooks.
+    /// [World-local](crate::component::ComponentInfo::is_world_local) components, like the
+    /// observers of the entity, are dropped instead: a moved observer is no longer an observer.
+    ///
+    /// This world removes the entity as if it was despawned: `on_remove` hooks run and its
+    /// relations are cleared. In `destination`, the moved components are added like any other
+    /// insert. Relations are moved too, but only an edge from the entity to itself is kept, as the
+    /// other targets live in this world.
+    ///
+    /// Components that refer to other entities still hold entities of this world. Use
+    /// [World::extract_entities] to move a group of entities while keeping the relations between
+    /// them, and [World::map_entities] to remap the other references between them.
+    pub fn move_to(self, destination: &mut World) -> Entity {
+        let entity = self.entity;
+        let mut kept_sources = HashSet::default();
+        kept_sources.insert(entity);
+        let moved = self.move_to_except(destination, &kept_sources);
+        let mut entity_map = EntityMap::default();
+        entity_map.insert(entity, moved);
+        Relations::map_moved(destination, &entity_map);
+        moved
+    }
+
+    /// Moves this entity to `destination` like [EntityMut::move_to], without remapping its
+    /// relations. Relations from `kept_sources` to this entity are kept in their source components,
+    /// so they can be remapped after those sources are moved as well.
+    pub(crate) fn move_to_except(
+        mut self,
+        destination: &mut World,
+        kept_sources: &HashSet<Entity>,
+    ) -> Entity {
+        let archetype = &self.world.archetypes[self.location.archetype_id];
+        let hooks = get_remove_hooks(&self.world.components, archetype, archetype.components());
+        self.run_hooks(hooks);
+
+        let world = self.world;
+        world.flush();
+        Relations::clear_entity_except(world, self.entity, kept_sources);
+        let location = world
+            .entities
+            .free(self.entity)
+            .expect("entity should exist at this point.");
+
+        let mut moved_components = Vec::new();
+        let mut destination_components = Vec::new();
+        let mut values = Vec::new();
+        for component_id in world.archetypes[location.archetype_id].components() {
+            // SAFE: archetypes only contain registered components and the entity has the component
+            // at `location`
+            unsafe {
+                let info = world.components.get_info_unchecked(component_id);
+                if info.is_world_local() {
+                    continue;
+                }
+                destination_components.push(match world.relations.init_fn(component_id) {
+                    Some(init_relation) => init_relation(destination),
+                    None => destination.components.init_matching(info),
+                });
+                values.push(get_component(world, component_id, self.entity, location).unwrap());
+            }
+            moved_components.push(component_id);
+        }
+
+        let mut destination_entity = destination.spawn();
+        // SAFE: each value is a valid value of the matching destination component, and the values
+        // are forgotten by this world below
+        unsafe { destination_entity.insert_by_ids(&destination_components, &values) };
+        let destination_entity = destination_entity.id();
+
+        let table_row;
+        let moved_entity;
+        {
+            let archetype = &mut world.archetypes[location.archetype_id];
+            for component_id in archetype.components() {
+                let removed_components = world
+                    .removed_components
+                    .get_or_insert_with(component_id, Vec::new);
+                removed_components.push(self.entity);
+            }
+            let remove_result = archetype.swap_remove(location.index);
+            if let Some(swapped_entity) = remove_result.swapped_entity {
+                world.entities.meta[swapped_entity.id as usize].location = location;
+            }
+            table_row = remove_result.table_row;
+
+            for component_id in archetype.sparse_set_components() {
+                let sparse_set = world.storages.sparse_sets.get_mut(*component_id).unwrap();
+                if moved_components.contains(component_id) {
+                    sparse_set.remove_and_forget(self.entity);
+                } else {
+                    sparse_set.remove(self.entity);
+                }
+            }
+            // SAFE: table rows stored in archetypes always exist and the moved values are owned by
+            // `destination` now
+            moved_entity = unsafe {
+                world.storages.tables[archetype.table_id()]
+                    .swap_remove_and_forget_columns_unchecked(table_row, &moved_components)
+            };
+        };
+
+        if let Some(moved_entity) = moved_entity {
+            let moved_location = world.entities.get(moved_entity).unwrap();
+            world.archetypes[moved_location.archetype_id]
+                .set_entity_table_row(moved_location.index, table_row);
+        }
+
+        destination_entity
+    }
+
     #[inline]
     pub fn world(&mut self) -> &World {
         self.world
@@ -525,6 +819,144 @@ impl<'w> EntityMut<'w> {
     pub fn update_location(&mut self) {
         self.location = self.world.entities().get(self.entity).unwrap();
     }
+
+    /// Runs the given component hooks for this entity. Hooks can change the world arbitrarily, so
+    /// the entity's location is refreshed afterwards.
+    fn run_hooks(&mut self, hooks: Vec<(ComponentHook, ComponentId)>) {
+        if hooks.is_empty() {
+            return;
+        }
+        for (hook, component_id) in hooks {
+            hook(self.world, self.entity, component_id);
+        }
+        self.update_location();
+    }
+}
+
+/// Moves `entity` to the archetype it has after inserting the bundle and returns that archetype,
+/// the status of each bundle component and the new location. Non-generic to cut down on
+/// monomorphization.
+///
+/// # Safety
+/// component ids in `bundle_info` and `current_location` must be valid
+unsafe fn get_insert_bundle_info<'a>(
+    entities: &mut Entities,
+    archetypes: &'a mut Archetypes,
+    components: &mut Components,
+    storages: &mut Storages,
+    bundle_info: &BundleInfo,
+    current_location: EntityLocation,
+    entity: Entity,
+) -> (&'a Archetype, &'a Vec<ComponentStatus>, EntityLocation) {
+    // SAFE: component ids in `bundle_info` and self.location are valid
+    let new_archetype_id = add_bundle_to_archetype(
+        archetypes,
+        storages,
+        components,
+        current_location.archetype_id,
+        bundle_info,
+    );
+    if new_archetype_id == current_location.archetype_id {
+        let archetype = &archetypes[current_location.archetype_id];
+        let edge = archetype.edges().get_add_bundle(bundle_info.id).unwrap();
+        (archetype, &edge.bundle_status, current_location)
+    } else {
+        let (old_table_row, old_table_id) = {
+            let old_archetype = &mut archetypes[current_location.archetype_id];
+            let result = old_archetype.swap_remove(current_location.index);
+            if let Some(swapped_entity) = result.swapped_entity {
+                entities.meta[swapped_entity.id as usize].location = current_location;
+            }
+            (result.table_row, old_archetype.table_id())
+        };
+
+        let new_table_id = archetypes[new_archetype_id].table_id();
+
+        let new_location = if old_table_id == new_table_id {
+            archetypes[new_archetype_id].allocate(entity, old_table_row)
+        } else {
+            let (old_table, new_table) = storages.tables.get_2_mut(old_table_id, new_table_id);
+            // PERF: store "non bundle" components in edge, then just move those to avoid
+            // redundant copies
+            let move_result = old_table.move_to_superset_unchecked(old_table_row, new_table);
+
+            let new_location = archetypes[new_archetype_id].allocate(entity, move_result.new_row);
+            // if an entity was moved into this entity's table spot, update its table row
+            if let Some(swapped_entity) = move_result.swapped_entity {
+                let swapped_location = entities.get(swapped_entity).unwrap();
+                archetypes[swapped_location.archetype_id]
+                    .set_entity_table_row(swapped_location.index, old_table_row);
+            }
+            new_location
+        };
+
+        entities.meta[entity.id as usize].location = new_location;
+        let (old_archetype, new_archetype) =
+            archetypes.get_2_mut(current_location.archetype_id, new_archetype_id);
+        let edge = old_archetype
+            .edges()
+            .get_add_bundle(bundle_info.id)
+            .unwrap();
+        (&*new_archetype, &edge.bundle_status, new_location)
+
+        // Sparse set components are intentionally ignored here. They don't need to move
+    }
+}
+
+/// Returns the functions inserting the required components of the newly added components in the
+/// bundle, then their `on_add` hooks, followed by the `on_insert` hooks of every component in the
+/// bundle.
+pub(crate) fn get_insert_hooks(
+    components: &Components,
+    bundle_info: &BundleInfo,
+    bundle_status: &[ComponentStatus],
+) -> Vec<(ComponentHook, ComponentId)> {
+    let mut hooks = Vec::new();
+    for (component_id, status) in bundle_info.component_ids.iter().zip(bundle_status) {
+        if let ComponentStatus::Added = status {
+            // SAFE: component ids in `bundle_info` are valid
+            let info = unsafe { components.get_info_unchecked(*component_id) };
+            for required in info.required_components() {
+                hooks.push((required.insert(), *component_id));
+            }
+        }
+    }
+    for (component_id, status) in bundle_info.component_ids.iter().zip(bundle_status) {
+        // SAFE: component ids in `bundle_info` are valid
+        let info = unsafe { components.get_info_unchecked(*component_id) };
+        if let (Some(on_add), ComponentStatus::Added) = (info.hooks().on_add(), status) {
+            hooks.push((on_add, *component_id));
+        }
+    }
+    for component_id in bundle_info.component_ids.iter() {
+        // SAFE: component ids in `bundle_info` are valid
+        let info = unsafe { components.get_info_unchecked(*component_id) };
+        if let Some(on_insert) = info.hooks().on_insert() {
+            hooks.push((on_insert, *component_id));
+        }
+    }
+    hooks
+}
+
+/// Returns the `on_remove` hooks of the given components that are in `archetype`, each followed by
+/// the hooks removing the entity from the indexes of the component.
+fn get_remove_hooks(
+    components: &Components,
+    archetype: &Archetype,
+    component_ids: impl Iterator<Item = ComponentId>,
+) -> Vec<(ComponentHook, ComponentId)> {
+    let mut hooks = Vec::new();
+    for component_id in component_ids.filter(|component_id| archetype.contains(*component_id)) {
+        // SAFE: components in an archetype are valid
+        let info = unsafe { components.get_info_unchecked(component_id) };
+        if let Some(on_remove) = info.hooks().on_remove() {
+            hooks.push((on_remove, component_id));
+        }
+        for index_hook in info.index_hooks() {
+            hooks.push((index_hook, component_id));
+        }
+    }
+    hooks
 }
 
 /// # Safety
@@ -1,10 +1,12 @@
 mod entity_ref;
 mod pointer;
+mod snapshot;
 mod spawn_batch;
 mod world_cell;
 
 pub use entity_ref::*;
 pub use pointer::*;
+pub use snapshot::*;
 pub use spawn_batch::*;
 pub use world_cell::*;
 
@@ -15,10 +17,15 @@ use crate::{
         Component, ComponentDescriptor, ComponentId, ComponentTicks, Components, ComponentsError,
         StorageType,
     },
-    entity::{Entities, Entity},
-    query::{FilterFetch, QueryState, WorldQuery},
+    entity::{Disabled, Entities, Entity, EntityMap},
+    index::{self, ComponentIndex, IndexMap, IndexedComponent},
+    observer::{self, Observer, Traversal},
+    query::{FilterFetch, QueryState, With, WorldQuery},
+    relation::{self, Relation, Relations},
     storage::{Column, SparseSet, Storages},
+    system::{BoxedSystem, RegisteredSystem, RunSystemError, System, SystemId, SystemRegistry},
 };
+use bevy_utils::HashSet;
 use std::{
     any::TypeId,
     fmt,
@@ -47,6 +54,10 @@ pub struct World {
     pub(crate) storages: Storages,
     pub(crate) bundles: Bundles,
     pub(crate) removed_components: SparseSet<ComponentId, Vec<Entity>>,
+    pub(crate) relations: Relations,
+    /// Run by [`World::restore`] to reset state that is derived from the restored storages, such
+    /// as [`ComponentIndex`] resources.
+    pub(crate) restore_hooks: Vec<fn(&mut World)>,
     /// Access cache used by [WorldCell].
     pub(crate) archetype_component_access: ArchetypeComponentAccess,
     main_thread_validator: MainThreadValidator,
@@ -56,14 +67,27 @@ pub struct World {
 
 impl Default for World {
     fn default() -> Self {
+        let mut components = Components::default();
+        // registered up front so that every query can skip disabled entities and observers. The
+        // markers are stored in tables, so that queries iterating whole tables never see them
+        components
+            .add(ComponentDescriptor::new::<Disabled>(StorageType::Table))
+            .unwrap();
+        components.register_snapshot::<Disabled>();
+        components
+            .add(ComponentDescriptor::new::<Observer>(StorageType::Table).world_local())
+            .unwrap();
+        components.register_snapshot::<Observer>();
         Self {
             id: Default::default(),
             entities: Default::default(),
-            components: Default::default(),
+            components,
             archetypes: Default::default(),
             storages: Default::default(),
             bundles: Default::default(),
             removed_components: Default::default(),
+            relations: Default::default(),
+            restore_hooks: Vec::new(),
             archetype_component_access: Default::default(),
             main_thread_validator: Default::default(),
             // Default value is `1`, and `last_change_tick`s default to `0`, such that changes
@@ -87,6 +111,24 @@ impl World {
         self.id
     }
 
+    /// Returns the [ComponentId] of the [Disabled] marker, which is registered in every [World].
+    #[inline]
+    pub fn disabled_component_id(&self) -> ComponentId {
+        self.components.get_id(TypeId::of::<Disabled>()).unwrap()
+    }
+
+    /// Returns the [ComponentId] of the [Observer] marker, which is registered in every [World].
+    #[inline]
+    pub fn observer_component_id(&self) -> ComponentId {
+        self.components.get_id(TypeId::of::<Observer>()).unwrap()
+    }
+
+    /// The markers of the entities that queries skip unless they mention the marker.
+    #[inline]
+    pub(crate) fn hidden_component_ids(&self) -> [ComponentId; 2] {
+        [self.disabled_component_id(), self.observer_component_id()]
+    }
+
     /// Retrieves this world's [Entities] collection
     #[inline]
     pub fn entities(&self) -> &Entities {
@@ -123,6 +165,12 @@ impl World {
         &self.bundles
     }
 
+    /// Retrieves this world's [Relations] index
+    #[inline]
+    pub fn relations(&self) -> &Relations {
+        &self.relations
+    }
+
     /// Retrieves a [WorldCell], which safely enables multiple mutable World accesses at the same
     /// time, provided those accesses do not conflict with each other.
     #[inline]
@@ -164,6 +212,129 @@ impl World {
         Ok(component_id)
     }
 
+    /// Makes `R` required by `T`: whenever `T` is added to an entity that has no `R`, through
+    /// [`World::spawn`], [`EntityMut::insert`] or [`Commands`](crate::system::Commands),
+    /// `R::default()` is inserted as well. See [`RequiredComponent`](crate::component::RequiredComponent).
+    ///
+    /// This registers `T` with the default [`StorageType`] if it isn't registered yet, so call
+    /// [`World::register_component`] first to use another storage type.
+    pub fn register_required_components<T: Component, R: Component + Default>(&mut self) {
+        self.components.register_required::<T, R>();
+    }
+
+    /// Allows entities with a `T` component to be captured by [`World::snapshot`].
+    ///
+    /// This registers `T` with the default [`StorageType`] if it isn't registered yet, so call
+    /// [`World::register_component`] first to use another storage type.
+    pub fn register_snapshot_component<T: Component + Clone>(&mut self) {
+        self.components.register_snapshot::<T>();
+    }
+
+    /// Makes [`World::snapshot`] capture the resource `T`, and [`World::restore`] reset it.
+    pub fn register_snapshot_resource<T: Component + Clone>(&mut self) {
+        self.components.register_snapshot_resource::<T>();
+    }
+
+    /// Copies every entity, component and registered resource of this world into a
+    /// [`WorldSnapshot`], which can later be passed to [`World::restore`] to roll back to the
+    /// current state.
+    ///
+    /// Returns an error if an entity has a component that was not registered with
+    /// [`World::register_snapshot_component`].
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Position(f32);
+    ///
+    /// let mut world = World::new();
+    /// world.register_snapshot_component::<Position>();
+    /// let entity = world.spawn().insert(Position(0.0)).id();
+    /// let snapshot = world.snapshot().unwrap();
+    ///
+    /// world.get_mut::<Position>(entity).unwrap().0 = 1.0;
+    /// let spawned = world.spawn().id();
+    /// world.despawn(entity);
+    ///
+    /// world.restore(&snapshot);
+    /// assert_eq!(world.get::<Position>(entity), Some(&Position(0.0)));
+    /// // the entity allocator is restored as well, so the same ids are handed out again
+    /// assert_eq!(world.spawn().id(), spawned);
+    /// ```
+    pub fn snapshot(&self) -> Result<WorldSnapshot, SnapshotError> {
+        WorldSnapshot::new(self)
+    }
+
+    /// Restores the entities, components and registered resources captured by `snapshot`,
+    /// including their [`Entity`] ids and [`ComponentTicks`]. Entities spawned since the snapshot
+    /// was taken are removed without running [hooks](crate::component::ComponentHooks), and
+    /// removed component trackers are cleared.
+    ///
+    /// The change tick of the world keeps increasing, so that systems don't see values they
+    /// already processed as changed again. [`ComponentIndex`]es are emptied and rebuilt from
+    /// scratch the next time they are used.
+    ///
+    /// # Panics
+    /// Panics if `snapshot` was taken from another world.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        snapshot.restore(self);
+    }
+
+    /// Creates the [`ComponentIndex<T, M>`] resource, if it doesn't exist yet. Systems using an
+    /// [`Index<T>`](crate::index::Index) create it when they are initialized.
+    pub fn init_index<T: IndexedComponent, M: IndexMap<T::Key>>(&mut self) {
+        if !self.contains_resource::<ComponentIndex<T, M>>() {
+            self.insert_resource(ComponentIndex::<T, M>::default());
+        }
+        // the resource may have been removed since the hooks were registered
+        if self
+            .components
+            .register_index_hook::<T, ComponentIndex<T, M>>(index::remove_from_index::<T, M>)
+        {
+            self.restore_hooks.push(index::reset_index::<T, M>);
+        }
+    }
+
+    /// Initializes the relation kind `K` and returns the [ComponentId] of [`Relation<K>`].
+    /// Unless `Relation<K>` was already registered with a different [StorageType], it is stored in
+    /// a sparse set, as edges tend to be added and removed often.
+    ///
+    /// # Panics
+    /// Panics if `Relation<K>` was registered with its own `on_insert` or `on_remove` hooks.
+    pub fn init_relation<K: Component>(&mut self) -> ComponentId {
+        let component_id = match self.components.get_id(TypeId::of::<Relation<K>>()) {
+            Some(component_id) if self.relations.contains_kind(component_id) => component_id,
+            // registered without the hooks keeping the index in sync, e.g. by a system querying
+            // `Relation<K>` before the first edge was inserted
+            Some(component_id) => {
+                self.components.set_insert_and_remove_hooks(
+                    component_id,
+                    relation::relation_on_insert::<K>,
+                    relation::relation_on_remove,
+                );
+                self.relations.init_kind::<K>(component_id);
+                let sources = self
+                    .query_filtered::<Entity, With<Relation<K>>>()
+                    .iter(self)
+                    .collect::<Vec<_>>();
+                for source in sources {
+                    relation::relation_on_insert::<K>(self, source, component_id);
+                }
+                return component_id;
+            }
+            None => self
+                .register_component(
+                    ComponentDescriptor::new::<Relation<K>>(StorageType::SparseSet)
+                        .on_insert(relation::relation_on_insert::<K>)
+                        .on_remove(relation::relation_on_remove),
+                )
+                .unwrap(),
+        };
+        self.relations.init_kind::<K>(component_id);
+        component_id
+    }
+
     /// Retrieves an [EntityRef] that exposes read-only operations for the given `entity`.
     /// This will panic if the `entity` does not exist. Use [World::get_entity] if you want
     /// to check for entity existence instead of implicitly panic-ing.
@@ -404,6 +575,230 @@ impl World {
             .unwrap_or(false)
     }
 
+    /// Moves `entity` and all of its components to `destination` and returns the new entity, or
+    /// `None` if `entity` does not exist. See [EntityMut::move_to].
+    ///
+    /// ```
+    /// use bevy_ecs::world::World;
+    ///
+    /// struct Position(f32);
+    ///
+    /// let mut staging = World::new();
+    /// let mut world = World::new();
+    /// let entity = staging.spawn().insert(Position(1.0)).id();
+    ///
+    /// let moved = staging.move_entity_to(&mut world, entity).unwrap();
+    /// assert!(staging.get_entity(entity).is_none());
+    /// assert_eq!(world.get::<Position>(moved).unwrap().0, 1.0);
+    /// ```
+    pub fn move_entity_to(&mut self, destination: &mut World, entity: Entity) -> Option<Entity> {
+        self.get_entity_mut(entity)
+            .map(|entity| entity.move_to(destination))
+    }
+
+    /// Moves the given entities and their components to `destination` and returns an [EntityMap]
+    /// from each moved entity to its new entity. Entities that do not exist are skipped.
+    ///
+    /// [Relation]s between the moved entities are remapped to the new entities, and edges to
+    /// entities that were not moved are dropped. Pass the map to [World::map_entities] on
+    /// `destination` to remap the other references between the moved entities.
+    pub fn extract_entities(&mut self, destination: &mut World, entities: &[Entity]) -> EntityMap {
+        let kept_sources = entities.iter().cloned().collect::<HashSet<_>>();
+        let mut entity_map = EntityMap::default();
+        for entity in entities.iter().cloned() {
+            if let Some(entity_mut) = self.get_entity_mut(entity) {
+                entity_map.insert(
+                    entity,
+                    entity_mut.move_to_except(destination, &kept_sources),
+                );
+            }
+        }
+        Relations::map_moved(destination, &entity_map);
+        entity_map
+    }
+
+    /// Remaps the entities stored in the components of the entities in `entity_map`, using the
+    /// [MapEntities](crate::entity::MapEntities) implementation of every component type that has
+    /// [ReflectMapEntities](crate::reflect::ReflectMapEntities) registered in `type_registry`.
+    ///
+    /// ```
+    /// use bevy_ecs::{
+    ///     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    ///     reflect::ReflectMapEntities,
+    ///     world::World,
+    /// };
+    /// use bevy_reflect::{Reflect, TypeRegistry};
+    ///
+    /// #[derive(Reflect)]
+    /// #[reflect(MapEntities)]
+    /// struct Target(Entity);
+    ///
+    /// impl MapEntities for Target {
+    ///     fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+    ///         self.0 = entity_map.get(self.0)?;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut type_registry = TypeRegistry::default();
+    /// type_registry.register::<Target>();
+    ///
+    /// let mut staging = World::new();
+    /// let a = staging.spawn().id();
+    /// let b = staging.spawn().insert(Target(a)).id();
+    ///
+    /// let mut world = World::new();
+    /// let entity_map = staging.extract_entities(&mut world, &[a, b]);
+    /// world.map_entities(&entity_map, &type_registry).unwrap();
+    ///
+    /// let new_b = entity_map.get(b).unwrap();
+    /// assert_eq!(world.get::<Target>(new_b).unwrap().0, entity_map.get(a).unwrap());
+    /// ```
+    #[cfg(feature = "bevy_reflect")]
+    pub fn map_entities(
+        &mut self,
+        entity_map: &EntityMap,
+        type_registry: &bevy_reflect::TypeRegistry,
+    ) -> Result<(), crate::entity::MapEntitiesError> {
+        for registration in type_registry.iter() {
+            if let Some(map_entities_reflect) =
+                registration.data::<crate::reflect::ReflectMapEntities>()
+            {
+                map_entities_reflect.map_entities(self, entity_map)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an iterator of entities that have a [Relation] of kind `K` to `target`.
+    ///
+    /// ```
+    /// use bevy_ecs::world::World;
+    ///
+    /// struct ChildOf;
+    ///
+    /// let mut world = World::new();
+    /// let parent = world.spawn().id();
+    /// let child = world.spawn().insert_relation(ChildOf, parent).id();
+    ///
+    /// assert_eq!(world.related_to::<ChildOf>(parent).collect::<Vec<_>>(), vec![child]);
+    /// ```
+    pub fn related_to<K: Component>(&self, target: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.components
+            .get_id(TypeId::of::<Relation<K>>())
+            .into_iter()
+            .flat_map(move |component_id| self.relations.sources(component_id, target))
+    }
+
+    /// Registers `system` so it can be run on demand with [World::run_system], and returns its
+    /// [SystemId]. Unlike a system that is re-created for every run, a registered system keeps
+    /// its [Local](crate::system::Local) values and query state between runs.
+    ///
+    /// ```
+    /// use bevy_ecs::prelude::*;
+    ///
+    /// #[derive(Default)]
+    /// struct Counter(usize);
+    ///
+    /// fn increment(mut counter: ResMut<Counter>) {
+    ///     counter.0 += 1;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Counter::default());
+    /// let id = world.register_system(increment.system());
+    ///
+    /// world.run_system(id).unwrap();
+    /// world.run_system(id).unwrap();
+    /// assert_eq!(world.get_resource::<Counter>().unwrap().0, 2);
+    /// ```
+    pub fn register_system(&mut self, system: impl System<In = (), Out = ()>) -> SystemId {
+        let id = system.id();
+        let system = RegisteredSystem::new(self, Box::new(system));
+        self.get_resource_or_insert_with(SystemRegistry::default)
+            .systems
+            .insert(id, Some(system));
+        id
+    }
+
+    /// Removes a system registered with [World::register_system] and returns it, if it exists.
+    /// A system that is currently running cannot be returned, but it will not be run again.
+    pub fn unregister_system(&mut self, id: SystemId) -> Option<BoxedSystem> {
+        self.get_resource_mut::<SystemRegistry>()?
+            .systems
+            .remove(&id)?
+            .map(RegisteredSystem::into_inner)
+    }
+
+    /// Runs the system registered with [World::register_system] for `id`, then applies its
+    /// [Commands](crate::system::Commands).
+    pub fn run_system(&mut self, id: SystemId) -> Result<(), RunSystemError> {
+        let mut system = self
+            .get_resource_mut::<SystemRegistry>()
+            .and_then(|mut registry| registry.systems.get_mut(&id).map(Option::take))
+            .ok_or(RunSystemError::SystemIdNotRegistered(id))?
+            .ok_or(RunSystemError::Recursive(id))?;
+        system.run(self);
+        if let Some(mut registry) = self.get_resource_mut::<SystemRegistry>() {
+            // the system may have been unregistered while it ran
+            if let Some(slot) = registry.systems.get_mut(&id) {
+                *slot = Some(system);
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers `system` as an observer of every event of type `E` and returns the observer
+    /// entity. The observer can access the event with a [Trigger](crate::observer::Trigger)
+    /// parameter. Despawning the returned entity removes the observer.
+    ///
+    /// To observe the events that target a single entity, use [EntityMut::observe].
+    ///
+    /// ```
+    /// use bevy_ecs::{observer::Trigger, prelude::*};
+    ///
+    /// struct Explode;
+    /// #[derive(Default)]
+    /// struct Explosions(usize);
+    ///
+    /// fn count_explosions(_trigger: Trigger<Explode>, mut explosions: ResMut<Explosions>) {
+    ///     explosions.0 += 1;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Explosions::default());
+    /// world.observe::<Explode>(count_explosions.system());
+    ///
+    /// world.trigger(Explode);
+    /// assert_eq!(world.get_resource::<Explosions>().unwrap().0, 1);
+    /// ```
+    pub fn observe<E: Component>(&mut self, system: impl System<In = (), Out = ()>) -> Entity {
+        let observer = self.spawn().id();
+        observer::insert_observer::<E>(self, observer, None, Box::new(system));
+        observer
+    }
+
+    /// Triggers `event` without a target. Only observers registered with [World::observe] run.
+    /// Observers run immediately, before this returns.
+    pub fn trigger<E: Component>(&mut self, event: E) {
+        observer::trigger(self, event, None, None);
+    }
+
+    /// Triggers `event` on `entity`. The observers of `entity` run first, followed by the
+    /// observers registered with [World::observe]. Observers run immediately, before this returns.
+    pub fn trigger_entity<E: Component>(&mut self, event: E, entity: Entity) {
+        observer::trigger(self, event, Some(entity), None);
+    }
+
+    /// Triggers `event` on `entity` and propagates it along the [Traversal] component `T`, like a
+    /// bubbling DOM event. The observers of `entity` run first, then the observers of the entity
+    /// `T` points to, and so on, until there is no next entity or an observer stops propagation
+    /// with [Trigger::propagate](crate::observer::Trigger::propagate). The observers registered
+    /// with [World::observe] run last.
+    pub fn trigger_propagated<T: Traversal, E: Component>(&mut self, event: E, entity: Entity) {
+        observer::trigger_propagated::<T, E>(self, event, entity);
+    }
+
     /// Clears component tracker state
     pub fn clear_trackers(&mut self) {
         for entities in self.removed_components.values_mut() {
@@ -906,6 +1301,42 @@ impl World {
         self.last_change_tick
     }
 
+    /// Runs `f` with [World::last_change_tick] set to `last_change_tick`, so that `Added` and
+    /// `Changed` filters of queries run on the world, as well as [Mut::is_added] and
+    /// [Mut::is_changed], compare against the given tick instead. The previous tick is restored
+    /// afterwards.
+    ///
+    /// This can be used to find components that changed since an arbitrary point in time, such as
+    /// the last network snapshot, by storing [World::read_change_tick] at that point.
+    /// ```
+    /// use bevy_ecs::{query::Changed, world::World};
+    ///
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::default();
+    /// let entity = world.spawn().insert(Health(10)).id();
+    /// let snapshot_tick = world.read_change_tick();
+    /// world.increment_change_tick();
+    ///
+    /// world.entity_mut(entity).get_mut::<Health>().unwrap().0 = 5;
+    ///
+    /// let changed = world.last_change_tick_scope(snapshot_tick, |world| {
+    ///     let mut query = world.query_filtered::<&Health, Changed<Health>>();
+    ///     query.iter(world).count()
+    /// });
+    /// assert_eq!(changed, 1);
+    /// ```
+    pub fn last_change_tick_scope<T>(
+        &mut self,
+        last_change_tick: u32,
+        f: impl FnOnce(&mut World) -> T,
+    ) -> T {
+        let saved_last_tick = std::mem::replace(&mut self.last_change_tick, last_change_tick);
+        let result = f(self);
+        self.last_change_tick = saved_last_tick;
+        result
+    }
+
     pub fn check_change_ticks(&mut self) {
         // Iterate over all component change ticks, clamping their age to max age
         // PERF: parallelize
@@ -46,4 +46,29 @@ impl<'w, T> Mut<'w, T> {
         self.component_ticks
             .is_changed(self.last_change_tick, self.change_tick)
     }
+
+    /// Returns true if (and only if) this component has been added since the given change tick,
+    /// for example one stored from [`World::read_change_tick`](crate::world::World::read_change_tick)
+    /// when a snapshot was taken.
+    pub fn is_added_since(&self, tick: u32) -> bool {
+        self.component_ticks.is_added(tick, self.change_tick)
+    }
+
+    /// Returns true if (and only if) this component has been changed since the given change tick.
+    pub fn is_changed_since(&self, tick: u32) -> bool {
+        self.component_ticks.is_changed(tick, self.change_tick)
+    }
+
+    /// Marks this component as changed without mutating it.
+    pub fn set_changed(&mut self) {
+        self.component_ticks.set_changed(self.change_tick);
+    }
+
+    /// Returns a mutable reference to the component without marking it as changed.
+    ///
+    /// Mutations made through the returned reference are not picked up by `Changed<T>` filters or
+    /// [`Mut::is_changed`]. Call [`Mut::set_changed`] afterwards if they should be.
+    pub fn bypass_change_detection(&mut self) -> &mut T {
+        self.value
+    }
 }
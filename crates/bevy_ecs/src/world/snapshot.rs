@@ -0,0 +1,216 @@
+use crate::{
+    component::{CloneFn, ComponentId, Components},
+    entity::{Entities, Entity},
+    relation::RelationIndex,
+    storage::{Column, ComponentSparseSet},
+    world::{World, WorldId},
+};
+use thiserror::Error;
+
+/// A copy of the entities, components and resources of a [`World`], created with
+/// [`World::snapshot`] and restored with [`World::restore`].
+///
+/// Snapshots copy the component storages directly rather than going through reflection, so taking
+/// and restoring one costs about as much as cloning every component value. Restoring a snapshot
+/// brings back the exact [`Entity`] ids, allocator state and [`ComponentTicks`](crate::component::ComponentTicks)
+/// of the world at the time it was taken, which makes it suitable for rollback.
+///
+/// Only components and resources registered with [`World::register_snapshot_component`] and
+/// [`World::register_snapshot_resource`] can be captured:
+/// * Taking a snapshot fails if an entity has a component that was not registered.
+/// * Resources that were not registered are left out of the snapshot, and are left untouched when
+///   it is restored.
+///
+/// [Observers](crate::observer::Trigger) are always captured. Their systems are shared with the
+/// snapshot rather than cloned, so a restored observer keeps the local state of its system.
+pub struct WorldSnapshot {
+    world_id: WorldId,
+    entities: Entities,
+    archetypes: Vec<ArchetypeSnapshot>,
+    tables: Vec<TableSnapshot>,
+    sparse_sets: Vec<Option<ComponentSparseSet>>,
+    resources: Vec<(ComponentId, Option<Column>)>,
+    relations: Vec<(ComponentId, RelationIndex)>,
+    change_tick: u32,
+}
+
+// SAFE: snapshots only store values of components and resources registered with a clone
+// function, which are all Send + Sync
+unsafe impl Send for WorldSnapshot {}
+unsafe impl Sync for WorldSnapshot {}
+
+struct ArchetypeSnapshot {
+    entities: Vec<Entity>,
+    table_rows: Vec<usize>,
+}
+
+struct TableSnapshot {
+    entities: Vec<Entity>,
+    /// One column per column of the table, or none if the table was empty.
+    columns: Vec<Column>,
+}
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Entity {entity:?} has the component {name}, which is not registered for snapshots")]
+    ComponentNotRegistered { entity: Entity, name: String },
+}
+
+impl WorldSnapshot {
+    pub(crate) fn new(world: &World) -> Result<Self, SnapshotError> {
+        let components = &world.components;
+        let archetypes = world
+            .archetypes
+            .iter()
+            .map(|archetype| ArchetypeSnapshot {
+                entities: archetype.entities().to_vec(),
+                table_rows: archetype.entity_table_rows().to_vec(),
+            })
+            .collect();
+
+        let mut tables = Vec::with_capacity(world.storages.tables.len());
+        for table in world.storages.tables.iter() {
+            let mut columns = Vec::new();
+            if let Some(entity) = table.entities().first() {
+                for column in table.iter() {
+                    let clone = registered_clone_fn(components, column.component_id, *entity)?;
+                    // SAFE: `clone` clones values of the column's component
+                    columns.push(unsafe { column.clone_with(clone) });
+                }
+            }
+            tables.push(TableSnapshot {
+                entities: table.entities().to_vec(),
+                columns,
+            });
+        }
+
+        let mut sparse_sets = Vec::new();
+        for (component_id, sparse_set) in world.storages.sparse_sets.iter() {
+            sparse_sets.push(match sparse_set.entities().first() {
+                Some(entity) => {
+                    let clone = registered_clone_fn(components, component_id, *entity)?;
+                    // SAFE: `clone` clones values of the sparse set's component
+                    Some(unsafe { sparse_set.clone_with(clone) })
+                }
+                None => None,
+            });
+        }
+
+        let unique_components = world.archetypes.resource().unique_components();
+        let resources = (0..components.len())
+            .map(ComponentId::new)
+            .filter_map(|component_id| {
+                let clone = components.get_info(component_id)?.clone_fn()?;
+                let column = unique_components
+                    .get(component_id)
+                    .filter(|column| !column.is_empty())
+                    // SAFE: `clone` clones values of the resource
+                    .map(|column| unsafe { column.clone_with(clone) });
+                Some((component_id, column))
+            })
+            .collect();
+
+        Ok(Self {
+            world_id: world.id(),
+            entities: world.entities.clone(),
+            archetypes,
+            tables,
+            sparse_sets,
+            resources,
+            relations: world.relations.indices_by_kind(),
+            change_tick: world.read_change_tick(),
+        })
+    }
+
+    /// The [`World`] this snapshot was taken from.
+    pub fn world_id(&self) -> WorldId {
+        self.world_id
+    }
+
+    /// The change tick of the [`World`] at the time this snapshot was taken.
+    pub fn change_tick(&self) -> u32 {
+        self.change_tick
+    }
+
+    pub(crate) fn restore(&self, world: &mut World) {
+        assert_eq!(
+            self.world_id, world.id,
+            "A snapshot can only be restored into the World it was taken from"
+        );
+        let components = &world.components;
+        let clone_fn = |component_id| {
+            components
+                .get_info(component_id)
+                .and_then(|info| info.clone_fn())
+                .unwrap()
+        };
+
+        // archetypes, tables and sparse sets are never removed, so everything captured in the
+        // snapshot still exists. Storages created since then are emptied
+        for (index, table) in world.storages.tables.iter_mut().enumerate() {
+            match self.tables.get(index) {
+                // SAFE: the columns were captured from this table with the same clone functions
+                Some(saved) => unsafe {
+                    table.clone_from_with(&saved.entities, &saved.columns, clone_fn)
+                },
+                None => table.clear(),
+            }
+        }
+        for (index, (component_id, sparse_set)) in world.storages.sparse_sets.iter_mut().enumerate()
+        {
+            match self.sparse_sets.get(index) {
+                // SAFE: the set was captured from this sparse set with the same clone function
+                Some(Some(saved)) => unsafe {
+                    sparse_set.clone_from_with(saved, clone_fn(component_id))
+                },
+                _ => sparse_set.clear(),
+            }
+        }
+        for (index, archetype) in world.archetypes.archetypes.iter_mut().enumerate() {
+            match self.archetypes.get(index) {
+                // SAFE: the tables were restored above, and the entity locations are below
+                Some(saved) => unsafe {
+                    archetype.set_entities(&saved.entities, &saved.table_rows)
+                },
+                None => unsafe { archetype.set_entities(&[], &[]) },
+            }
+        }
+        world.entities.clone_from(&self.entities);
+
+        let unique_components = world.archetypes.resource_mut().unique_components_mut();
+        for (component_id, saved) in self.resources.iter() {
+            if let Some(column) = unique_components.get_mut(*component_id) {
+                match saved {
+                    // SAFE: the column was captured from this resource with the same clone function
+                    Some(saved) => unsafe {
+                        column.clone_from_with(saved, clone_fn(*component_id))
+                    },
+                    None => column.clear(),
+                }
+            }
+        }
+
+        world.relations.restore_indices(&self.relations);
+        for entities in world.removed_components.values_mut() {
+            entities.clear();
+        }
+        // e.g. component indexes are rebuilt on their next update, whether or not they were
+        // captured
+        for hook in world.restore_hooks.clone() {
+            hook(world);
+        }
+    }
+}
+
+fn registered_clone_fn(
+    components: &Components,
+    component_id: ComponentId,
+    entity: Entity,
+) -> Result<CloneFn, SnapshotError> {
+    let info = components.get_info(component_id).unwrap();
+    info.clone_fn()
+        .ok_or_else(|| SnapshotError::ComponentNotRegistered {
+            entity,
+            name: info.name().to_string(),
+        })
+}
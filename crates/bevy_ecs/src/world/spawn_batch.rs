@@ -1,9 +1,9 @@
 use crate::{
-    archetype::{Archetype, ArchetypeId, ComponentStatus},
-    bundle::{Bundle, BundleInfo},
-    entity::{Entities, Entity},
-    storage::{SparseSets, Table},
-    world::{add_bundle_to_archetype, World},
+    archetype::ArchetypeId,
+    bundle::{Bundle, BundleId},
+    component::{ComponentHook, ComponentId},
+    entity::Entity,
+    world::{add_bundle_to_archetype, entity_ref::get_insert_hooks, World},
 };
 
 pub struct SpawnBatchIter<'w, I>
@@ -12,12 +12,10 @@ where
     I::Item: Bundle,
 {
     inner: I,
-    entities: &'w mut Entities,
-    archetype: &'w mut Archetype,
-    table: &'w mut Table,
-    sparse_sets: &'w mut SparseSets,
-    bundle_info: &'w BundleInfo,
-    bundle_status: &'w [ComponentStatus],
+    world: &'w mut World,
+    bundle_id: BundleId,
+    archetype_id: ArchetypeId,
+    hooks: Vec<(ComponentHook, ComponentId)>,
     change_tick: u32,
 }
 
@@ -58,15 +56,14 @@ where
             .edges()
             .get_add_bundle(bundle_info.id())
             .unwrap();
+        let hooks = get_insert_hooks(&world.components, bundle_info, &edge.bundle_status);
         Self {
             inner: iter,
-            entities: &mut world.entities,
-            archetype,
-            table,
-            sparse_sets: &mut world.storages.sparse_sets,
-            bundle_info,
+            bundle_id: bundle_info.id(),
+            archetype_id,
+            hooks,
             change_tick: *world.change_tick.get_mut(),
-            bundle_status: &edge.bundle_status,
+            world,
         }
     }
 }
@@ -90,22 +87,37 @@ where
 
     fn next(&mut self) -> Option<Entity> {
         let bundle = self.inner.next()?;
-        let entity = self.entities.alloc();
+        let world = &mut *self.world;
+        let entity = world.entities.alloc();
+        let bundle_info = world.bundles.get(self.bundle_id).unwrap();
+        let archetype = &mut world.archetypes[self.archetype_id];
+        let table = &mut world.storages.tables[archetype.table_id()];
         // SAFE: component values are immediately written to relevant storages (which have been
         // allocated)
         unsafe {
-            let table_row = self.table.allocate(entity);
-            let location = self.archetype.allocate(entity, table_row);
-            self.bundle_info.write_components(
-                self.sparse_sets,
+            let table_row = table.allocate(entity);
+            let location = archetype.allocate(entity, table_row);
+            let bundle_status = &world.archetypes[ArchetypeId::empty()]
+                .edges()
+                .get_add_bundle(self.bundle_id)
+                .unwrap()
+                .bundle_status;
+            bundle_info.write_components(
+                &mut world.storages.sparse_sets,
                 entity,
-                self.table,
+                table,
                 table_row,
-                self.bundle_status,
+                bundle_status,
                 bundle,
                 self.change_tick,
             );
-            self.entities.meta[entity.id as usize].location = location;
+            world.entities.meta[entity.id as usize].location = location;
+        }
+        if !self.hooks.is_empty() {
+            for (hook, component_id) in self.hooks.iter() {
+                hook(world, entity, *component_id);
+            }
+            world.flush();
         }
         Some(entity)
     }
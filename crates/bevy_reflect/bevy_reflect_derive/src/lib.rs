@@ -15,13 +15,16 @@ use syn::{
     parse_macro_input,
     punctuated::Punctuated,
     token::{Comma, Paren, Where},
-    Data, DataStruct, DeriveInput, Field, Fields, Generics, Ident, Index, Member, Meta, NestedMeta,
-    Path,
+    Attribute, Data, DataStruct, DeriveInput, Field, Fields, Generics, Ident, Index, Lit, Member,
+    Meta, NestedMeta, Path, Variant,
 };
 
 #[derive(Default)]
 struct PropAttributeArgs {
     pub ignore: Option<bool>,
+    /// Whether a dynamic value of an enum variant field is applied to a default value, see
+    /// `enum_variant_field_or_default`.
+    pub default: Option<bool>,
 }
 
 #[derive(Clone)]
@@ -41,6 +44,7 @@ enum DeriveType {
     Struct,
     TupleStruct,
     UnitStruct,
+    Enum,
     Value,
 }
 
@@ -64,47 +68,11 @@ pub fn derive_reflect(input: TokenStream) -> TokenStream {
             fields: Fields::Unit,
             ..
         }) => (&unit_struct_punctuated, DeriveType::UnitStruct),
+        Data::Enum(_) => (&unit_struct_punctuated, DeriveType::Enum),
         _ => (&unit_struct_punctuated, DeriveType::Value),
     };
 
-    let fields_and_args = fields
-        .iter()
-        .enumerate()
-        .map(|(i, f)| {
-            (
-                f,
-                f.attrs
-                    .iter()
-                    .find(|a| *a.path.get_ident().as_ref().unwrap() == REFLECT_ATTRIBUTE_NAME)
-                    .map(|a| {
-                        syn::custom_keyword!(ignore);
-                        let mut attribute_args = PropAttributeArgs { ignore: None };
-                        a.parse_args_with(|input: ParseStream| {
-                            if input.parse::<Option<ignore>>()?.is_some() {
-                                attribute_args.ignore = Some(true);
-                                return Ok(());
-                            }
-                            Ok(())
-                        })
-                        .expect("Invalid 'property' attribute format.");
-
-                        attribute_args
-                    }),
-                i,
-            )
-        })
-        .collect::<Vec<(&Field, Option<PropAttributeArgs>, usize)>>();
-    let active_fields = fields_and_args
-        .iter()
-        .filter(|(_field, attrs, _i)| {
-            attrs.is_none()
-                || match attrs.as_ref().unwrap().ignore {
-                    Some(ignore) => !ignore,
-                    None => true,
-                }
-        })
-        .map(|(f, _attr, i)| (*f, *i))
-        .collect::<Vec<(&Field, usize)>>();
+    let active_fields = get_active_fields(fields);
 
     let modules = get_modules();
     let bevy_reflect_path = get_path(&modules.bevy_reflect);
@@ -143,6 +111,7 @@ pub fn derive_reflect(input: TokenStream) -> TokenStream {
             get_type_registration_impl,
             &bevy_reflect_path,
             &reflect_attrs,
+            &ast.attrs,
             &active_fields,
         ),
         DeriveType::TupleStruct => impl_tuple_struct(
@@ -151,24 +120,87 @@ pub fn derive_reflect(input: TokenStream) -> TokenStream {
             get_type_registration_impl,
             &bevy_reflect_path,
             &reflect_attrs,
+            &ast.attrs,
             &active_fields,
         ),
+        DeriveType::Enum => {
+            let variants = match &ast.data {
+                Data::Enum(data) => &data.variants,
+                _ => unreachable!(),
+            };
+            impl_enum(
+                type_name,
+                &ast.generics,
+                get_type_registration_impl,
+                &bevy_reflect_path,
+                &reflect_attrs,
+                &ast.attrs,
+                variants,
+            )
+        }
         DeriveType::Value => impl_value(
             type_name,
             &ast.generics,
             get_type_registration_impl,
             &bevy_reflect_path,
             &reflect_attrs,
+            &ast.attrs,
         ),
     }
 }
 
+fn get_field_attrs(field: &Field) -> Option<PropAttributeArgs> {
+    field
+        .attrs
+        .iter()
+        .find(|a| *a.path.get_ident().as_ref().unwrap() == REFLECT_ATTRIBUTE_NAME)
+        .map(|a| {
+            syn::custom_keyword!(ignore);
+            syn::custom_keyword!(default);
+            let mut attribute_args = PropAttributeArgs::default();
+            a.parse_args_with(|input: ParseStream| {
+                if input.parse::<Option<ignore>>()?.is_some() {
+                    attribute_args.ignore = Some(true);
+                    return Ok(());
+                }
+                if input.parse::<Option<default>>()?.is_some() {
+                    attribute_args.default = Some(true);
+                    return Ok(());
+                }
+                Ok(())
+            })
+            .expect("Invalid 'property' attribute format.");
+
+            attribute_args
+        })
+}
+
+fn get_active_fields(fields: &Punctuated<Field, Comma>) -> Vec<(&Field, usize)> {
+    let fields_and_args = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| (f, get_field_attrs(f), i))
+        .collect::<Vec<(&Field, Option<PropAttributeArgs>, usize)>>();
+    fields_and_args
+        .iter()
+        .filter(|(_field, attrs, _i)| {
+            attrs.is_none()
+                || match attrs.as_ref().unwrap().ignore {
+                    Some(ignore) => !ignore,
+                    None => true,
+                }
+        })
+        .map(|(f, _attr, i)| (*f, *i))
+        .collect::<Vec<(&Field, usize)>>()
+}
+
 fn impl_struct(
     struct_name: &Ident,
     generics: &Generics,
     get_type_registration_impl: proc_macro2::TokenStream,
     bevy_reflect_path: &Path,
     reflect_attrs: &ReflectAttrs,
+    attrs: &[Attribute],
     active_fields: &[(&Field, usize)],
 ) -> TokenStream {
     let field_names = active_fields
@@ -204,11 +236,34 @@ fn impl_struct(
         TraitImpl::Implemented | TraitImpl::Custom(_) => reflect_attrs.get_partial_eq_impl(),
     };
 
+    let field_infos = active_fields
+        .iter()
+        .zip(field_names.iter())
+        .map(|((field, _), name)| {
+            let ty = &field.ty;
+            let docs = get_docs(&field.attrs);
+            quote!(#bevy_reflect_path::NamedField::new::<#ty>(#name).with_docs(#docs))
+        })
+        .collect::<Vec<_>>();
+    let docs = get_docs(attrs);
+    let typed_impl = impl_typed(
+        struct_name,
+        generics,
+        quote! {
+            #bevy_reflect_path::TypeInfo::Struct(
+                #bevy_reflect_path::StructInfo::new::<Self>(&[#(#field_infos),*]).with_docs(#docs)
+            )
+        },
+        bevy_reflect_path,
+    );
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     TokenStream::from(quote! {
         #get_type_registration_impl
 
+        #typed_impl
+
         impl #impl_generics #bevy_reflect_path::Struct for #struct_name#ty_generics #where_clause {
             fn field(&self, name: &str) -> Option<&dyn #bevy_reflect_path::Reflect> {
                 match name {
@@ -329,6 +384,7 @@ fn impl_tuple_struct(
     get_type_registration_impl: proc_macro2::TokenStream,
     bevy_reflect_path: &Path,
     reflect_attrs: &ReflectAttrs,
+    attrs: &[Attribute],
     active_fields: &[(&Field, usize)],
 ) -> TokenStream {
     let field_idents = active_fields
@@ -348,10 +404,33 @@ fn impl_tuple_struct(
         TraitImpl::Implemented | TraitImpl::Custom(_) => reflect_attrs.get_partial_eq_impl(),
     };
 
+    let field_infos = active_fields
+        .iter()
+        .zip(field_indices.iter())
+        .map(|((field, _), index)| {
+            let ty = &field.ty;
+            let docs = get_docs(&field.attrs);
+            quote!(#bevy_reflect_path::UnnamedField::new::<#ty>(#index).with_docs(#docs))
+        })
+        .collect::<Vec<_>>();
+    let docs = get_docs(attrs);
+    let typed_impl = impl_typed(
+        struct_name,
+        generics,
+        quote! {
+            #bevy_reflect_path::TypeInfo::TupleStruct(
+                #bevy_reflect_path::TupleStructInfo::new::<Self>(&[#(#field_infos),*]).with_docs(#docs)
+            )
+        },
+        bevy_reflect_path,
+    );
+
     let (impl_generics, ty_generics, _where_clause) = generics.split_for_impl();
     TokenStream::from(quote! {
         #get_type_registration_impl
 
+        #typed_impl
+
         impl #impl_generics #bevy_reflect_path::TupleStruct for #struct_name#ty_generics {
             fn field(&self, index: usize) -> Option<&dyn #bevy_reflect_path::Reflect> {
                 match index {
@@ -444,21 +523,385 @@ fn impl_tuple_struct(
     })
 }
 
+fn impl_enum(
+    enum_name: &Ident,
+    generics: &Generics,
+    get_type_registration_impl: proc_macro2::TokenStream,
+    bevy_reflect_path: &Path,
+    reflect_attrs: &ReflectAttrs,
+    attrs: &[Attribute],
+    variants: &Punctuated<Variant, Comma>,
+) -> TokenStream {
+    let mut variant_names = Vec::new();
+    let mut variant_types = Vec::new();
+    let mut variant_patterns = Vec::new();
+    let mut field_arms = Vec::new();
+    let mut field_at_arms = Vec::new();
+    let mut name_at_arms = Vec::new();
+    let mut field_lens = Vec::new();
+    let mut dynamic_variants = Vec::new();
+    let mut constructors = Vec::new();
+    let mut variant_infos = Vec::new();
+    for variant in variants.iter() {
+        let variant_ident = &variant.ident;
+        let variant_name = variant_ident.to_string();
+        let active_fields = match &variant.fields {
+            Fields::Named(fields) => get_active_fields(&fields.named),
+            Fields::Unnamed(fields) => get_active_fields(&fields.unnamed),
+            Fields::Unit => Vec::new(),
+        };
+        let field_members = active_fields
+            .iter()
+            .map(|(field, index)| {
+                field
+                    .ident
+                    .as_ref()
+                    .map(|ident| Member::Named(ident.clone()))
+                    .unwrap_or_else(|| Member::Unnamed(Index::from(*index)))
+            })
+            .collect::<Vec<_>>();
+        let field_bindings = (0..active_fields.len())
+            .map(|i| Ident::new(&format!("__field_{}", i), Span::call_site()))
+            .collect::<Vec<_>>();
+        let field_indices = (0..active_fields.len()).collect::<Vec<usize>>();
+        let field_count = active_fields.len();
+        let pattern = quote! {
+            Self::#variant_ident { #(#field_members: #field_bindings,)* .. }
+        };
+
+        // struct variants are accessed by name, and tuple variants by index
+        let (variant_type, field_names) = match &variant.fields {
+            Fields::Named(_) => (
+                quote!(#bevy_reflect_path::VariantType::Struct),
+                active_fields
+                    .iter()
+                    .map(|(field, _)| field.ident.as_ref().unwrap().to_string())
+                    .collect::<Vec<_>>(),
+            ),
+            Fields::Unnamed(_) => (
+                quote!(#bevy_reflect_path::VariantType::Tuple),
+                field_indices.iter().map(|i| i.to_string()).collect(),
+            ),
+            Fields::Unit => (quote!(#bevy_reflect_path::VariantType::Unit), Vec::new()),
+        };
+
+        field_arms.push(match &variant.fields {
+            Fields::Named(_) => quote! {
+                #pattern => match name {
+                    #(#field_names => Some(#field_bindings),)*
+                    _ => None,
+                },
+            },
+            _ => quote!(Self::#variant_ident { .. } => None,),
+        });
+        field_at_arms.push(quote! {
+            #pattern => match index {
+                #(#field_indices => Some(#field_bindings),)*
+                _ => None,
+            },
+        });
+        name_at_arms.push(match &variant.fields {
+            Fields::Named(_) => quote! {
+                Self::#variant_ident { .. } => match index {
+                    #(#field_indices => Some(#field_names),)*
+                    _ => None,
+                },
+            },
+            _ => quote!(Self::#variant_ident { .. } => None,),
+        });
+        dynamic_variants.push(match &variant.fields {
+            Fields::Named(_) => quote! {
+                #pattern => {
+                    let mut dynamic = #bevy_reflect_path::DynamicStruct::default();
+                    #(dynamic.insert_boxed(#field_names, #field_bindings.clone_value());)*
+                    #bevy_reflect_path::DynamicVariant::Struct(dynamic)
+                }
+            },
+            Fields::Unnamed(_) => quote! {
+                #pattern => {
+                    let mut dynamic = #bevy_reflect_path::DynamicTuple::default();
+                    #(dynamic.insert_boxed(#field_bindings.clone_value());)*
+                    #bevy_reflect_path::DynamicVariant::Tuple(dynamic)
+                }
+            },
+            Fields::Unit => quote! {
+                Self::#variant_ident { .. } => #bevy_reflect_path::DynamicVariant::Unit,
+            },
+        });
+
+        // ignored fields are default initialized when switching to their variant. Active fields
+        // are taken from the applied value, or with `#[reflect(default)]`, applied to a default
+        // value if it is dynamic
+        let all_fields = match &variant.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            Fields::Unnamed(fields) => fields.unnamed.iter().collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+        };
+        let field_values = all_fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let member = field
+                    .ident
+                    .as_ref()
+                    .map(|ident| Member::Named(ident.clone()))
+                    .unwrap_or_else(|| Member::Unnamed(Index::from(index)));
+                match active_fields.iter().position(|(_, i)| *i == index) {
+                    Some(active_index) => {
+                        let field_name = &field_names[active_index];
+                        let default = get_field_attrs(field)
+                            .and_then(|attrs| attrs.default)
+                            .unwrap_or(false);
+                        if default {
+                            quote! {
+                                #member: #bevy_reflect_path::enum_variant_field_or_default(
+                                    enum_value,
+                                    #field_name,
+                                )
+                            }
+                        } else {
+                            quote! {
+                                #member: #bevy_reflect_path::enum_variant_field(enum_value, #field_name)
+                            }
+                        }
+                    }
+                    None => quote!(#member: Default::default()),
+                }
+            })
+            .collect::<Vec<_>>();
+        constructors.push(quote!(Self::#variant_ident { #(#field_values,)* }));
+
+        let variant_fields = match &variant.fields {
+            Fields::Named(_) => {
+                let field_infos = active_fields.iter().zip(field_names.iter()).map(
+                    |((field, _), name)| {
+                        let ty = &field.ty;
+                        let docs = get_docs(&field.attrs);
+                        quote!(#bevy_reflect_path::NamedField::new::<#ty>(#name).with_docs(#docs))
+                    },
+                );
+                quote!(#bevy_reflect_path::VariantFields::Struct(vec![#(#field_infos),*]))
+            }
+            Fields::Unnamed(_) => {
+                let field_infos = active_fields.iter().zip(field_indices.iter()).map(
+                    |((field, _), index)| {
+                        let ty = &field.ty;
+                        let docs = get_docs(&field.attrs);
+                        quote!(#bevy_reflect_path::UnnamedField::new::<#ty>(#index).with_docs(#docs))
+                    },
+                );
+                quote!(#bevy_reflect_path::VariantFields::Tuple(vec![#(#field_infos),*]))
+            }
+            Fields::Unit => quote!(#bevy_reflect_path::VariantFields::Unit),
+        };
+        let variant_docs = get_docs(&variant.attrs);
+        variant_infos.push(quote! {
+            #bevy_reflect_path::VariantInfo::new(#variant_name, #variant_fields).with_docs(#variant_docs)
+        });
+
+        variant_names.push(variant_name);
+        variant_types.push(variant_type);
+        variant_patterns.push(quote!(Self::#variant_ident { .. }));
+        field_lens.push(field_count);
+    }
+    let variant_indices = (0..variant_names.len()).collect::<Vec<usize>>();
+
+    let hash_fn = reflect_attrs.get_hash_impl(bevy_reflect_path);
+    let serialize_fn = reflect_attrs.get_serialize_impl(bevy_reflect_path);
+    let partial_eq_fn = match reflect_attrs.reflect_partial_eq {
+        TraitImpl::NotImplemented => quote! {
+            use #bevy_reflect_path::Enum;
+            #bevy_reflect_path::enum_partial_eq(self, value)
+        },
+        TraitImpl::Implemented | TraitImpl::Custom(_) => reflect_attrs.get_partial_eq_impl(),
+    };
+
+    let docs = get_docs(attrs);
+    let typed_impl = impl_typed(
+        enum_name,
+        generics,
+        quote! {
+            #bevy_reflect_path::TypeInfo::Enum(
+                #bevy_reflect_path::EnumInfo::new::<Self>(&[#(#variant_infos),*]).with_docs(#docs)
+            )
+        },
+        bevy_reflect_path,
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let field_mut_arms = field_arms.clone();
+    let field_at_mut_arms = field_at_arms.clone();
+
+    TokenStream::from(quote! {
+        #get_type_registration_impl
+
+        #typed_impl
+
+        #[allow(unused_variables)]
+        impl #impl_generics #bevy_reflect_path::Enum for #enum_name#ty_generics #where_clause {
+            fn variant_name(&self) -> &str {
+                match self {
+                    #(#variant_patterns => #variant_names,)*
+                }
+            }
+
+            fn variant_index(&self) -> usize {
+                match self {
+                    #(#variant_patterns => #variant_indices,)*
+                }
+            }
+
+            fn variant_type(&self) -> #bevy_reflect_path::VariantType {
+                match self {
+                    #(#variant_patterns => #variant_types,)*
+                }
+            }
+
+            fn field(&self, name: &str) -> Option<&dyn #bevy_reflect_path::Reflect> {
+                match self {
+                    #(#field_arms)*
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn #bevy_reflect_path::Reflect> {
+                match self {
+                    #(#field_mut_arms)*
+                }
+            }
+
+            fn field_at(&self, index: usize) -> Option<&dyn #bevy_reflect_path::Reflect> {
+                match self {
+                    #(#field_at_arms)*
+                }
+            }
+
+            fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn #bevy_reflect_path::Reflect> {
+                match self {
+                    #(#field_at_mut_arms)*
+                }
+            }
+
+            fn name_at(&self, index: usize) -> Option<&str> {
+                match self {
+                    #(#name_at_arms)*
+                }
+            }
+
+            fn field_len(&self) -> usize {
+                match self {
+                    #(#variant_patterns => #field_lens,)*
+                }
+            }
+
+            fn iter_fields(&self) -> #bevy_reflect_path::VariantFieldIter {
+                #bevy_reflect_path::VariantFieldIter::new(self)
+            }
+
+            fn clone_dynamic(&self) -> #bevy_reflect_path::DynamicEnum {
+                let variant = match self {
+                    #(#dynamic_variants)*
+                };
+                let mut dynamic = #bevy_reflect_path::DynamicEnum::default();
+                dynamic.set_name(self.type_name().to_string());
+                dynamic.set_variant(self.variant_name(), variant);
+                dynamic.set_variant_index(self.variant_index());
+                dynamic
+            }
+        }
+
+        // SAFE: any and any_mut both return self
+        unsafe impl #impl_generics #bevy_reflect_path::Reflect for #enum_name#ty_generics #where_clause {
+            #[inline]
+            fn type_name(&self) -> &str {
+                std::any::type_name::<Self>()
+            }
+
+            #[inline]
+            fn any(&self) -> &dyn std::any::Any {
+                self
+            }
+            #[inline]
+            fn any_mut(&mut self) -> &mut dyn std::any::Any {
+                self
+            }
+            #[inline]
+            fn clone_value(&self) -> Box<dyn #bevy_reflect_path::Reflect> {
+                use #bevy_reflect_path::Enum;
+                Box::new(self.clone_dynamic())
+            }
+            #[inline]
+            fn set(&mut self, value: Box<dyn #bevy_reflect_path::Reflect>) -> Result<(), Box<dyn #bevy_reflect_path::Reflect>> {
+                *self = value.take()?;
+                Ok(())
+            }
+
+            #[inline]
+            fn apply(&mut self, value: &dyn #bevy_reflect_path::Reflect) {
+                use #bevy_reflect_path::Enum;
+                if let #bevy_reflect_path::ReflectRef::Enum(enum_value) = value.reflect_ref() {
+                    if self.variant_name() == enum_value.variant_name() {
+                        #bevy_reflect_path::enum_apply_fields(self, enum_value);
+                    } else {
+                        *self = match enum_value.variant_name() {
+                            #(#variant_names => #constructors,)*
+                            name => panic!("{} does not have a variant named {}.", std::any::type_name::<Self>(), name),
+                        };
+                    }
+                } else {
+                    panic!("Attempted to apply non-enum type to enum type.");
+                }
+            }
+
+            fn reflect_ref(&self) -> #bevy_reflect_path::ReflectRef {
+                #bevy_reflect_path::ReflectRef::Enum(self)
+            }
+
+            fn reflect_mut(&mut self) -> #bevy_reflect_path::ReflectMut {
+                #bevy_reflect_path::ReflectMut::Enum(self)
+            }
+
+            fn serializable(&self) -> Option<#bevy_reflect_path::serde::Serializable> {
+                #serialize_fn
+            }
+
+            fn reflect_hash(&self) -> Option<u64> {
+                #hash_fn
+            }
+
+            fn reflect_partial_eq(&self, value: &dyn #bevy_reflect_path::Reflect) -> Option<bool> {
+                #partial_eq_fn
+            }
+        }
+    })
+}
+
 fn impl_value(
     type_name: &Ident,
     generics: &Generics,
     get_type_registration_impl: proc_macro2::TokenStream,
     bevy_reflect_path: &Path,
     reflect_attrs: &ReflectAttrs,
+    attrs: &[Attribute],
 ) -> TokenStream {
     let hash_fn = reflect_attrs.get_hash_impl(&bevy_reflect_path);
     let partial_eq_fn = reflect_attrs.get_partial_eq_impl();
     let serialize_fn = reflect_attrs.get_serialize_impl(&bevy_reflect_path);
+    let docs = get_docs(attrs);
+    let typed_impl = impl_typed(
+        type_name,
+        generics,
+        quote! {
+            #bevy_reflect_path::TypeInfo::Value(#bevy_reflect_path::ValueInfo::new::<Self>().with_docs(#docs))
+        },
+        bevy_reflect_path,
+    );
 
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     TokenStream::from(quote! {
         #get_type_registration_impl
 
+        #typed_impl
+
         // SAFE: any and any_mut both return self
         unsafe impl #impl_generics #bevy_reflect_path::Reflect for #type_name#ty_generics #where_clause  {
             #[inline]
@@ -519,6 +962,58 @@ fn impl_value(
         }
     })
 }
+fn impl_typed(
+    type_name: &Ident,
+    generics: &Generics,
+    type_info: proc_macro2::TokenStream,
+    bevy_reflect_path: &Path,
+) -> proc_macro2::TokenStream {
+    // statics are shared by every instantiation of a generic type, so generic types need a cell
+    // that stores one TypeInfo per type
+    let get_type_info = if generics.params.is_empty() {
+        quote! {
+            static CELL: #bevy_reflect_path::NonGenericTypeInfoCell = #bevy_reflect_path::NonGenericTypeInfoCell::new();
+            CELL.get_or_init(|| #type_info)
+        }
+    } else {
+        quote! {
+            static CELL: #bevy_reflect_path::GenericTypeInfoCell = #bevy_reflect_path::GenericTypeInfoCell::new();
+            CELL.get_or_insert::<Self, _>(|| #type_info)
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #bevy_reflect_path::Typed for #type_name#ty_generics #where_clause {
+            fn type_info() -> &'static #bevy_reflect_path::TypeInfo {
+                #get_type_info
+            }
+        }
+    }
+}
+
+/// Returns the doc comment in `attrs` as an `Option<&'static str>` expression.
+fn get_docs(attrs: &[Attribute]) -> proc_macro2::TokenStream {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(name_value)) => match name_value.lit {
+                Lit::Str(doc) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        quote!(None)
+    } else {
+        let docs = lines.join("\n");
+        quote!(Some(#docs))
+    }
+}
+
 struct ReflectDef {
     type_name: Ident,
     generics: Generics,
@@ -584,6 +1079,7 @@ pub fn impl_reflect_value(input: TokenStream) -> TokenStream {
         get_type_registration_impl,
         &bevy_reflect_path,
         &reflect_attrs,
+        &[],
     )
 }
 
@@ -0,0 +1,387 @@
+use crate::{
+    serde::Serializable, DynamicStruct, DynamicTuple, Reflect, ReflectMut, ReflectRef, Struct,
+    Tuple,
+};
+use std::any::Any;
+
+/// The kind of fields held by an enum variant.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VariantType {
+    /// A variant with named fields, like `Shape::Rect { width: f32, height: f32 }`
+    Struct,
+    /// A variant with unnamed fields, like `Shape::Circle(f32)`
+    Tuple,
+    /// A variant without fields, like `Shape::Empty`
+    Unit,
+}
+
+/// A rust "enum" reflection. Only the fields of the active variant are accessible.
+///
+/// Fields of struct variants can be accessed by name or by index, and fields of tuple variants
+/// by index.
+///
+/// Applying an enum with a different variant replaces the active variant. This requires the
+/// fields of the new variant to be concrete values (like the ones created by deserializing
+/// `#[reflect_value]` types), unless they are marked with `#[reflect(default)]`: dynamic values
+/// of those fields, such as the [`DynamicStruct`]s created by deserializing a struct, are applied
+/// to a default value of the field's type.
+pub trait Enum: Reflect {
+    fn variant_name(&self) -> &str;
+    fn variant_index(&self) -> usize;
+    fn variant_type(&self) -> VariantType;
+    fn field(&self, name: &str) -> Option<&dyn Reflect>;
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect>;
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect>;
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect>;
+    /// Returns the name of the field at `index`, or `None` if the active variant is not a struct
+    /// variant.
+    fn name_at(&self, index: usize) -> Option<&str>;
+    fn field_len(&self) -> usize;
+    fn iter_fields(&self) -> VariantFieldIter;
+    fn clone_dynamic(&self) -> DynamicEnum;
+}
+
+pub struct VariantFieldIter<'a> {
+    pub(crate) enum_value: &'a dyn Enum,
+    pub(crate) index: usize,
+}
+
+impl<'a> VariantFieldIter<'a> {
+    pub fn new(value: &'a dyn Enum) -> Self {
+        VariantFieldIter {
+            enum_value: value,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for VariantFieldIter<'a> {
+    type Item = &'a dyn Reflect;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.enum_value.field_at(self.index);
+        self.index += 1;
+        value
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.enum_value.field_len();
+        (size, Some(size))
+    }
+}
+
+impl<'a> ExactSizeIterator for VariantFieldIter<'a> {}
+
+/// The fields of the active variant of a [`DynamicEnum`].
+pub enum DynamicVariant {
+    Unit,
+    Tuple(DynamicTuple),
+    Struct(DynamicStruct),
+}
+
+impl Default for DynamicVariant {
+    fn default() -> Self {
+        DynamicVariant::Unit
+    }
+}
+
+impl DynamicVariant {
+    pub fn variant_type(&self) -> VariantType {
+        match self {
+            DynamicVariant::Unit => VariantType::Unit,
+            DynamicVariant::Tuple(_) => VariantType::Tuple,
+            DynamicVariant::Struct(_) => VariantType::Struct,
+        }
+    }
+}
+
+impl Clone for DynamicVariant {
+    fn clone(&self) -> Self {
+        match self {
+            DynamicVariant::Unit => DynamicVariant::Unit,
+            DynamicVariant::Tuple(tuple) => DynamicVariant::Tuple(tuple.clone_dynamic()),
+            DynamicVariant::Struct(struct_value) => {
+                DynamicVariant::Struct(struct_value.clone_dynamic())
+            }
+        }
+    }
+}
+
+/// A dynamic enum value.
+///
+/// The variant index is only meaningful for values cloned from a concrete enum. Enums read by the
+/// [`ReflectDeserializer`](crate::serde::ReflectDeserializer) are identified by their variant name
+/// and always have an index of 0.
+#[derive(Default)]
+pub struct DynamicEnum {
+    name: String,
+    variant_name: String,
+    variant_index: usize,
+    variant: DynamicVariant,
+}
+
+impl DynamicEnum {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn variant(&self) -> &DynamicVariant {
+        &self.variant
+    }
+
+    pub fn set_variant(&mut self, name: &str, variant: DynamicVariant) {
+        self.variant_name = name.to_string();
+        self.variant = variant;
+    }
+
+    pub fn set_variant_index(&mut self, index: usize) {
+        self.variant_index = index;
+    }
+}
+
+impl Enum for DynamicEnum {
+    #[inline]
+    fn variant_name(&self) -> &str {
+        &self.variant_name
+    }
+
+    #[inline]
+    fn variant_index(&self) -> usize {
+        self.variant_index
+    }
+
+    #[inline]
+    fn variant_type(&self) -> VariantType {
+        self.variant.variant_type()
+    }
+
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        match &self.variant {
+            DynamicVariant::Struct(struct_value) => struct_value.field(name),
+            _ => None,
+        }
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        match &mut self.variant {
+            DynamicVariant::Struct(struct_value) => struct_value.field_mut(name),
+            _ => None,
+        }
+    }
+
+    fn field_at(&self, index: usize) -> Option<&dyn Reflect> {
+        match &self.variant {
+            DynamicVariant::Unit => None,
+            DynamicVariant::Tuple(tuple) => tuple.field(index),
+            DynamicVariant::Struct(struct_value) => struct_value.field_at(index),
+        }
+    }
+
+    fn field_at_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        match &mut self.variant {
+            DynamicVariant::Unit => None,
+            DynamicVariant::Tuple(tuple) => tuple.field_mut(index),
+            DynamicVariant::Struct(struct_value) => struct_value.field_at_mut(index),
+        }
+    }
+
+    fn name_at(&self, index: usize) -> Option<&str> {
+        match &self.variant {
+            DynamicVariant::Struct(struct_value) => struct_value.name_at(index),
+            _ => None,
+        }
+    }
+
+    fn field_len(&self) -> usize {
+        match &self.variant {
+            DynamicVariant::Unit => 0,
+            DynamicVariant::Tuple(tuple) => tuple.field_len(),
+            DynamicVariant::Struct(struct_value) => struct_value.field_len(),
+        }
+    }
+
+    #[inline]
+    fn iter_fields(&self) -> VariantFieldIter {
+        VariantFieldIter::new(self)
+    }
+
+    fn clone_dynamic(&self) -> DynamicEnum {
+        DynamicEnum {
+            name: self.name.clone(),
+            variant_name: self.variant_name.clone(),
+            variant_index: self.variant_index,
+            variant: self.variant.clone(),
+        }
+    }
+}
+
+// SAFE: any and any_mut both return self
+unsafe impl Reflect for DynamicEnum {
+    #[inline]
+    fn type_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    #[inline]
+    fn any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    #[inline]
+    fn clone_value(&self) -> Box<dyn Reflect> {
+        Box::new(self.clone_dynamic())
+    }
+
+    #[inline]
+    fn reflect_ref(&self) -> ReflectRef {
+        ReflectRef::Enum(self)
+    }
+
+    #[inline]
+    fn reflect_mut(&mut self) -> ReflectMut {
+        ReflectMut::Enum(self)
+    }
+
+    fn apply(&mut self, value: &dyn Reflect) {
+        if let ReflectRef::Enum(enum_value) = value.reflect_ref() {
+            if self.variant_name == enum_value.variant_name() {
+                enum_apply_fields(self, enum_value);
+            } else {
+                *self = enum_value.clone_dynamic();
+            }
+        } else {
+            panic!("Attempted to apply non-enum type to enum type.");
+        }
+    }
+
+    fn set(&mut self, value: Box<dyn Reflect>) -> Result<(), Box<dyn Reflect>> {
+        *self = value.take()?;
+        Ok(())
+    }
+
+    fn reflect_hash(&self) -> Option<u64> {
+        None
+    }
+
+    fn reflect_partial_eq(&self, value: &dyn Reflect) -> Option<bool> {
+        enum_partial_eq(self, value)
+    }
+
+    fn serializable(&self) -> Option<Serializable> {
+        None
+    }
+}
+
+/// Applies the fields of `b` to the fields of `a`, which must have the same active variant.
+#[inline]
+pub fn enum_apply_fields<E: Enum>(a: &mut E, b: &dyn Enum) {
+    for (i, value) in b.iter_fields().enumerate() {
+        let field = match b.name_at(i) {
+            Some(name) => a.field_mut(name),
+            None => a.field_at_mut(i),
+        };
+        if let Some(field) = field {
+            field.apply(value);
+        }
+    }
+}
+
+/// Returns the value of a field of the active variant of `value` as a `T`. Used by
+/// `#[derive(Reflect)]` to switch enums to a different variant.
+///
+/// `field` is the field name for struct variants, and the field index for tuple variants.
+///
+/// # Panics
+/// Panics if the field does not exist, or if it can't be converted to a `T`.
+pub fn enum_variant_field<T: Reflect>(value: &dyn Enum, field: &str) -> T {
+    let field_value = variant_field(value, field);
+    match field_value.clone_value().take::<T>() {
+        Ok(field_value) => field_value,
+        Err(field_value) => panic!(
+            "Attempted to switch to variant {} of {}, but field {} is a {} instead of a {}.",
+            value.variant_name(),
+            value.type_name(),
+            field,
+            field_value.type_name(),
+            std::any::type_name::<T>(),
+        ),
+    }
+}
+
+/// Like [`enum_variant_field`], but fields that are dynamic values, e.g. a
+/// [`DynamicStruct`](crate::DynamicStruct) produced by the deserializer, are applied to a default
+/// `T`. Used by `#[derive(Reflect)]` for fields marked with `#[reflect(default)]`.
+///
+/// # Panics
+/// Panics if the field does not exist.
+pub fn enum_variant_field_or_default<T: Reflect + Default>(value: &dyn Enum, field: &str) -> T {
+    let field_value = variant_field(value, field);
+    match field_value.clone_value().take::<T>() {
+        Ok(field_value) => field_value,
+        Err(_) => {
+            let mut new_value = T::default();
+            new_value.apply(field_value);
+            new_value
+        }
+    }
+}
+
+fn variant_field<'a>(value: &'a dyn Enum, field: &str) -> &'a dyn Reflect {
+    let field_value = match value.variant_type() {
+        VariantType::Struct => value.field(field),
+        VariantType::Tuple => field
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| value.field_at(index)),
+        VariantType::Unit => None,
+    };
+    field_value.unwrap_or_else(|| {
+        panic!(
+            "Attempted to switch to variant {} of {}, but field {} is missing.",
+            value.variant_name(),
+            value.type_name(),
+            field,
+        )
+    })
+}
+
+#[inline]
+pub fn enum_partial_eq<E: Enum>(a: &E, b: &dyn Reflect) -> Option<bool> {
+    let enum_value = if let ReflectRef::Enum(enum_value) = b.reflect_ref() {
+        enum_value
+    } else {
+        return Some(false);
+    };
+
+    if a.variant_name() != enum_value.variant_name()
+        || a.variant_type() != enum_value.variant_type()
+        || a.field_len() != enum_value.field_len()
+    {
+        return Some(false);
+    }
+
+    for (i, value) in enum_value.iter_fields().enumerate() {
+        let field = match enum_value.name_at(i) {
+            Some(name) => a.field(name),
+            None => a.field_at(i),
+        };
+        if let Some(field_value) = field {
+            if let Some(false) | None = field_value.reflect_partial_eq(value) {
+                return Some(false);
+            }
+        } else {
+            return Some(false);
+        }
+    }
+
+    Some(true)
+}
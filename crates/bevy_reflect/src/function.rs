@@ -0,0 +1,264 @@
+use crate::Reflect;
+use std::{
+    any::{type_name, TypeId},
+    sync::Arc,
+};
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum ReflectCallError {
+    #[error("expected {expected} arguments, but received {received}")]
+    ArgumentCount { expected: usize, received: usize },
+    #[error("expected argument {index} to be a {expected}, but received a {received}")]
+    ArgumentType {
+        index: usize,
+        expected: &'static str,
+        received: String,
+    },
+    #[error("expected the receiver to be a {expected}, but received a {received}")]
+    ReceiverType {
+        expected: &'static str,
+        received: String,
+    },
+    #[error("the method takes `&mut self`, but was called with a shared reference")]
+    MutableReceiver,
+}
+
+type FunctionFn =
+    dyn Fn(&[&dyn Reflect]) -> Result<Box<dyn Reflect>, ReflectCallError> + Send + Sync;
+type MethodFn = dyn Fn(&dyn Reflect, &[&dyn Reflect]) -> Result<Box<dyn Reflect>, ReflectCallError>
+    + Send
+    + Sync;
+type MethodMutFn = dyn Fn(&mut dyn Reflect, &[&dyn Reflect]) -> Result<Box<dyn Reflect>, ReflectCallError>
+    + Send
+    + Sync;
+
+/// A type-erased function that can be called with reflected arguments.
+///
+/// Any `Fn` whose arguments are `Reflect + Clone` and whose return type is [`Reflect`] can be
+/// converted into a [`ReflectFunction`] with [`IntoReflectFunction`]. Arguments are cloned out of
+/// the `&dyn Reflect` values passed to [`ReflectFunction::call`], so they must be the concrete
+/// argument types rather than dynamic values like [`DynamicStruct`](crate::DynamicStruct).
+#[derive(Clone)]
+pub struct ReflectFunction {
+    arg_type_names: Vec<&'static str>,
+    return_type_name: &'static str,
+    func: Arc<FunctionFn>,
+}
+
+impl ReflectFunction {
+    pub fn call(&self, args: &[&dyn Reflect]) -> Result<Box<dyn Reflect>, ReflectCallError> {
+        check_arg_count(&self.arg_type_names, args)?;
+        (self.func)(args)
+    }
+
+    pub fn arg_type_names(&self) -> &[&'static str] {
+        &self.arg_type_names
+    }
+
+    pub fn return_type_name(&self) -> &'static str {
+        self.return_type_name
+    }
+}
+
+#[derive(Clone)]
+enum MethodReceiver {
+    Ref(Arc<MethodFn>),
+    Mut(Arc<MethodMutFn>),
+}
+
+/// A type-erased method that can be called on a reflected receiver with reflected arguments.
+///
+/// Any `Fn` taking `&T` or `&mut T` followed by `Reflect + Clone` arguments and returning a
+/// [`Reflect`] value can be converted into a [`ReflectMethod`] with [`IntoReflectMethod`], which
+/// includes methods like `Timer::reset`.
+#[derive(Clone)]
+pub struct ReflectMethod {
+    receiver_type_id: TypeId,
+    receiver_type_name: &'static str,
+    arg_type_names: Vec<&'static str>,
+    return_type_name: &'static str,
+    receiver: MethodReceiver,
+}
+
+impl ReflectMethod {
+    /// Calls a method that takes `&self`. Fails with [`ReflectCallError::MutableReceiver`] if the
+    /// method takes `&mut self`.
+    pub fn call(
+        &self,
+        receiver: &dyn Reflect,
+        args: &[&dyn Reflect],
+    ) -> Result<Box<dyn Reflect>, ReflectCallError> {
+        check_arg_count(&self.arg_type_names, args)?;
+        match &self.receiver {
+            MethodReceiver::Ref(func) => func(receiver, args),
+            MethodReceiver::Mut(_) => Err(ReflectCallError::MutableReceiver),
+        }
+    }
+
+    /// Calls a method that takes either `&self` or `&mut self`.
+    pub fn call_mut(
+        &self,
+        receiver: &mut dyn Reflect,
+        args: &[&dyn Reflect],
+    ) -> Result<Box<dyn Reflect>, ReflectCallError> {
+        check_arg_count(&self.arg_type_names, args)?;
+        match &self.receiver {
+            MethodReceiver::Ref(func) => func(receiver, args),
+            MethodReceiver::Mut(func) => func(receiver, args),
+        }
+    }
+
+    /// Returns true if the method takes `&mut self`.
+    pub fn is_mut(&self) -> bool {
+        matches!(self.receiver, MethodReceiver::Mut(_))
+    }
+
+    pub fn receiver_type_id(&self) -> TypeId {
+        self.receiver_type_id
+    }
+
+    pub fn receiver_type_name(&self) -> &'static str {
+        self.receiver_type_name
+    }
+
+    pub fn arg_type_names(&self) -> &[&'static str] {
+        &self.arg_type_names
+    }
+
+    pub fn return_type_name(&self) -> &'static str {
+        self.return_type_name
+    }
+}
+
+/// Conversion into a [`ReflectFunction`]. `Params` is a marker type used to tell apart the
+/// implementations for each function signature.
+pub trait IntoReflectFunction<Params> {
+    fn into_reflect_function(self) -> ReflectFunction;
+}
+
+/// Conversion into a [`ReflectMethod`] with a receiver of type `T`. `Params` is a marker type used
+/// to tell apart the implementations for each method signature.
+pub trait IntoReflectMethod<T, Params> {
+    fn into_reflect_method(self) -> ReflectMethod;
+}
+
+fn check_arg_count(
+    arg_type_names: &[&'static str],
+    args: &[&dyn Reflect],
+) -> Result<(), ReflectCallError> {
+    if arg_type_names.len() == args.len() {
+        Ok(())
+    } else {
+        Err(ReflectCallError::ArgumentCount {
+            expected: arg_type_names.len(),
+            received: args.len(),
+        })
+    }
+}
+
+fn take_arg<A: Reflect + Clone>(
+    (index, arg): (usize, &&dyn Reflect),
+) -> Result<A, ReflectCallError> {
+    arg.downcast_ref::<A>()
+        .cloned()
+        .ok_or_else(|| ReflectCallError::ArgumentType {
+            index,
+            expected: type_name::<A>(),
+            received: arg.type_name().to_string(),
+        })
+}
+
+fn receiver_type_error<T: Reflect>(receiver: &dyn Reflect) -> ReflectCallError {
+    ReflectCallError::ReceiverType {
+        expected: type_name::<T>(),
+        received: receiver.type_name().to_string(),
+    }
+}
+
+macro_rules! impl_into_reflect_function {
+    ($($arg: ident),*) => {
+        impl<Func, R, $($arg),*> IntoReflectFunction<fn($($arg),*) -> R> for Func
+        where
+            Func: Fn($($arg),*) -> R + Send + Sync + 'static,
+            R: Reflect,
+            $($arg: Reflect + Clone,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn into_reflect_function(self) -> ReflectFunction {
+                ReflectFunction {
+                    arg_type_names: vec![$(type_name::<$arg>()),*],
+                    return_type_name: type_name::<R>(),
+                    func: Arc::new(move |args| {
+                        let mut args = args.iter().enumerate();
+                        $(let $arg = take_arg::<$arg>(args.next().unwrap())?;)*
+                        Ok(Box::new(self($($arg),*)))
+                    }),
+                }
+            }
+        }
+
+        impl<T, Func, R, $($arg),*> IntoReflectMethod<T, fn(&T, $($arg),*) -> R> for Func
+        where
+            T: Reflect,
+            Func: Fn(&T, $($arg),*) -> R + Send + Sync + 'static,
+            R: Reflect,
+            $($arg: Reflect + Clone,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn into_reflect_method(self) -> ReflectMethod {
+                ReflectMethod {
+                    receiver_type_id: TypeId::of::<T>(),
+                    receiver_type_name: type_name::<T>(),
+                    arg_type_names: vec![$(type_name::<$arg>()),*],
+                    return_type_name: type_name::<R>(),
+                    receiver: MethodReceiver::Ref(Arc::new(move |receiver, args| {
+                        let receiver = receiver
+                            .downcast_ref::<T>()
+                            .ok_or_else(|| receiver_type_error::<T>(receiver))?;
+                        let mut args = args.iter().enumerate();
+                        $(let $arg = take_arg::<$arg>(args.next().unwrap())?;)*
+                        Ok(Box::new(self(receiver, $($arg),*)))
+                    })),
+                }
+            }
+        }
+
+        impl<T, Func, R, $($arg),*> IntoReflectMethod<T, fn(&mut T, $($arg),*) -> R> for Func
+        where
+            T: Reflect,
+            Func: Fn(&mut T, $($arg),*) -> R + Send + Sync + 'static,
+            R: Reflect,
+            $($arg: Reflect + Clone,)*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn into_reflect_method(self) -> ReflectMethod {
+                ReflectMethod {
+                    receiver_type_id: TypeId::of::<T>(),
+                    receiver_type_name: type_name::<T>(),
+                    arg_type_names: vec![$(type_name::<$arg>()),*],
+                    return_type_name: type_name::<R>(),
+                    receiver: MethodReceiver::Mut(Arc::new(move |receiver, args| {
+                        if !receiver.is::<T>() {
+                            return Err(receiver_type_error::<T>(receiver));
+                        }
+                        let receiver = receiver.downcast_mut::<T>().unwrap();
+                        let mut args = args.iter().enumerate();
+                        $(let $arg = take_arg::<$arg>(args.next().unwrap())?;)*
+                        Ok(Box::new(self(receiver, $($arg),*)))
+                    })),
+                }
+            }
+        }
+    };
+}
+
+impl_into_reflect_function!();
+impl_into_reflect_function!(A);
+impl_into_reflect_function!(A, B);
+impl_into_reflect_function!(A, B, C);
+impl_into_reflect_function!(A, B, C, D);
+impl_into_reflect_function!(A, B, C, D, E);
+impl_into_reflect_function!(A, B, C, D, E, F);
+impl_into_reflect_function!(A, B, C, D, E, F, G);
+impl_into_reflect_function!(A, B, C, D, E, F, G, H);
@@ -1,7 +1,10 @@
 use smallvec::{Array, SmallVec};
 use std::any::Any;
 
-use crate::{serde::Serializable, List, ListIter, Reflect, ReflectMut, ReflectRef};
+use crate::{
+    serde::Serializable, GenericTypeInfoCell, List, ListInfo, ListIter, Reflect, ReflectMut,
+    ReflectRef, TypeInfo, Typed,
+};
 
 impl<T: Array + Send + Sync + 'static> List for SmallVec<T>
 where
@@ -95,3 +98,13 @@ where
         None
     }
 }
+
+impl<T: Array + Send + Sync + 'static> Typed for SmallVec<T>
+where
+    T::Item: Reflect + Clone,
+{
+    fn type_info() -> &'static TypeInfo {
+        static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+        CELL.get_or_insert::<Self, _>(|| TypeInfo::List(ListInfo::new::<Self, T::Item>()))
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
-    map_partial_eq, serde::Serializable, DynamicMap, FromType, GetTypeRegistration, List, ListIter,
-    Map, MapIter, Reflect, ReflectDeserialize, ReflectMut, ReflectRef, TypeRegistration,
+    map_partial_eq, serde::Serializable, DynamicMap, FromType, GenericTypeInfoCell,
+    GetTypeRegistration, List, ListInfo, ListIter, Map, MapInfo, MapIter, NonGenericTypeInfoCell,
+    Reflect, ReflectDeserialize, ReflectMut, ReflectRef, TypeInfo, TypeRegistration, Typed,
+    ValueInfo,
 };
 
 use bevy_reflect_derive::impl_reflect_value;
@@ -113,6 +115,13 @@ unsafe impl<T: Reflect> Reflect for Vec<T> {
     }
 }
 
+impl<T: Reflect> Typed for Vec<T> {
+    fn type_info() -> &'static TypeInfo {
+        static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+        CELL.get_or_insert::<Self, _>(|| TypeInfo::List(ListInfo::new::<Self, T>()))
+    }
+}
+
 impl<T: Reflect + for<'de> Deserialize<'de>> GetTypeRegistration for Vec<T> {
     fn get_type_registration() -> TypeRegistration {
         let mut registration = TypeRegistration::of::<Vec<T>>();
@@ -217,6 +226,13 @@ unsafe impl<K: Reflect + Clone + Eq + Hash, V: Reflect + Clone> Reflect for Hash
     }
 }
 
+impl<K: Reflect + Clone + Eq + Hash, V: Reflect + Clone> Typed for HashMap<K, V> {
+    fn type_info() -> &'static TypeInfo {
+        static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+        CELL.get_or_insert::<Self, _>(|| TypeInfo::Map(MapInfo::new::<Self, K, V>()))
+    }
+}
+
 impl<K, V> GetTypeRegistration for HashMap<K, V>
 where
     K: Reflect + Clone + Eq + Hash + for<'de> Deserialize<'de>,
@@ -290,6 +306,13 @@ unsafe impl Reflect for Cow<'static, str> {
     }
 }
 
+impl Typed for Cow<'static, str> {
+    fn type_info() -> &'static TypeInfo {
+        static CELL: NonGenericTypeInfoCell = NonGenericTypeInfoCell::new();
+        CELL.get_or_init(|| TypeInfo::Value(ValueInfo::new::<Self>()))
+    }
+}
+
 impl GetTypeRegistration for Cow<'static, str> {
     fn get_type_registration() -> TypeRegistration {
         let mut registration = TypeRegistration::of::<Cow<'static, str>>();
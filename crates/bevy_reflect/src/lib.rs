@@ -1,3 +1,5 @@
+mod enum_trait;
+mod function;
 mod list;
 mod map;
 mod path;
@@ -5,6 +7,7 @@ mod reflect;
 mod struct_trait;
 mod tuple;
 mod tuple_struct;
+mod type_info;
 mod type_registry;
 mod type_uuid;
 mod impls {
@@ -25,11 +28,13 @@ pub mod serde;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        reflect_trait, GetField, GetTupleStructField, Reflect, ReflectDeserialize, Struct,
+        reflect_trait, Enum, GetField, GetTupleStructField, Reflect, ReflectDeserialize, Struct,
         TupleStruct,
     };
 }
 
+pub use enum_trait::*;
+pub use function::*;
 pub use impls::*;
 pub use list::*;
 pub use map::*;
@@ -38,6 +43,7 @@ pub use reflect::*;
 pub use struct_trait::*;
 pub use tuple::*;
 pub use tuple_struct::*;
+pub use type_info::*;
 pub use type_registry::*;
 pub use type_uuid::*;
 
@@ -54,7 +60,9 @@ mod tests {
         Deserializer,
     };
 
-    use crate::serde::{ReflectDeserializer, ReflectSerializer};
+    use crate::serde::{
+        ReflectDeserializer, ReflectSerializer, TypedReflectDeserializer, TypedReflectSerializer,
+    };
 
     use super::*;
 
@@ -317,6 +325,407 @@ mod tests {
         assert!(foo.reflect_partial_eq(&dynamic_struct).unwrap());
     }
 
+    #[test]
+    fn reflect_enum() {
+        #[derive(Reflect, Debug, PartialEq)]
+        enum Shape {
+            Empty,
+            Circle(u32),
+            Rect {
+                width: u32,
+                height: u32,
+                #[reflect(ignore)]
+                _id: u32,
+            },
+        }
+
+        let mut shape = Shape::Rect {
+            width: 1,
+            height: 2,
+            _id: 7,
+        };
+        assert_eq!(shape.variant_name(), "Rect");
+        assert_eq!(shape.variant_index(), 2);
+        assert_eq!(shape.variant_type(), VariantType::Struct);
+        assert_eq!(shape.field_len(), 2);
+        assert_eq!(shape.name_at(1), Some("height"));
+        assert_eq!(*shape.get_path::<u32>("height").unwrap(), 2);
+
+        // patch the fields of the active variant
+        let mut patch = DynamicStruct::default();
+        patch.insert("width", 3u32);
+        let mut dynamic_enum = DynamicEnum::default();
+        dynamic_enum.set_variant("Rect", DynamicVariant::Struct(patch));
+        shape.apply(&dynamic_enum);
+        assert_eq!(
+            shape,
+            Shape::Rect {
+                width: 3,
+                height: 2,
+                _id: 7,
+            }
+        );
+
+        // switch to a different variant
+        let mut fields = DynamicTuple::default();
+        fields.insert(5u32);
+        dynamic_enum.set_variant("Circle", DynamicVariant::Tuple(fields));
+        shape.apply(&dynamic_enum);
+        assert_eq!(shape, Shape::Circle(5));
+        assert_eq!(*shape.get_path::<u32>("0").unwrap(), 5);
+
+        *shape.get_path_mut::<u32>("0").unwrap() = 6;
+        assert!(shape
+            .reflect_partial_eq(&Shape::Circle(6).clone_dynamic())
+            .unwrap());
+        assert!(!shape.reflect_partial_eq(&Shape::Empty).unwrap());
+
+        shape.apply(&Shape::Empty);
+        assert_eq!(shape, Shape::Empty);
+        assert_eq!(shape.field_len(), 0);
+    }
+
+    #[test]
+    fn reflect_enum_serialize() {
+        #[derive(Reflect, Debug, PartialEq)]
+        enum Action {
+            Idle,
+            Walk(i32, i32),
+            Attack { target: String, damage: u32 },
+        }
+
+        #[derive(Reflect)]
+        struct Foo {
+            a: Action,
+            b: Vec<Action>,
+        }
+
+        let foo = Foo {
+            a: Action::Attack {
+                target: "orc".to_string(),
+                damage: 3,
+            },
+            b: vec![Action::Idle, Action::Walk(1, 2)],
+        };
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<u32>();
+        registry.register::<i32>();
+        registry.register::<String>();
+
+        let serializer = ReflectSerializer::new(&foo, &registry);
+        let serialized = to_string_pretty(&serializer, PrettyConfig::default()).unwrap();
+
+        let mut deserializer = Deserializer::from_str(&serialized).unwrap();
+        let reflect_deserializer = ReflectDeserializer::new(&registry);
+        let value = reflect_deserializer.deserialize(&mut deserializer).unwrap();
+        let dynamic_struct = value.take::<DynamicStruct>().unwrap();
+        assert!(foo.reflect_partial_eq(&dynamic_struct).unwrap());
+
+        let mut action = Action::Idle;
+        action.apply(dynamic_struct.field("a").unwrap());
+        assert_eq!(action, foo.a);
+    }
+
+    #[test]
+    fn reflect_enum_serialize_switches_variant_with_struct_field() {
+        #[derive(Reflect, Default, Debug, PartialEq)]
+        struct Target {
+            name: String,
+            distance: u32,
+        }
+
+        #[derive(Reflect, Debug, PartialEq)]
+        enum Order {
+            Hold,
+            Attack {
+                #[reflect(default)]
+                target: Target,
+                damage: u32,
+            },
+        }
+
+        #[derive(Reflect)]
+        struct Foo {
+            order: Order,
+        }
+
+        let foo = Foo {
+            order: Order::Attack {
+                target: Target {
+                    name: "orc".to_string(),
+                    distance: 4,
+                },
+                damage: 3,
+            },
+        };
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<u32>();
+        registry.register::<String>();
+
+        let serializer = ReflectSerializer::new(&foo, &registry);
+        let serialized = to_string_pretty(&serializer, PrettyConfig::default()).unwrap();
+
+        let mut deserializer = Deserializer::from_str(&serialized).unwrap();
+        let reflect_deserializer = ReflectDeserializer::new(&registry);
+        let value = reflect_deserializer.deserialize(&mut deserializer).unwrap();
+        let dynamic_struct = value.take::<DynamicStruct>().unwrap();
+
+        // the deserialized `target` is a `DynamicStruct`, which is applied to a default `Target`
+        let mut order = Order::Hold;
+        order.apply(dynamic_struct.field("order").unwrap());
+        assert_eq!(order, foo.order);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to switch to variant Attack of , but field 0 is a ")]
+    fn reflect_enum_switch_variant_with_dynamic_field() {
+        // fields without `#[reflect(default)]` don't need to implement `Default`
+        #[derive(Reflect, Debug, PartialEq)]
+        struct Target {
+            name: String,
+        }
+
+        #[derive(Reflect, Debug, PartialEq)]
+        enum Order {
+            Hold,
+            Attack(Target),
+        }
+
+        let mut target = DynamicStruct::default();
+        target.insert("name", "orc".to_string());
+        let mut attack = DynamicTuple::default();
+        attack.insert(target);
+        let mut dynamic_order = DynamicEnum::default();
+        dynamic_order.set_variant("Attack", DynamicVariant::Tuple(attack));
+        let mut order = Order::Hold;
+        order.apply(&dynamic_order);
+    }
+
+    #[test]
+    fn reflect_type_info() {
+        /// A unit that can be placed on the map.
+        #[derive(Reflect)]
+        struct Unit {
+            /// Shown above the unit.
+            name: String,
+            health: u32,
+            #[reflect(ignore)]
+            _id: u32,
+            orders: Vec<Order>,
+        }
+
+        #[derive(Reflect)]
+        enum Order {
+            Hold,
+            /// Moves to the given tile.
+            Move(i32, i32),
+            Attack {
+                target: String,
+            },
+        }
+
+        #[derive(Reflect)]
+        struct Wrapper<T: Reflect>(T);
+
+        let info = match <Unit as Typed>::type_info() {
+            TypeInfo::Struct(info) => info,
+            _ => panic!("Expected struct info."),
+        };
+        assert_eq!(info.type_id(), std::any::TypeId::of::<Unit>());
+        assert_eq!(info.docs(), Some("A unit that can be placed on the map."));
+        assert_eq!(info.field_len(), 3);
+        let names = info.iter().map(|field| field.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["name", "health", "orders"]);
+        let name = info.field("name").unwrap();
+        assert_eq!(name.type_id(), std::any::TypeId::of::<String>());
+        assert_eq!(name.docs(), Some("Shown above the unit."));
+        assert_eq!(info.index_of("orders"), Some(2));
+
+        match <Vec<Order> as Typed>::type_info() {
+            TypeInfo::List(info) => {
+                assert_eq!(info.item_type_id(), std::any::TypeId::of::<Order>())
+            }
+            _ => panic!("Expected list info."),
+        }
+
+        let info = match <Order as Typed>::type_info() {
+            TypeInfo::Enum(info) => info,
+            _ => panic!("Expected enum info."),
+        };
+        assert_eq!(info.variant_len(), 3);
+        let move_variant = info.variant("Move").unwrap();
+        assert_eq!(move_variant.variant_type(), VariantType::Tuple);
+        assert_eq!(move_variant.docs(), Some("Moves to the given tile."));
+        assert_eq!(info.index_of("Attack"), Some(2));
+
+        // generic types get one TypeInfo per concrete type
+        let a = <Wrapper<u32> as Typed>::type_info();
+        let b = <Wrapper<String> as Typed>::type_info();
+        assert_eq!(a.type_id(), std::any::TypeId::of::<Wrapper<u32>>());
+        assert_eq!(b.type_id(), std::any::TypeId::of::<Wrapper<String>>());
+        assert!(std::ptr::eq(a, <Wrapper<u32> as Typed>::type_info()));
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Unit>();
+        let registration = registry.get(std::any::TypeId::of::<Unit>()).unwrap();
+        assert!(std::ptr::eq(
+            registration.type_info(),
+            <Unit as Typed>::type_info()
+        ));
+    }
+
+    #[test]
+    fn reflect_typed_serialize() {
+        #[derive(Reflect, Debug, PartialEq)]
+        #[reflect(PartialEq)]
+        struct Foo {
+            a: u32,
+            b: Vec<String>,
+            c: HashMap<u32, String>,
+            d: (i32, Bar),
+            e: Baz,
+            f: Action,
+        }
+
+        #[derive(Reflect, Debug, PartialEq, Default)]
+        #[reflect(PartialEq)]
+        struct Bar {
+            x: u32,
+        }
+
+        #[derive(Reflect, Debug, PartialEq, Default)]
+        #[reflect(PartialEq)]
+        struct Baz(u32, String);
+
+        #[derive(Reflect, Debug, PartialEq)]
+        enum Action {
+            Idle,
+            Attack { target: String },
+        }
+
+        let mut map = HashMap::default();
+        map.insert(1, "one".to_string());
+        let foo = Foo {
+            a: 1,
+            b: vec!["hi".to_string()],
+            c: map,
+            d: (3, Bar { x: 4 }),
+            e: Baz(5, "baz".to_string()),
+            f: Action::Attack {
+                target: "orc".to_string(),
+            },
+        };
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Foo>();
+        registry.register::<Bar>();
+        registry.register::<Baz>();
+        registry.register::<Action>();
+        registry.register::<Vec<String>>();
+        registry.register::<HashMap<u32, String>>();
+        registry.register::<u32>();
+        registry.register::<i32>();
+        registry.register::<String>();
+        registry.register::<(i32, Bar)>();
+
+        let serializer = TypedReflectSerializer::new(&foo);
+        let serialized = to_string_pretty(&serializer, PrettyConfig::default()).unwrap();
+        assert!(!serialized.contains("type"));
+
+        let mut deserializer = Deserializer::from_str(&serialized).unwrap();
+        let registration = registry.get(std::any::TypeId::of::<Foo>()).unwrap();
+        let value = TypedReflectDeserializer::new(registration, &registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+
+        // applying a map only patches the keys that already exist
+        let mut map = HashMap::default();
+        map.insert(1, String::new());
+        let mut deserialized = Foo {
+            a: 0,
+            b: Vec::new(),
+            c: map,
+            d: (0, Bar::default()),
+            e: Baz::default(),
+            f: Action::Idle,
+        };
+        deserialized.apply(&*value);
+        assert_eq!(deserialized, foo);
+    }
+
+    #[test]
+    fn reflect_call() {
+        #[derive(Reflect)]
+        struct Counter {
+            value: u32,
+        }
+
+        impl Counter {
+            fn add(&mut self, amount: u32) {
+                self.value += amount;
+            }
+
+            fn get(&self) -> u32 {
+                self.value
+            }
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<Counter>();
+        registry.register_method("add", Counter::add);
+        registry.register_method("get", Counter::get);
+        registry.register_function("sum", |a: u32, b: u32| a + b);
+
+        let mut counter = Counter { value: 1 };
+        let registration = registry.get_with_short_name("Counter").unwrap();
+        let add = registration.method("add").unwrap();
+        let get = registration.method("get").unwrap();
+        assert!(add.is_mut());
+        assert!(!get.is_mut());
+        assert_eq!(add.arg_type_names(), &[std::any::type_name::<u32>()]);
+
+        let result = add.call_mut(&mut counter, &[&2u32]).unwrap();
+        assert!(result.is::<()>());
+        let result = get.call_mut(&mut counter, &[]).unwrap();
+        assert_eq!(result.downcast_ref::<u32>(), Some(&3));
+        let result = get.call(&counter, &[]).unwrap();
+        assert_eq!(result.downcast_ref::<u32>(), Some(&3));
+
+        assert_eq!(
+            add.call(&counter, &[&2u32]).unwrap_err(),
+            ReflectCallError::MutableReceiver
+        );
+        assert_eq!(
+            add.call_mut(&mut counter, &[]).unwrap_err(),
+            ReflectCallError::ArgumentCount {
+                expected: 1,
+                received: 0
+            }
+        );
+        assert_eq!(
+            add.call_mut(&mut counter, &[&2i32]).unwrap_err(),
+            ReflectCallError::ArgumentType {
+                index: 0,
+                expected: std::any::type_name::<u32>(),
+                received: std::any::type_name::<i32>().to_string(),
+            }
+        );
+        assert_eq!(
+            get.call(&2u32, &[]).unwrap_err(),
+            ReflectCallError::ReceiverType {
+                expected: std::any::type_name::<Counter>(),
+                received: std::any::type_name::<u32>().to_string(),
+            }
+        );
+        assert_eq!(counter.value, 3);
+
+        let sum = registry.get_function("sum").unwrap();
+        let result = sum.call(&[&1u32, &2u32]).unwrap();
+        assert_eq!(result.downcast_ref::<u32>(), Some(&3));
+    }
+
     #[test]
     fn reflect_take() {
         #[derive(Reflect, Debug, PartialEq)]
@@ -1,6 +1,6 @@
 use std::num::ParseIntError;
 
-use crate::{Reflect, ReflectMut, ReflectRef};
+use crate::{Reflect, ReflectMut, ReflectRef, VariantType};
 use thiserror::Error;
 
 #[derive(Debug, PartialEq, Eq, Error)]
@@ -225,6 +225,24 @@ fn read_field<'r, 'p>(
                 },
             )?)
         }
+        ReflectRef::Enum(reflect_enum) => {
+            if reflect_enum.variant_type() == VariantType::Tuple {
+                let tuple_index = field.parse::<usize>()?;
+                Ok(reflect_enum.field_at(tuple_index).ok_or(
+                    ReflectPathError::InvalidTupleStructIndex {
+                        index: current_index,
+                        tuple_struct_index: tuple_index,
+                    },
+                )?)
+            } else {
+                Ok(reflect_enum
+                    .field(field)
+                    .ok_or(ReflectPathError::InvalidField {
+                        index: current_index,
+                        field,
+                    })?)
+            }
+        }
         _ => Err(ReflectPathError::ExpectedStruct {
             index: current_index,
         }),
@@ -254,6 +272,24 @@ fn read_field_mut<'r, 'p>(
                 },
             )?)
         }
+        ReflectMut::Enum(reflect_enum) => {
+            if reflect_enum.variant_type() == VariantType::Tuple {
+                let tuple_index = field.parse::<usize>()?;
+                Ok(reflect_enum.field_at_mut(tuple_index).ok_or(
+                    ReflectPathError::InvalidTupleStructIndex {
+                        index: current_index,
+                        tuple_struct_index: tuple_index,
+                    },
+                )?)
+            } else {
+                Ok(reflect_enum
+                    .field_mut(field)
+                    .ok_or(ReflectPathError::InvalidField {
+                        index: current_index,
+                        field,
+                    })?)
+            }
+        }
         _ => Err(ReflectPathError::ExpectedStruct {
             index: current_index,
         }),
@@ -1,9 +1,12 @@
 use crate::{
-    serde::type_fields, DynamicList, DynamicMap, DynamicStruct, DynamicTuple, DynamicTupleStruct,
-    Reflect, ReflectDeserialize, TypeRegistry,
+    serde::type_fields, DynamicEnum, DynamicList, DynamicMap, DynamicStruct, DynamicTuple,
+    DynamicTupleStruct, DynamicVariant, EnumInfo, ListInfo, MapInfo, NamedField, Reflect,
+    ReflectDeserialize, StructInfo, TypeInfo, TypeRegistration, TypeRegistry, UnnamedField,
+    VariantFields,
 };
 use erased_serde::Deserializer;
 use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use std::any::TypeId;
 
 pub trait DeserializeValue {
     fn deserialize(
@@ -194,6 +197,16 @@ impl<'a, 'de> Visitor<'de> for ReflectVisitor<'a> {
                     })?;
                     return Ok(Box::new(list));
                 }
+                type_fields::ENUM => {
+                    let type_name = type_name
+                        .take()
+                        .ok_or_else(|| de::Error::missing_field(type_fields::TYPE))?;
+                    let mut dynamic_enum = map.next_value_seed(EnumDeserializer {
+                        registry: self.registry,
+                    })?;
+                    dynamic_enum.set_name(type_name);
+                    return Ok(Box::new(dynamic_enum));
+                }
                 type_fields::VALUE => {
                     let type_name = type_name
                         .take()
@@ -411,6 +424,76 @@ impl<'a, 'de> Visitor<'de> for TupleStructVisitor<'a> {
     }
 }
 
+struct EnumDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for EnumDeserializer<'a> {
+    type Value = DynamicEnum;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(EnumVisitor {
+            registry: self.registry,
+        })
+    }
+}
+
+struct EnumVisitor<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for EnumVisitor<'a> {
+    type Value = DynamicEnum;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("enum value")
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let mut variant_name: Option<String> = None;
+        let mut variant = DynamicVariant::Unit;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                type_fields::VARIANT => {
+                    variant_name = Some(map.next_value()?);
+                }
+                type_fields::STRUCT => {
+                    variant = DynamicVariant::Struct(map.next_value_seed(StructDeserializer {
+                        registry: self.registry,
+                    })?);
+                }
+                type_fields::TUPLE => {
+                    variant = DynamicVariant::Tuple(map.next_value_seed(TupleDeserializer {
+                        registry: self.registry,
+                    })?);
+                }
+                _ => {
+                    return Err(de::Error::unknown_field(
+                        key.as_str(),
+                        &[
+                            type_fields::VARIANT,
+                            type_fields::STRUCT,
+                            type_fields::TUPLE,
+                        ],
+                    ))
+                }
+            }
+        }
+
+        let variant_name =
+            variant_name.ok_or_else(|| de::Error::missing_field(type_fields::VARIANT))?;
+        let mut dynamic_enum = DynamicEnum::default();
+        dynamic_enum.set_variant(&variant_name, variant);
+        Ok(dynamic_enum)
+    }
+}
+
 struct TupleDeserializer<'a> {
     registry: &'a TypeRegistry,
 }
@@ -452,3 +535,375 @@ impl<'a, 'de> Visitor<'de> for TupleVisitor<'a> {
         Ok(tuple)
     }
 }
+
+/// Deserializes a value of a known type that was serialized without the `"type"` tags written by
+/// [`ReflectSerializer`](crate::serde::ReflectSerializer), like the output of
+/// [`TypedReflectSerializer`](crate::serde::TypedReflectSerializer).
+///
+/// The shape of the data is read from the [`TypeInfo`] of the type, and the types of its fields
+/// are looked up in the [`TypeRegistry`], so every nested type must be registered. Values that are
+/// not [`TypeInfo::Value`]s are returned as dynamic values (like [`DynamicStruct`]) which can be
+/// applied to a concrete value with [`Reflect::apply`].
+pub struct TypedReflectDeserializer<'a> {
+    registration: &'a TypeRegistration,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> TypedReflectDeserializer<'a> {
+    pub fn new(registration: &'a TypeRegistration, registry: &'a TypeRegistry) -> Self {
+        TypedReflectDeserializer {
+            registration,
+            registry,
+        }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for TypedReflectDeserializer<'a> {
+    type Value = Box<dyn Reflect>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let registry = self.registry;
+        match self.registration.type_info() {
+            TypeInfo::Struct(info) => {
+                let mut dynamic_struct =
+                    deserializer.deserialize_map(TypedStructVisitor { info, registry })?;
+                dynamic_struct.set_name(info.type_name().to_string());
+                Ok(Box::new(dynamic_struct))
+            }
+            TypeInfo::TupleStruct(info) => {
+                let fields = deserializer.deserialize_seq(TypedTupleVisitor {
+                    fields: info.iter().as_slice(),
+                    registry,
+                })?;
+                let mut tuple_struct = DynamicTupleStruct::default();
+                tuple_struct.set_name(info.type_name().to_string());
+                for field in fields {
+                    tuple_struct.insert_boxed(field);
+                }
+                Ok(Box::new(tuple_struct))
+            }
+            TypeInfo::Tuple(info) => {
+                let fields = deserializer.deserialize_seq(TypedTupleVisitor {
+                    fields: info.iter().as_slice(),
+                    registry,
+                })?;
+                let mut tuple = DynamicTuple::default();
+                for field in fields {
+                    tuple.insert_boxed(field);
+                }
+                Ok(Box::new(tuple))
+            }
+            TypeInfo::List(info) => {
+                let mut list = deserializer.deserialize_seq(TypedListVisitor { info, registry })?;
+                list.set_name(info.type_name().to_string());
+                Ok(Box::new(list))
+            }
+            TypeInfo::Map(info) => {
+                let mut map = deserializer.deserialize_map(TypedMapVisitor { info, registry })?;
+                map.set_name(info.type_name().to_string());
+                Ok(Box::new(map))
+            }
+            TypeInfo::Enum(info) => {
+                let mut dynamic_enum =
+                    deserializer.deserialize_map(TypedEnumVisitor { info, registry })?;
+                dynamic_enum.set_name(info.type_name().to_string());
+                Ok(Box::new(dynamic_enum))
+            }
+            TypeInfo::Value(info) => {
+                let reflect_deserialize = self
+                    .registration
+                    .data::<ReflectDeserialize>()
+                    .ok_or_else(|| {
+                        de::Error::custom(format!(
+                            "The TypeRegistration for {} doesn't have DeserializeReflect",
+                            info.type_name()
+                        ))
+                    })?;
+                reflect_deserialize.deserialize(deserializer)
+            }
+        }
+    }
+}
+
+fn get_registration<'a, E: de::Error>(
+    type_id: TypeId,
+    type_name: &str,
+    registry: &'a TypeRegistry,
+) -> Result<&'a TypeRegistration, E> {
+    registry
+        .get(type_id)
+        .ok_or_else(|| de::Error::custom(format!("No registration found for {}", type_name)))
+}
+
+struct TypedStructVisitor<'a> {
+    info: &'a StructInfo,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for TypedStructVisitor<'a> {
+    type Value = DynamicStruct;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "struct {}", self.info.type_name())
+    }
+
+    fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        visit_named_fields(map, |name| self.info.field(name), self.registry)
+    }
+}
+
+fn visit_named_fields<'a, 'de, V, F>(
+    mut map: V,
+    get_field: F,
+    registry: &TypeRegistry,
+) -> Result<DynamicStruct, V::Error>
+where
+    V: MapAccess<'de>,
+    F: Fn(&str) -> Option<&'a NamedField>,
+{
+    let mut dynamic_struct = DynamicStruct::default();
+    while let Some(key) = map.next_key::<String>()? {
+        let field = get_field(&key).ok_or_else(|| de::Error::unknown_field(&key, &[]))?;
+        let registration = get_registration(field.type_id(), field.type_name(), registry)?;
+        let value = map.next_value_seed(TypedReflectDeserializer::new(registration, registry))?;
+        dynamic_struct.insert_boxed(&key, value);
+    }
+
+    Ok(dynamic_struct)
+}
+
+struct TypedTupleVisitor<'a> {
+    fields: &'a [UnnamedField],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for TypedTupleVisitor<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "tuple with {} fields", self.fields.len())
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let mut values = Vec::with_capacity(self.fields.len());
+        for field in self.fields.iter() {
+            let registration = get_registration(field.type_id(), field.type_name(), self.registry)?;
+            let value = seq
+                .next_element_seed(TypedReflectDeserializer::new(registration, self.registry))?
+                .ok_or_else(|| de::Error::invalid_length(values.len(), &self))?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+struct TypedListVisitor<'a> {
+    info: &'a ListInfo,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for TypedListVisitor<'a> {
+    type Value = DynamicList;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "list of {}", self.info.item_type_name())
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let registration = get_registration(
+            self.info.item_type_id(),
+            self.info.item_type_name(),
+            self.registry,
+        )?;
+        let mut list = DynamicList::default();
+        while let Some(value) =
+            seq.next_element_seed(TypedReflectDeserializer::new(registration, self.registry))?
+        {
+            list.push_box(value);
+        }
+        Ok(list)
+    }
+}
+
+struct TypedMapVisitor<'a> {
+    info: &'a MapInfo,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for TypedMapVisitor<'a> {
+    type Value = DynamicMap;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            formatter,
+            "map of {} to {}",
+            self.info.key_type_name(),
+            self.info.value_type_name()
+        )
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let key_registration = get_registration(
+            self.info.key_type_id(),
+            self.info.key_type_name(),
+            self.registry,
+        )?;
+        let value_registration = get_registration(
+            self.info.value_type_id(),
+            self.info.value_type_name(),
+            self.registry,
+        )?;
+        let mut dynamic_map = DynamicMap::default();
+        while let Some(key) = map.next_key_seed(TypedReflectDeserializer::new(
+            key_registration,
+            self.registry,
+        ))? {
+            let value = map.next_value_seed(TypedReflectDeserializer::new(
+                value_registration,
+                self.registry,
+            ))?;
+            dynamic_map.insert_boxed(key, value);
+        }
+        Ok(dynamic_map)
+    }
+}
+
+struct TypedEnumVisitor<'a> {
+    info: &'a EnumInfo,
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> Visitor<'de> for TypedEnumVisitor<'a> {
+    type Value = DynamicEnum;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "enum {}", self.info.type_name())
+    }
+
+    fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        // the variant has to come first, because the shape of the fields depends on it
+        match map.next_key::<String>()? {
+            Some(key) if key == type_fields::VARIANT => {}
+            _ => return Err(de::Error::missing_field(type_fields::VARIANT)),
+        }
+        let variant_name = map.next_value::<String>()?;
+        let variant_index = self.info.index_of(&variant_name).ok_or_else(|| {
+            de::Error::custom(format!(
+                "{} does not have a variant named {}",
+                self.info.type_name(),
+                variant_name
+            ))
+        })?;
+        let variant_info = self.info.variant_at(variant_index).unwrap();
+
+        let variant = match variant_info.fields() {
+            VariantFields::Struct(fields) => {
+                match map.next_key::<String>()? {
+                    Some(key) if key == type_fields::STRUCT => {}
+                    _ => return Err(de::Error::missing_field(type_fields::STRUCT)),
+                }
+                DynamicVariant::Struct(map.next_value_seed(TypedVariantStructDeserializer {
+                    fields,
+                    registry: self.registry,
+                })?)
+            }
+            VariantFields::Tuple(fields) => {
+                match map.next_key::<String>()? {
+                    Some(key) if key == type_fields::TUPLE => {}
+                    _ => return Err(de::Error::missing_field(type_fields::TUPLE)),
+                }
+                let values = map.next_value_seed(TypedTupleDeserializer {
+                    fields,
+                    registry: self.registry,
+                })?;
+                let mut tuple = DynamicTuple::default();
+                for value in values {
+                    tuple.insert_boxed(value);
+                }
+                DynamicVariant::Tuple(tuple)
+            }
+            VariantFields::Unit => DynamicVariant::Unit,
+        };
+        if let Some(key) = map.next_key::<String>()? {
+            return Err(de::Error::unknown_field(&key, &[]));
+        }
+
+        let mut dynamic_enum = DynamicEnum::default();
+        dynamic_enum.set_variant(&variant_name, variant);
+        dynamic_enum.set_variant_index(variant_index);
+        Ok(dynamic_enum)
+    }
+}
+
+struct TypedVariantStructDeserializer<'a> {
+    fields: &'a [NamedField],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for TypedVariantStructDeserializer<'a> {
+    type Value = DynamicStruct;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de> Visitor<'de> for TypedVariantStructDeserializer<'a> {
+    type Value = DynamicStruct;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("struct variant")
+    }
+
+    fn visit_map<V>(self, map: V) -> Result<Self::Value, V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let fields = self.fields;
+        visit_named_fields(
+            map,
+            |name| fields.iter().find(|field| field.name() == name),
+            self.registry,
+        )
+    }
+}
+
+struct TypedTupleDeserializer<'a> {
+    fields: &'a [UnnamedField],
+    registry: &'a TypeRegistry,
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for TypedTupleDeserializer<'a> {
+    type Value = Vec<Box<dyn Reflect>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(TypedTupleVisitor {
+            fields: self.fields,
+            registry: self.registry,
+        })
+    }
+}
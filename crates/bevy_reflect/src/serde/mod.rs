@@ -12,4 +12,6 @@ pub(crate) mod type_fields {
     pub const TUPLE: &str = "tuple";
     pub const LIST: &str = "list";
     pub const VALUE: &str = "value";
+    pub const ENUM: &str = "enum";
+    pub const VARIANT: &str = "variant";
 }
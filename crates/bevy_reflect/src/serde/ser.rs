@@ -1,5 +1,6 @@
 use crate::{
-    serde::type_fields, List, Map, Reflect, ReflectRef, Struct, Tuple, TupleStruct, TypeRegistry,
+    serde::type_fields, Enum, List, Map, Reflect, ReflectRef, Struct, Tuple, TupleStruct,
+    TypeRegistry, VariantType,
 };
 use serde::{
     ser::{SerializeMap, SerializeSeq},
@@ -72,6 +73,11 @@ impl<'a> Serialize for ReflectSerializer<'a> {
                 registry: self.registry,
             }
             .serialize(serializer),
+            ReflectRef::Enum(value) => EnumSerializer {
+                enum_value: value,
+                registry: self.registry,
+            }
+            .serialize(serializer),
             ReflectRef::Value(value) => ReflectValueSerializer {
                 registry: self.registry,
                 value,
@@ -273,6 +279,87 @@ impl<'a> Serialize for MapValueSerializer<'a> {
     }
 }
 
+pub struct EnumSerializer<'a> {
+    pub enum_value: &'a dyn Enum,
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for EnumSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_map(Some(2))?;
+        state.serialize_entry(type_fields::TYPE, self.enum_value.type_name())?;
+        state.serialize_entry(
+            type_fields::ENUM,
+            &EnumValueSerializer {
+                enum_value: self.enum_value,
+                registry: self.registry,
+            },
+        )?;
+        state.end()
+    }
+}
+
+pub struct EnumValueSerializer<'a> {
+    pub enum_value: &'a dyn Enum,
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for EnumValueSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant_type = self.enum_value.variant_type();
+        let len = if variant_type == VariantType::Unit {
+            1
+        } else {
+            2
+        };
+        let mut state = serializer.serialize_map(Some(len))?;
+        state.serialize_entry(type_fields::VARIANT, self.enum_value.variant_name())?;
+        let fields = VariantFieldsSerializer {
+            enum_value: self.enum_value,
+            registry: self.registry,
+        };
+        match variant_type {
+            VariantType::Struct => state.serialize_entry(type_fields::STRUCT, &fields)?,
+            VariantType::Tuple => state.serialize_entry(type_fields::TUPLE, &fields)?,
+            VariantType::Unit => {}
+        }
+        state.end()
+    }
+}
+
+pub struct VariantFieldsSerializer<'a> {
+    pub enum_value: &'a dyn Enum,
+    pub registry: &'a TypeRegistry,
+}
+
+impl<'a> Serialize for VariantFieldsSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if self.enum_value.variant_type() == VariantType::Struct {
+            let mut state = serializer.serialize_map(Some(self.enum_value.field_len()))?;
+            for (index, value) in self.enum_value.iter_fields().enumerate() {
+                let key = self.enum_value.name_at(index).unwrap();
+                state.serialize_entry(key, &ReflectSerializer::new(value, self.registry))?;
+            }
+            state.end()
+        } else {
+            let mut state = serializer.serialize_seq(Some(self.enum_value.field_len()))?;
+            for value in self.enum_value.iter_fields() {
+                state.serialize_element(&ReflectSerializer::new(value, self.registry))?;
+            }
+            state.end()
+        }
+    }
+}
+
 pub struct ListSerializer<'a> {
     pub list: &'a dyn List,
     pub registry: &'a TypeRegistry,
@@ -313,3 +400,111 @@ impl<'a> Serialize for ListValueSerializer<'a> {
         state.end()
     }
 }
+
+/// Serializes a reflected value without the `"type"` tags written by [`ReflectSerializer`], so
+/// it can only be read back with a
+/// [`TypedReflectDeserializer`](crate::serde::TypedReflectDeserializer) that knows its type.
+pub struct TypedReflectSerializer<'a> {
+    pub value: &'a dyn Reflect,
+}
+
+impl<'a> TypedReflectSerializer<'a> {
+    pub fn new(value: &'a dyn Reflect) -> Self {
+        TypedReflectSerializer { value }
+    }
+}
+
+impl<'a> Serialize for TypedReflectSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                let mut state = serializer.serialize_map(Some(value.field_len()))?;
+                for (index, field) in value.iter_fields().enumerate() {
+                    let key = value.name_at(index).unwrap();
+                    state.serialize_entry(key, &TypedReflectSerializer::new(field))?;
+                }
+                state.end()
+            }
+            ReflectRef::TupleStruct(value) => {
+                let mut state = serializer.serialize_seq(Some(value.field_len()))?;
+                for field in value.iter_fields() {
+                    state.serialize_element(&TypedReflectSerializer::new(field))?;
+                }
+                state.end()
+            }
+            ReflectRef::Tuple(value) => {
+                let mut state = serializer.serialize_seq(Some(value.field_len()))?;
+                for field in value.iter_fields() {
+                    state.serialize_element(&TypedReflectSerializer::new(field))?;
+                }
+                state.end()
+            }
+            ReflectRef::List(value) => {
+                let mut state = serializer.serialize_seq(Some(value.len()))?;
+                for item in value.iter() {
+                    state.serialize_element(&TypedReflectSerializer::new(item))?;
+                }
+                state.end()
+            }
+            ReflectRef::Map(value) => {
+                let mut state = serializer.serialize_map(Some(value.len()))?;
+                for (key, value) in value.iter() {
+                    state.serialize_entry(
+                        &TypedReflectSerializer::new(key),
+                        &TypedReflectSerializer::new(value),
+                    )?;
+                }
+                state.end()
+            }
+            ReflectRef::Enum(value) => {
+                let variant_type = value.variant_type();
+                let len = if variant_type == VariantType::Unit {
+                    1
+                } else {
+                    2
+                };
+                let mut state = serializer.serialize_map(Some(len))?;
+                state.serialize_entry(type_fields::VARIANT, value.variant_name())?;
+                let fields = TypedVariantFieldsSerializer { enum_value: value };
+                match variant_type {
+                    VariantType::Struct => state.serialize_entry(type_fields::STRUCT, &fields)?,
+                    VariantType::Tuple => state.serialize_entry(type_fields::TUPLE, &fields)?,
+                    VariantType::Unit => {}
+                }
+                state.end()
+            }
+            ReflectRef::Value(value) => get_serializable::<S::Error>(value)?
+                .borrow()
+                .serialize(serializer),
+        }
+    }
+}
+
+struct TypedVariantFieldsSerializer<'a> {
+    enum_value: &'a dyn Enum,
+}
+
+impl<'a> Serialize for TypedVariantFieldsSerializer<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if self.enum_value.variant_type() == VariantType::Struct {
+            let mut state = serializer.serialize_map(Some(self.enum_value.field_len()))?;
+            for (index, value) in self.enum_value.iter_fields().enumerate() {
+                let key = self.enum_value.name_at(index).unwrap();
+                state.serialize_entry(key, &TypedReflectSerializer::new(value))?;
+            }
+            state.end()
+        } else {
+            let mut state = serializer.serialize_seq(Some(self.enum_value.field_len()))?;
+            for value in self.enum_value.iter_fields() {
+                state.serialize_element(&TypedReflectSerializer::new(value))?;
+            }
+            state.end()
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use std::any::Any;
 
-use crate::{serde::Serializable, Reflect, ReflectMut, ReflectRef};
+use crate::{
+    serde::Serializable, GenericTypeInfoCell, GetTypeRegistration, Reflect, ReflectMut, ReflectRef,
+    TupleInfo, TypeInfo, TypeRegistration, Typed, UnnamedField,
+};
 
 pub trait Tuple: Reflect {
     fn field(&self, index: usize) -> Option<&dyn Reflect>;
@@ -329,6 +332,22 @@ macro_rules! impl_reflect_tuple {
                 None
             }
         }
+
+        impl<$($name: Reflect),*> Typed for ($($name,)*) {
+            fn type_info() -> &'static TypeInfo {
+                static CELL: GenericTypeInfoCell = GenericTypeInfoCell::new();
+                CELL.get_or_insert::<Self, _>(|| {
+                    let fields = [$(UnnamedField::new::<$name>($index),)*];
+                    TypeInfo::Tuple(TupleInfo::new::<Self>(&fields))
+                })
+            }
+        }
+
+        impl<$($name: Reflect),*> GetTypeRegistration for ($($name,)*) {
+            fn get_type_registration() -> TypeRegistration {
+                TypeRegistration::of::<($($name,)*)>()
+            }
+        }
     }
 }
 
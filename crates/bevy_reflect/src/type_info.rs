@@ -0,0 +1,579 @@
+use crate::{Reflect, VariantType};
+use bevy_utils::HashMap;
+use once_cell::sync::OnceCell;
+use parking_lot::RwLock;
+use std::{
+    any::{Any, TypeId},
+    collections::hash_map::Entry,
+};
+
+/// A reflected type whose shape can be described without an instance of it.
+///
+/// This is implemented by `#[derive(Reflect)]` and `impl_reflect_value!`, and is stored in the
+/// [`TypeRegistration`](crate::TypeRegistration) of every registered type.
+pub trait Typed: Reflect {
+    fn type_info() -> &'static TypeInfo;
+}
+
+/// The static shape of a reflected type, matching the [`ReflectRef`](crate::ReflectRef) of its
+/// values.
+#[derive(Debug)]
+pub enum TypeInfo {
+    Struct(StructInfo),
+    TupleStruct(TupleStructInfo),
+    Tuple(TupleInfo),
+    List(ListInfo),
+    Map(MapInfo),
+    Enum(EnumInfo),
+    Value(ValueInfo),
+}
+
+impl TypeInfo {
+    pub fn type_id(&self) -> TypeId {
+        match self {
+            TypeInfo::Struct(info) => info.type_id,
+            TypeInfo::TupleStruct(info) => info.type_id,
+            TypeInfo::Tuple(info) => info.type_id,
+            TypeInfo::List(info) => info.type_id,
+            TypeInfo::Map(info) => info.type_id,
+            TypeInfo::Enum(info) => info.type_id,
+            TypeInfo::Value(info) => info.type_id,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            TypeInfo::Struct(info) => info.type_name,
+            TypeInfo::TupleStruct(info) => info.type_name,
+            TypeInfo::Tuple(info) => info.type_name,
+            TypeInfo::List(info) => info.type_name,
+            TypeInfo::Map(info) => info.type_name,
+            TypeInfo::Enum(info) => info.type_name,
+            TypeInfo::Value(info) => info.type_name,
+        }
+    }
+
+    /// The doc comment of the type, if it has one.
+    pub fn docs(&self) -> Option<&'static str> {
+        match self {
+            TypeInfo::Struct(info) => info.docs,
+            TypeInfo::TupleStruct(info) => info.docs,
+            TypeInfo::Enum(info) => info.docs,
+            TypeInfo::Value(info) => info.docs,
+            TypeInfo::Tuple(_) | TypeInfo::List(_) | TypeInfo::Map(_) => None,
+        }
+    }
+}
+
+/// A field of a struct or of a struct variant.
+#[derive(Debug, Clone)]
+pub struct NamedField {
+    name: &'static str,
+    type_name: &'static str,
+    type_id: TypeId,
+    docs: Option<&'static str>,
+}
+
+impl NamedField {
+    pub fn new<T: Any>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+/// A field of a tuple, a tuple struct or a tuple variant.
+#[derive(Debug, Clone)]
+pub struct UnnamedField {
+    index: usize,
+    type_name: &'static str,
+    type_id: TypeId,
+    docs: Option<&'static str>,
+}
+
+impl UnnamedField {
+    pub fn new<T: Any>(index: usize) -> Self {
+        Self {
+            index,
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+#[derive(Debug)]
+pub struct StructInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    fields: Vec<NamedField>,
+    field_indices: HashMap<&'static str, usize>,
+    docs: Option<&'static str>,
+}
+
+impl StructInfo {
+    pub fn new<T: Reflect>(fields: &[NamedField]) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            fields: fields.to_vec(),
+            field_indices: named_field_indices(fields),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn field(&self, name: &str) -> Option<&NamedField> {
+        self.index_of(name).map(|index| &self.fields[index])
+    }
+
+    pub fn field_at(&self, index: usize) -> Option<&NamedField> {
+        self.fields.get(index)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.field_indices.get(name).copied()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, NamedField> {
+        self.fields.iter()
+    }
+
+    pub fn field_len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+#[derive(Debug)]
+pub struct TupleStructInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    fields: Vec<UnnamedField>,
+    docs: Option<&'static str>,
+}
+
+impl TupleStructInfo {
+    pub fn new<T: Reflect>(fields: &[UnnamedField]) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            fields: fields.to_vec(),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn field_at(&self, index: usize) -> Option<&UnnamedField> {
+        self.fields.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, UnnamedField> {
+        self.fields.iter()
+    }
+
+    pub fn field_len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+#[derive(Debug)]
+pub struct TupleInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    fields: Vec<UnnamedField>,
+}
+
+impl TupleInfo {
+    pub fn new<T: Reflect>(fields: &[UnnamedField]) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            fields: fields.to_vec(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn field_at(&self, index: usize) -> Option<&UnnamedField> {
+        self.fields.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, UnnamedField> {
+        self.fields.iter()
+    }
+
+    pub fn field_len(&self) -> usize {
+        self.fields.len()
+    }
+}
+
+#[derive(Debug)]
+pub struct ListInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    item_type_name: &'static str,
+    item_type_id: TypeId,
+}
+
+impl ListInfo {
+    pub fn new<T: Reflect, TItem: Any>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            item_type_name: std::any::type_name::<TItem>(),
+            item_type_id: TypeId::of::<TItem>(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn item_type_name(&self) -> &'static str {
+        self.item_type_name
+    }
+
+    pub fn item_type_id(&self) -> TypeId {
+        self.item_type_id
+    }
+}
+
+#[derive(Debug)]
+pub struct MapInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    key_type_name: &'static str,
+    key_type_id: TypeId,
+    value_type_name: &'static str,
+    value_type_id: TypeId,
+}
+
+impl MapInfo {
+    pub fn new<T: Reflect, TKey: Any, TValue: Any>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            key_type_name: std::any::type_name::<TKey>(),
+            key_type_id: TypeId::of::<TKey>(),
+            value_type_name: std::any::type_name::<TValue>(),
+            value_type_id: TypeId::of::<TValue>(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn key_type_name(&self) -> &'static str {
+        self.key_type_name
+    }
+
+    pub fn key_type_id(&self) -> TypeId {
+        self.key_type_id
+    }
+
+    pub fn value_type_name(&self) -> &'static str {
+        self.value_type_name
+    }
+
+    pub fn value_type_id(&self) -> TypeId {
+        self.value_type_id
+    }
+}
+
+/// The fields of an enum variant.
+#[derive(Debug, Clone)]
+pub enum VariantFields {
+    Struct(Vec<NamedField>),
+    Tuple(Vec<UnnamedField>),
+    Unit,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantInfo {
+    name: &'static str,
+    fields: VariantFields,
+    docs: Option<&'static str>,
+}
+
+impl VariantInfo {
+    pub fn new(name: &'static str, fields: VariantFields) -> Self {
+        Self {
+            name,
+            fields,
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn variant_type(&self) -> VariantType {
+        match self.fields {
+            VariantFields::Struct(_) => VariantType::Struct,
+            VariantFields::Tuple(_) => VariantType::Tuple,
+            VariantFields::Unit => VariantType::Unit,
+        }
+    }
+
+    pub fn fields(&self) -> &VariantFields {
+        &self.fields
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+#[derive(Debug)]
+pub struct EnumInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    variants: Vec<VariantInfo>,
+    docs: Option<&'static str>,
+}
+
+impl EnumInfo {
+    pub fn new<T: Reflect>(variants: &[VariantInfo]) -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            variants: variants.to_vec(),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn variant(&self, name: &str) -> Option<&VariantInfo> {
+        self.variants.iter().find(|variant| variant.name == name)
+    }
+
+    pub fn variant_at(&self, index: usize) -> Option<&VariantInfo> {
+        self.variants.get(index)
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.variants
+            .iter()
+            .position(|variant| variant.name == name)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, VariantInfo> {
+        self.variants.iter()
+    }
+
+    pub fn variant_len(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+/// A type that is reflected as a single opaque value, like `u32`, `String` or a
+/// `#[reflect_value]` type.
+#[derive(Debug)]
+pub struct ValueInfo {
+    type_name: &'static str,
+    type_id: TypeId,
+    docs: Option<&'static str>,
+}
+
+impl ValueInfo {
+    pub fn new<T: Reflect>() -> Self {
+        Self {
+            type_name: std::any::type_name::<T>(),
+            type_id: TypeId::of::<T>(),
+            docs: None,
+        }
+    }
+
+    pub fn with_docs(self, docs: Option<&'static str>) -> Self {
+        Self { docs, ..self }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn docs(&self) -> Option<&'static str> {
+        self.docs
+    }
+}
+
+fn named_field_indices(fields: &[NamedField]) -> HashMap<&'static str, usize> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| (field.name, index))
+        .collect()
+}
+
+/// Lazily initialized storage for the [`TypeInfo`] of a non-generic type, used to implement
+/// [`Typed`].
+pub struct NonGenericTypeInfoCell(OnceCell<TypeInfo>);
+
+impl Default for NonGenericTypeInfoCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonGenericTypeInfoCell {
+    pub const fn new() -> Self {
+        Self(OnceCell::new())
+    }
+
+    pub fn get_or_init<F>(&self, f: F) -> &TypeInfo
+    where
+        F: FnOnce() -> TypeInfo,
+    {
+        self.0.get_or_init(f)
+    }
+}
+
+/// Lazily initialized storage for the [`TypeInfo`] of a generic type, used to implement
+/// [`Typed`].
+///
+/// A `static` inside a generic function is shared by every instantiation of that function, so
+/// this stores one [`TypeInfo`] per concrete type.
+pub struct GenericTypeInfoCell(OnceCell<RwLock<HashMap<TypeId, &'static TypeInfo>>>);
+
+impl Default for GenericTypeInfoCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GenericTypeInfoCell {
+    pub const fn new() -> Self {
+        Self(OnceCell::new())
+    }
+
+    pub fn get_or_insert<T, F>(&self, f: F) -> &'static TypeInfo
+    where
+        T: Any + ?Sized,
+        F: FnOnce() -> TypeInfo,
+    {
+        let type_id = TypeId::of::<T>();
+        let map = self.0.get_or_init(Default::default);
+        if let Some(info) = map.read().get(&type_id) {
+            return info;
+        }
+
+        // the info is leaked so it can be handed out without holding the lock. Each type is only
+        // inserted once, so this is bounded by the number of reflected types
+        match map.write().entry(type_id) {
+            Entry::Occupied(entry) => entry.get(),
+            Entry::Vacant(entry) => entry.insert(Box::leak(Box::new(f()))),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::Reflect;
+use crate::{
+    IntoReflectFunction, IntoReflectMethod, Reflect, ReflectFunction, ReflectMethod, TypeInfo,
+    Typed,
+};
 use bevy_utils::{HashMap, HashSet};
 use downcast_rs::{impl_downcast, Downcast};
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
@@ -11,6 +14,7 @@ pub struct TypeRegistry {
     short_name_to_id: HashMap<String, TypeId>,
     full_name_to_id: HashMap<String, TypeId>,
     ambiguous_names: HashSet<String>,
+    functions: HashMap<String, ReflectFunction>,
 }
 
 // TODO:  remove this wrapper once we migrate to Atelier Assets and the Scene AssetLoader doesn't
@@ -91,6 +95,47 @@ impl TypeRegistry {
     pub fn iter(&self) -> impl Iterator<Item = &TypeRegistration> {
         self.registrations.values()
     }
+
+    /// Registers `method` under `name` on the registration of `T`, replacing any method with the
+    /// same name. See [`TypeRegistration::method`].
+    ///
+    /// # Panics
+    /// Panics if `T` has not been registered.
+    pub fn register_method<T: Reflect, Params>(
+        &mut self,
+        name: &str,
+        method: impl IntoReflectMethod<T, Params>,
+    ) {
+        self.get_mut(TypeId::of::<T>())
+            .unwrap_or_else(|| {
+                panic!(
+                    "Attempted to register method {} on {}, which is not registered.",
+                    name,
+                    std::any::type_name::<T>()
+                )
+            })
+            .add_method(name, method.into_reflect_method());
+    }
+
+    /// Registers a free function under `name`, replacing any function with the same name.
+    pub fn register_function<Params>(
+        &mut self,
+        name: &str,
+        function: impl IntoReflectFunction<Params>,
+    ) {
+        self.functions
+            .insert(name.to_string(), function.into_reflect_function());
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<&ReflectFunction> {
+        self.functions.get(name)
+    }
+
+    pub fn iter_functions(&self) -> impl Iterator<Item = (&str, &ReflectFunction)> {
+        self.functions
+            .iter()
+            .map(|(name, function)| (name.as_str(), function))
+    }
 }
 
 impl TypeRegistryArc {
@@ -107,7 +152,9 @@ pub struct TypeRegistration {
     type_id: TypeId,
     short_name: String,
     name: &'static str,
+    type_info: &'static TypeInfo,
     data: HashMap<TypeId, Box<dyn TypeData>>,
+    methods: HashMap<String, ReflectMethod>,
 }
 
 impl TypeRegistration {
@@ -132,17 +179,51 @@ impl TypeRegistration {
         self.data.insert(TypeId::of::<T>(), Box::new(data));
     }
 
-    pub fn of<T: Reflect>() -> Self {
+    pub fn method(&self, name: &str) -> Option<&ReflectMethod> {
+        self.methods.get(name)
+    }
+
+    pub fn iter_methods(&self) -> impl Iterator<Item = (&str, &ReflectMethod)> {
+        self.methods
+            .iter()
+            .map(|(name, method)| (name.as_str(), method))
+    }
+
+    /// Adds `method` under `name`, replacing any method with the same name.
+    ///
+    /// # Panics
+    /// Panics if the receiver of `method` is not the registered type.
+    pub fn add_method(&mut self, name: &str, method: ReflectMethod) {
+        assert_eq!(
+            method.receiver_type_id(),
+            self.type_id,
+            "Attempted to add method {} of {} to the registration of {}.",
+            name,
+            method.receiver_type_name(),
+            self.name
+        );
+        self.methods.insert(name.to_string(), method);
+    }
+
+    pub fn of<T: Typed>() -> Self {
         let ty = TypeId::of::<T>();
         let type_name = std::any::type_name::<T>();
         Self {
             type_id: ty,
             data: HashMap::default(),
+            methods: HashMap::default(),
             name: type_name,
             short_name: Self::get_short_name(type_name),
+            type_info: T::type_info(),
         }
     }
 
+    /// The static shape of the registered type, see [`TypeInfo`].
+    #[inline]
+    pub fn type_info(&self) -> &'static TypeInfo {
+        self.type_info
+    }
+
     pub fn short_name(&self) -> &str {
         &self.short_name
     }
@@ -198,9 +279,11 @@ impl Clone for TypeRegistration {
 
         TypeRegistration {
             data,
+            methods: self.methods.clone(),
             name: self.name,
             short_name: self.short_name.clone(),
             type_id: self.type_id,
+            type_info: self.type_info,
         }
     }
 }
@@ -1,5 +1,6 @@
 use bevy_ecs::{
     entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    observer::Traversal,
     reflect::{ReflectComponent, ReflectMapEntities},
     world::{FromWorld, World},
 };
@@ -27,6 +28,14 @@ impl MapEntities for Parent {
     }
 }
 
+/// Events triggered with [`World::trigger_propagated::<Parent, _>`](World::trigger_propagated)
+/// bubble up the hierarchy.
+impl Traversal for Parent {
+    fn traverse(&self) -> Option<Entity> {
+        Some(self.0)
+    }
+}
+
 impl Deref for Parent {
     type Target = Entity;
 
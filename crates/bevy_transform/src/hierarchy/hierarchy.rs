@@ -1,6 +1,7 @@
 use crate::components::{Children, Parent};
 use bevy_ecs::{
-    entity::Entity,
+    entity::{Disabled, Entity},
+    invariant::Invariants,
     system::{Command, EntityCommands},
     world::World,
 };
@@ -55,15 +56,93 @@ impl<'a, 'b> DespawnRecursiveExt for EntityCommands<'a, 'b> {
     }
 }
 
+#[derive(Debug)]
+pub struct DisableRecursive {
+    entity: Entity,
+    disabled: bool,
+}
+
+/// Disables or enables `entity` and all of its descendants by inserting or removing [`Disabled`].
+pub fn set_disabled_recursive(world: &mut World, entity: Entity, disabled: bool) {
+    if let Some(children) = world.get::<Children>(entity) {
+        for child in children.0.clone() {
+            set_disabled_recursive(world, child, disabled);
+        }
+    }
+
+    if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+        if disabled {
+            entity_mut.insert(Disabled);
+        } else {
+            entity_mut.remove::<Disabled>();
+        }
+    } else {
+        debug!("Failed to set disabled state of entity {:?}", entity);
+    }
+}
+
+impl Command for DisableRecursive {
+    fn write(self: Box<Self>, world: &mut World) {
+        set_disabled_recursive(world, self.entity, self.disabled);
+    }
+}
+
+pub trait DisableRecursiveExt {
+    /// Disables the provided entity and its children.
+    fn disable_recursive(&mut self);
+
+    /// Enables the provided entity and its children.
+    fn enable_recursive(&mut self);
+}
+
+impl<'a, 'b> DisableRecursiveExt for EntityCommands<'a, 'b> {
+    /// Disables the provided entity and its children.
+    fn disable_recursive(&mut self) {
+        let entity = self.id();
+        self.commands().add(DisableRecursive {
+            entity,
+            disabled: true,
+        });
+    }
+
+    /// Enables the provided entity and its children.
+    fn enable_recursive(&mut self) {
+        let entity = self.id();
+        self.commands().add(DisableRecursive {
+            entity,
+            disabled: false,
+        });
+    }
+}
+
+/// Adds the invariants of the hierarchy to `invariants`: [`Parent`] and [`Children`] must refer to
+/// living entities, and [`Children`] must not list an entity twice.
+pub fn add_hierarchy_invariants(invariants: &mut Invariants) -> &mut Invariants {
+    invariants
+        .entities_alive(|parent: &Parent| vec![parent.0])
+        .entities_alive(|children: &Children| children.to_vec())
+        .holds("children are unique", |_, children: &Children| {
+            children
+                .iter()
+                .enumerate()
+                .all(|(index, child)| !children[..index].contains(child))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use bevy_ecs::{
+        entity::Disabled,
+        invariant::Invariants,
         system::{CommandQueue, Commands},
         world::World,
     };
 
-    use super::DespawnRecursiveExt;
-    use crate::{components::Children, hierarchy::BuildChildren};
+    use super::{add_hierarchy_invariants, DespawnRecursiveExt, DisableRecursiveExt};
+    use crate::{
+        components::{Children, Parent},
+        hierarchy::BuildChildren,
+    };
 
     #[test]
     fn despawn_recursive() {
@@ -140,4 +219,63 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn disable_recursive() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::default();
+        let parent;
+        let child;
+        let grandchild;
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            parent = commands.spawn_bundle((0u32,)).id();
+            child = commands.spawn_bundle((1u32,)).id();
+            grandchild = commands.spawn_bundle((2u32,)).id();
+            commands.entity(parent).push_children(&[child]);
+            commands.entity(child).push_children(&[grandchild]);
+            commands.spawn_bundle((3u32,));
+        }
+        queue.apply(&mut world);
+
+        let mut query = world.query::<&u32>();
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(child).disable_recursive();
+        }
+        queue.apply(&mut world);
+        let mut results = query.iter(&world).cloned().collect::<Vec<_>>();
+        results.sort_unstable();
+        assert_eq!(results, vec![0, 3]);
+        assert!(world.get::<Disabled>(grandchild).is_some());
+
+        {
+            let mut commands = Commands::new(&mut queue, &world);
+            commands.entity(parent).enable_recursive();
+        }
+        queue.apply(&mut world);
+        assert_eq!(query.iter(&world).count(), 4);
+    }
+
+    #[test]
+    fn hierarchy_invariants() {
+        let mut world = World::default();
+        let mut invariants = Invariants::default();
+        add_hierarchy_invariants(&mut invariants);
+
+        let parent = world.spawn().id();
+        let child = world.spawn().insert(Parent(parent)).id();
+        world.entity_mut(parent).insert(Children::with(&[child]));
+        assert!(invariants.check(&world).is_empty());
+
+        world
+            .entity_mut(parent)
+            .insert(Children::with(&[child, child]));
+        world.despawn(child);
+        let violations = invariants.check(&world);
+        assert_eq!(violations.len(), 3);
+        assert!(violations
+            .iter()
+            .all(|violation| violation.entity == parent));
+    }
 }
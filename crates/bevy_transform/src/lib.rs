@@ -29,7 +29,15 @@ impl Plugin for TransformPlugin {
             .register_type::<Parent>()
             .register_type::<PreviousParent>()
             .register_type::<Transform>()
+            .register_method("look_at", Transform::look_at)
+            .register_method("rotate", Transform::rotate)
+            .register_method(
+                "apply_non_uniform_scale",
+                Transform::apply_non_uniform_scale,
+            )
+            .register_method("mul_vec3", Transform::mul_vec3)
             .register_type::<GlobalTransform>()
+            .register_required_components::<Transform, GlobalTransform>()
             // add transform systems to startup so the first update is "correct"
             .add_startup_system_to_stage(
                 StartupStage::PostStartup,
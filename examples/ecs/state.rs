@@ -7,10 +7,10 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .init_resource::<ButtonMaterials>()
         .add_state(AppState::Menu)
-        .add_system_set(SystemSet::on_enter(AppState::Menu).with_system(setup_menu.system()))
+        .add_system_on_enter(AppState::Menu, setup_menu.system())
         .add_system_set(SystemSet::on_update(AppState::Menu).with_system(menu.system()))
-        .add_system_set(SystemSet::on_exit(AppState::Menu).with_system(cleanup_menu.system()))
-        .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(setup_game.system()))
+        .add_system_on_exit(AppState::Menu, cleanup_menu.system())
+        .add_system_on_enter(AppState::InGame, setup_game.system())
         .add_system_set(
             SystemSet::on_update(AppState::InGame)
                 .with_system(movement.system())
@@ -70,7 +70,7 @@ fn setup_menu(
 }
 
 fn menu(
-    mut state: ResMut<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
     button_materials: Res<ButtonMaterials>,
     mut interaction_query: Query<
         (&Interaction, &mut Handle<ColorMaterial>),
@@ -81,7 +81,7 @@ fn menu(
         match *interaction {
             Interaction::Clicked => {
                 *material = button_materials.pressed.clone();
-                state.set(AppState::InGame).unwrap();
+                next_state.set(AppState::InGame);
             }
             Interaction::Hovered => {
                 *material = button_materials.hovered.clone();
@@ -19,7 +19,7 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_state(GameState::Playing)
         .add_startup_system(setup_cameras.system())
-        .add_system_set(SystemSet::on_enter(GameState::Playing).with_system(setup.system()))
+        .add_system_on_enter(GameState::Playing, setup.system())
         .add_system_set(
             SystemSet::on_update(GameState::Playing)
                 .with_system(move_player.system())
@@ -27,14 +27,12 @@ fn main() {
                 .with_system(rotate_bonus.system())
                 .with_system(scoreboard_system.system()),
         )
-        .add_system_set(SystemSet::on_exit(GameState::Playing).with_system(teardown.system()))
-        .add_system_set(
-            SystemSet::on_enter(GameState::GameOver).with_system(display_score.system()),
-        )
+        .add_system_on_exit(GameState::Playing, teardown.system())
+        .add_system_on_enter(GameState::GameOver, display_score.system())
         .add_system_set(
             SystemSet::on_update(GameState::GameOver).with_system(gameover_keyboard.system()),
         )
-        .add_system_set(SystemSet::on_exit(GameState::GameOver).with_system(teardown.system()))
+        .add_system_on_exit(GameState::GameOver, teardown.system())
         .add_system_set(
             SystemSet::new()
                 .with_run_criteria(FixedTimestep::step(5.0))
@@ -292,7 +290,8 @@ fn focus_camera(
 
 // despawn the bonus if there is one, then spawn a new one at a random location
 fn spawn_bonus(
-    mut state: ResMut<State<GameState>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
     mut commands: Commands,
     mut game: ResMut<Game>,
 ) {
@@ -304,7 +303,7 @@ fn spawn_bonus(
         commands.entity(entity).despawn_recursive();
         game.bonus.entity = None;
         if game.score <= -5 {
-            state.set(GameState::GameOver).unwrap();
+            next_state.set(GameState::GameOver);
             return;
         }
     }
@@ -356,9 +355,12 @@ fn scoreboard_system(game: Res<Game>, mut query: Query<&mut Text>) {
 }
 
 // restart the game when pressing spacebar
-fn gameover_keyboard(mut state: ResMut<State<GameState>>, keyboard_input: Res<Input<KeyCode>>) {
+fn gameover_keyboard(
+    mut next_state: ResMut<NextState<GameState>>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
     if keyboard_input.just_pressed(KeyCode::Space) {
-        state.set(GameState::Playing).unwrap();
+        next_state.set(GameState::Playing);
     }
 }
 
@@ -39,8 +39,8 @@ pub struct D {
     x: usize,
 }
 
-/// By default, deriving with Reflect assumes the type is a "struct". You can tell reflect to treat
-/// your type as a "value type" by using the `reflect_value` attribute instead of `reflect`. It is
+/// By default, deriving with Reflect assumes the type is a "struct" or an "enum". You can tell
+/// reflect to treat your type as a "value type" by using the `reflect_value` attribute instead of `reflect`. It is
 /// generally a good idea to implement (and reflect) the PartialEq, Serialize, and Deserialize
 /// traits on `reflect_value` types to ensure that these values behave as expected when nested
 /// underneath Reflect-ed structs.
@@ -87,6 +87,10 @@ fn setup() {
         // This exposes "map" operations on your type, such as getting / inserting by key.
         // Map is automatically implemented for relevant core types like HashMap<K, V>
         ReflectRef::Map(_) => {}
+        // `Enum` is a trait automatically implemented for enums that derive Reflect. This trait
+        // allows you to read the active variant and interact with its fields via their names or
+        // indices
+        ReflectRef::Enum(_) => {}
         // `Value` types do not implement any of the other traits above. They are simply a Reflect
         // implementation. Value is implemented for core types like i32, usize, f32, and
         // String.
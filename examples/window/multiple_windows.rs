@@ -35,7 +35,7 @@ enum AppState {
 }
 
 fn setup_window(
-    mut app_state: ResMut<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
     mut create_window_events: EventWriter<CreateWindow>,
 ) {
     let window_id = WindowId::new();
@@ -52,7 +52,7 @@ fn setup_window(
         },
     });
 
-    app_state.set(AppState::Setup).unwrap();
+    next_state.set(AppState::Setup);
 }
 
 fn setup_pipeline(
@@ -62,7 +62,7 @@ fn setup_pipeline(
     mut render_graph: ResMut<RenderGraph>,
     asset_server: Res<AssetServer>,
     msaa: Res<Msaa>,
-    mut app_state: ResMut<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
 ) {
     // get the non-default window id
     let window_id = windows
@@ -207,5 +207,5 @@ fn setup_pipeline(
         ..Default::default()
     });
 
-    app_state.set(AppState::Done).unwrap();
+    next_state.set(AppState::Done);
 }